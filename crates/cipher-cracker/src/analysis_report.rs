@@ -0,0 +1,89 @@
+use cipher_utils::{character_set::CharacterSet, cipher_type::CipherType, frequency, Analyze as _};
+use colored::Colorize as _;
+
+/// A summary of the statistics of a ciphertext, for diagnosing what kind of cipher it is and why a crack may have failed.
+/// This is created by `CipherCracker::analyze()`, and doesn't attempt any decryption.
+#[derive(Debug, Clone)]
+pub struct AnalysisReport {
+    /// The index of coincidence of the ciphertext's letters.
+    pub index_of_coincidence: f64,
+
+    /// The Shannon entropy of the ciphertext's non-whitespace characters, in bits per character.
+    pub entropy: f64,
+
+    /// The set of non-whitespace characters that appear in the ciphertext.
+    pub character_set: CharacterSet,
+
+    /// The most likely type of cipher used to create the ciphertext, if one could be identified.
+    pub cipher_type: Option<CipherType>,
+
+    /// The most likely key length, if the ciphertext appears to be polyalphabetic.
+    pub key_length: Option<usize>,
+
+    /// The frequency of each letter in the ciphertext, case-insensitively, from most to least common.
+    pub letter_frequencies: Vec<(char, f64)>,
+}
+
+/// The largest key length that `AnalysisReport::new()` checks for polyalphabetic ciphers.
+const MAX_KEY_LENGTH: usize = 20;
+
+impl AnalysisReport {
+    /// Analyzes the given ciphertext.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext to analyze.
+    ///
+    /// # Returns
+    /// The report of the ciphertext's statistics.
+    pub fn new(ciphertext: &str) -> Self {
+        let cipher_type = CipherType::best_match(ciphertext);
+
+        // The shortest period whose columns look like English; Multiples of the key length score just as well.
+        let key_length = (cipher_type == Some(CipherType::Vigenere))
+            .then(|| {
+                let profile = ciphertext.period_ioc_profile(MAX_KEY_LENGTH);
+                let best = profile.iter().map(|(_, ioc)| *ioc).fold(0., f64::max);
+                profile.into_iter().find(|(_, ioc)| *ioc >= 0.9 * best).map(|(period, _)| period)
+            })
+            .flatten();
+
+        let letters = ciphertext.chars().filter(|character| character.is_alphabetic()).collect::<String>();
+        let mut letter_frequencies = frequency::of(&letters).into_iter().collect::<Vec<_>>();
+        letter_frequencies.sort_by(|first, other| other.1.total_cmp(&first.1).then(first.0.cmp(&other.0)));
+
+        Self {
+            index_of_coincidence: ciphertext.index_of_coincidence(),
+            entropy: ciphertext.entropy(),
+            character_set: CharacterSet::raw(ciphertext),
+            cipher_type,
+            key_length,
+            letter_frequencies,
+        }
+    }
+}
+
+impl std::fmt::Display for AnalysisReport {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(formatter, "{}", "Ciphertext analysis".bold().green())?;
+        writeln!(formatter, "\t{}: {:.4}", "Index of coincidence".bold(), self.index_of_coincidence)?;
+        writeln!(formatter, "\t{}: {:.4} bits per character", "Entropy".bold(), self.entropy)?;
+        writeln!(formatter, "\t{}: {} unique characters", "Character set".bold(), self.character_set.characters().len())?;
+        writeln!(
+            formatter,
+            "\t{}: {}",
+            "Cipher type".bold(),
+            self.cipher_type
+                .map_or_else(|| "unknown".to_owned(), |cipher_type| format!("{cipher_type:?}"))
+                .cyan()
+                .bold()
+        )?;
+        if let Some(key_length) = self.key_length {
+            writeln!(formatter, "\t{}: {}", "Key length".bold(), key_length.to_string().cyan().bold())?;
+        }
+        writeln!(formatter, "\t{}:", "Letter frequencies".bold())?;
+        for (letter, frequency) in &self.letter_frequencies {
+            writeln!(formatter, "\t\t{letter}: {:.2}%", 100. * frequency)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use cipher_utils::cipher_type::CipherType;
+
+/// A single layer of encryption removed by `CipherCracker::crack_explained()`, along with the evidence that it was
+/// identified by. This is the structured form of the narrative that `CipherCracker::crack()` prints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrackStep {
+    /// The type of cipher that the layer was identified as.
+    pub cipher_type: CipherType,
+
+    /// The index of coincidence of the text before the layer was removed, which is part of how its cipher type was
+    /// identified.
+    pub index_of_coincidence: f64,
+
+    /// How English-like the text is after the layer was removed; See `cipher_utils::englishness()`. When an encoding is
+    /// removed and this is below `CipherCracker::PLAINTEXT_SCORE`, the text is checked for another layer.
+    pub score: f64,
+
+    /// The text after the layer was removed.
+    pub output: String,
+}
@@ -0,0 +1,112 @@
+use a1z26_cipher::A1Z26;
+use base64_cipher::Base64;
+use cipher_utils::{alphabet::Alphabet, cipher_type::CipherType};
+use columnar_transposition::ColumnarTransposition;
+use hex_cipher::HexCipher;
+use morse_code_cipher::MorseCode;
+use octal_cipher::OctalCipher;
+use substitution_cipher::{Substitution, SubstitutionBuilder as _};
+use vigenere_lib::{Vigenere, VigenereBuilder as _};
+
+/// Decrypts the given ciphertext with the cipher of the given type, without having to build the cipher manually. Along
+/// with `CipherType::best_match()`, this allows detecting and decrypting a ciphertext in two calls when the key is known.
+///
+/// Each cipher type uses the parameters as follows:
+///
+/// - `Base64`, `Hex`, `Octal`, `Morse`, and `A1Z26` are encodings, and take neither a key nor an alphabet. They're
+///   decoded like `Base64::decrypt()`, `HexCipher::decrypt()`, `OctalCipher::decrypt()`, `MorseCode::decrypt()`, and
+///   `A1Z26::decrypt()`.
+/// - `Vigenere` requires a key, and takes an optional alphabet that defaults to `A-Z`.
+/// - `Substitution` requires a key, which is the 26-letter alphabet that the letters of `A-Z` were replaced with. The
+///   alphabet must be `A-Z` if it's given.
+/// - `Transposition` is a columnar transposition, and requires a keyword; The columns are read off in the alphabetical
+///   order of the keyword's letters.
+/// - `Enigma` can't be decrypted with a key alone, as it needs the full machine settings; Use the `enigma-simulator`
+///   crate instead.
+///
+/// # Parameters
+/// - `cipher_type` - The type of cipher the ciphertext was encrypted with.
+/// - `ciphertext` - The text to decrypt.
+/// - `key` - The key of the cipher, if it has one.
+/// - `alphabet` - The alphabet of the cipher, if it takes one.
+///
+/// # Returns
+/// The decrypted text.
+///
+/// # Errors
+/// - If the cipher type requires a key and none was given
+/// - If the key isn't valid for the cipher type, such as a Vigenere key with letters that aren't in the alphabet, or if
+///   the cipher type doesn't take the given alphabet
+/// - If the ciphertext isn't valid for the cipher type, such as base 64 with invalid characters
+pub fn decrypt(cipher_type: CipherType, ciphertext: &str, key: Option<&str>, alphabet: Option<&Alphabet>) -> anyhow::Result<String> {
+    let require_key = || key.ok_or_else(|| anyhow::anyhow!("Decrypting a {cipher_type:?} cipher requires a key"));
+
+    match cipher_type {
+        CipherType::Base64 => Base64::decrypt(ciphertext),
+        CipherType::Hex => HexCipher::decrypt(ciphertext),
+        CipherType::Octal => OctalCipher::decrypt(ciphertext),
+        CipherType::A1Z26 => A1Z26::decrypt(ciphertext),
+        CipherType::Morse => Ok(MorseCode::decrypt(ciphertext)),
+        CipherType::Vigenere => {
+            let alphabet = alphabet.map_or_else(|| "ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_owned(), |alphabet| alphabet.characters().iter().collect());
+            Vigenere::new().alphabet(alphabet).key(require_key()?).build()?.decrypt(ciphertext)
+        }
+        CipherType::Substitution => {
+            if alphabet.is_some_and(|alphabet| *alphabet != Alphabet::default()) {
+                anyhow::bail!("Decrypting a substitution cipher only supports the alphabet A-Z");
+            }
+            Ok(Substitution::new().key(require_key()?).build()?.decrypt(ciphertext))
+        }
+        CipherType::Enigma => anyhow::bail!("Decrypting an Enigma cipher requires the machine settings; Use the enigma-simulator crate"),
+        CipherType::Transposition => {
+            let key = require_key()?;
+            if key.is_empty() {
+                anyhow::bail!("Decrypting a columnar transposition requires a non-empty key");
+            }
+            Ok(ColumnarTransposition::new(key).decrypt(ciphertext))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decrypt;
+    use cipher_utils::{alphabet::Alphabet, cipher_type::CipherType};
+
+    #[test]
+    fn dispatch() -> anyhow::Result<()> {
+        assert_eq!("Hello, World!", decrypt(CipherType::Base64, "SGVsbG8sIFdvcmxkIQ==", None, None)?);
+        assert_eq!("Hi", decrypt(CipherType::Base64, "SGk=", Some("ignored"), None)?);
+        assert!(decrypt(CipherType::Base64, "SGk*", None, None).is_err());
+
+        let alphabet = Alphabet::default();
+        assert_eq!("ATTACKATDAWN", decrypt(CipherType::Vigenere, "LXFOPVEFRNHR", Some("LEMON"), Some(&alphabet))?);
+        assert_eq!("Attack at dawn!", decrypt(CipherType::Vigenere, "Lxfopv ef rnhr!", Some("LEMON"), None)?);
+        assert!(decrypt(CipherType::Vigenere, "LXFOPVEFRNHR", None, Some(&alphabet)).is_err());
+        assert!(decrypt(CipherType::Vigenere, "LXFOPVEFRNHR", Some("L3MON"), Some(&alphabet)).is_err());
+
+        assert_eq!("HI", decrypt(CipherType::Hex, "48 49", None, None)?);
+        assert_eq!("Hi", decrypt(CipherType::Hex, "0x4869", None, None)?);
+        assert!(decrypt(CipherType::Base64, "SG=k", None, None).is_err());
+        assert_eq!("HI", decrypt(CipherType::Octal, "110 111", None, None)?);
+        assert_eq!("HI", decrypt(CipherType::A1Z26, "8 9", None, None)?);
+        assert_eq!("HI THERE", decrypt(CipherType::A1Z26, "8-9 20-8-5-18-5", None, None)?);
+        assert!(decrypt(CipherType::A1Z26, "8 27", None, None).is_err());
+        assert_eq!("SOS HI", decrypt(CipherType::Morse, "... --- ... / .... ..", None, None)?);
+        assert_eq!("Flee", decrypt(CipherType::Substitution, "Siaa", Some("ZEBRASCDFGHIJKLMNOPQTUVWXY"), None)?);
+        assert!(decrypt(
+            CipherType::Substitution,
+            "Siaa",
+            Some("ZEBRASCDFGHIJKLMNOPQTUVWXY"),
+            Some(&Alphabet::caseless("ZYXWVUTSRQPONMLKJIHGFEDCBA")?)
+        )
+        .is_err());
+        assert_eq!(
+            "WEAREDISCOVEREDFLEEATONCE",
+            decrypt(CipherType::Transposition, "EVLNACDTESEAROFODEECWIREE", Some("ZEBRAS"), None)?
+        );
+        assert!(decrypt(CipherType::Transposition, "EVLNACDTESEAROFODEECWIREE", None, None).is_err());
+
+        Ok(())
+    }
+}
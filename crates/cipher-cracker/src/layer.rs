@@ -0,0 +1,79 @@
+use base64_cipher::Base64;
+use cipher_utils::{frequency, reverse, rot13, rotn};
+use hex_cipher::HexCipher;
+use octal_cipher::OctalCipher;
+
+/// A single layer of encoding or encryption that `CipherCracker::try_combinations()` can peel off of a ciphertext.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    Base64,
+    Octal,
+    Hex,
+    Rot13,
+
+    /// A Caesar cipher with the given shift, which is never 13; See `Layer::Rot13`.
+    Caesar(u8),
+    Atbash,
+    Reverse,
+}
+
+impl Layer {
+    /// The layers to try, in the order they're tried. Caesar ciphers are tried with every shift, but only the shift
+    /// that produces the most English-like text is kept.
+    pub(crate) const ALL: [Layer; 7] = [Layer::Base64, Layer::Octal, Layer::Hex, Layer::Rot13, Layer::Caesar(0), Layer::Atbash, Layer::Reverse];
+
+    /// Removes this layer from the given text.
+    ///
+    /// # Parameters
+    /// - `text` - The text to decode.
+    ///
+    /// # Returns
+    /// The decoded text and the layer that was removed, which differs from `self` only in the shift of a Caesar cipher,
+    /// or `None` if the text can't be decoded as this layer.
+    pub(crate) fn decode(self, text: &str) -> Option<(Layer, String)> {
+        match self {
+            Self::Base64 => Base64::decrypt(text).ok().map(|decoded| (self, decoded)),
+            Self::Octal => OctalCipher::decrypt(text).ok().map(|decoded| (self, decoded)),
+            Self::Hex => HexCipher::decrypt(text).ok().map(|decoded| (self, decoded)),
+            Self::Rot13 => Some((self, rot13(text))),
+            Self::Caesar(_) => (1..26)
+                .filter(|amount| *amount != 13)
+                .map(|amount| (Self::Caesar(amount), rotn(text, 26 - amount)))
+                .max_by(|first, other| frequency::quadgram_score(&first.1).total_cmp(&frequency::quadgram_score(&other.1))),
+            Self::Atbash => Some((
+                self,
+                text.chars()
+                    .map(|character| match character {
+                        'A'..='Z' => (b'Z' - (character as u8 - b'A')) as char,
+                        'a'..='z' => (b'z' - (character as u8 - b'a')) as char,
+                        _ => character,
+                    })
+                    .collect(),
+            )),
+            Self::Reverse => Some((self, reverse(text))),
+        }
+    }
+
+    /// Returns whether this layer can be applied directly on top of the given layer without being redundant. Two
+    /// shifts in a row are equivalent to a single shift, and two Atbash ciphers or reversals in a row cancel out.
+    pub(crate) fn can_follow(self, previous: Layer) -> bool {
+        !matches!(
+            (self, previous),
+            (Self::Rot13 | Self::Caesar(_), Self::Rot13 | Self::Caesar(_)) | (Self::Atbash, Self::Atbash) | (Self::Reverse, Self::Reverse)
+        )
+    }
+}
+
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Base64 => write!(f, "base 64"),
+            Self::Octal => write!(f, "octal"),
+            Self::Hex => write!(f, "hex"),
+            Self::Rot13 => write!(f, "ROT13"),
+            Self::Caesar(shift) => write!(f, "Caesar (shift {shift})"),
+            Self::Atbash => write!(f, "Atbash"),
+            Self::Reverse => write!(f, "reversal"),
+        }
+    }
+}
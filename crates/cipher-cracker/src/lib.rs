@@ -1,9 +1,25 @@
+mod analysis_report;
+mod crack_step;
+mod decrypt;
+mod layer;
+
+pub use analysis_report::AnalysisReport;
+pub use crack_step::CrackStep;
+pub use decrypt::decrypt;
+pub use layer::Layer;
+
+use a1z26_cipher::A1Z26;
+use affine_cracker::AffineCracker;
 use base64_cipher::Base64;
-use cipher_utils::{alphabet::Alphabet, cipher_type::CipherType, score::PossiblePlaintext, Analyze};
-use colored::Colorize;
+use cipher_utils::{alphabet::Alphabet, cipher_type::CipherType, Analyze};
+use colored::{ColoredString, Colorize};
+use enigma_cracker::EnigmaCracker;
 use gronsfeld_cracker::GronsfeldCracker;
+use hex_cipher::HexCipher;
 use morse_code_cipher::MorseCode;
 use octal_cipher::OctalCipher;
+use substitution_cracker::SubstitutionCracker;
+use vigenere_cracker::VigenereCracker;
 
 #[derive(Default)]
 pub struct CipherCracker {
@@ -12,118 +28,374 @@ pub struct CipherCracker {
 
     /// The alphabet of the cipher to crack, if it's known.
     alphabet: Option<Alphabet>,
+
+    /// Whether to remove framing such as labels and line numbers from the ciphertext before cracking it. See
+    /// `CipherCracker::strip_framing()`.
+    strip_framing: bool,
+
+    /// Whether to leave the colors out of this cracker's output, regardless of the global setting. See
+    /// `CipherCracker::no_color()`.
+    no_color: bool,
 }
 
 impl CipherCracker {
+    /// The englishness above which a decoded text is considered fully decrypted, and isn't checked for another layer of
+    /// encryption. See `cipher_utils::englishness()`.
+    const PLAINTEXT_SCORE: f64 = 0.8;
+
     pub fn new() -> Self {
         Self::default()
     }
 
     pub fn with_known_key<T: AsRef<str>>(mut self, key: T) -> Self {
-        self.key = Some(key.as_ref().to_owned());
+        self.set_known_key(key);
         self
     }
 
     pub fn with_known_alphabet<T: AsRef<str>>(mut self, key: T) -> anyhow::Result<Self> {
-        self.alphabet = Some(Alphabet::caseless(key.as_ref())?);
+        self.set_known_alphabet(key)?;
         Ok(self)
     }
 
-    pub fn crack(&self, ciphertext: &str) -> anyhow::Result<String> {
-        println!("\n{} cipher...", "Cracking".bold().green());
-        let cipher_type = CipherType::best_match(ciphertext).ok_or_else(|| anyhow::anyhow!("Unable to identify cipher type."))?;
-
-        Ok(match cipher_type {
-            CipherType::Octal => {
-                println!("\t{} cipher type as {}.", "Identified".green().bold(), "octal".cyan().bold());
-                println!("\t{} as {} encoding...", "Decrypting".bold().green(), "octal".cyan().bold());
-                let plaintext = OctalCipher::decrypt(ciphertext)?;
-                if plaintext.chars().all(|character| character.is_ascii()) {
-                    println!(
-                        "\t{} that {} decryption was successful.\n\t{} for additional encryption layers...",
-                        "Detected".green().bold(),
-                        "octal".cyan().bold(),
-                        "Checking".green().bold()
-                    );
-                    self.check_for_encryption(&plaintext)?
-                } else {
-                    todo!()
-                }
+    /// Sets the known key of the cipher to crack, in place. See `CipherCracker::with_known_key()`.
+    ///
+    /// # Parameters
+    /// - `key` - The key of the cipher.
+    pub fn set_known_key<T: AsRef<str>>(&mut self, key: T) {
+        self.key = Some(key.as_ref().to_owned());
+    }
+
+    /// Sets the known alphabet of the cipher to crack, in place. See `CipherCracker::with_known_alphabet()`.
+    ///
+    /// # Parameters
+    /// - `alphabet` - The alphabet of the cipher.
+    ///
+    /// # Errors
+    /// If the alphabet isn't 26 distinct letters. The previously known alphabet is kept in that case.
+    pub fn set_known_alphabet<T: AsRef<str>>(&mut self, alphabet: T) -> anyhow::Result<()> {
+        self.alphabet = Some(Alphabet::caseless(alphabet.as_ref())?);
+        Ok(())
+    }
+
+    /// Forgets the known key and alphabet, so that the cracker can be reused on a ciphertext with different hints.
+    /// Other settings, such as `CipherCracker::strip_framing()`, are kept.
+    pub fn reset(&mut self) {
+        self.key = None;
+        self.alphabet = None;
+    }
+
+    /// Removes framing such as a `"Ciphertext:"` label, header lines, and line numbers from ciphertexts before cracking
+    /// them, so that they don't skew the detection of the cipher type. See `cipher_utils::strip_framing()`.
+    pub fn strip_framing(mut self) -> Self {
+        self.strip_framing = true;
+        self
+    }
+
+    /// Disables colored output for this and every other cracker, such as when the output is being written to a file
+    /// or a terminal without ANSI support. Colors are also disabled automatically when the `NO_COLOR` environment
+    /// variable is set.
+    ///
+    /// The crackers of the individual ciphers print their own colored output, so this sets `colored`'s global override,
+    /// which affects every thread and stays in effect for the rest of the program, even after this cracker is dropped.
+    /// Call `colored::control::unset_override()` to turn colors back on.
+    pub fn no_color(mut self) -> Self {
+        self.no_color = true;
+        colored::control::set_override(false);
+        self
+    }
+
+    /// Removes the colors from the given text if this cracker's colors are disabled. See `CipherCracker::no_color()`.
+    fn style(&self, text: ColoredString) -> ColoredString {
+        if self.no_color {
+            text.clear()
+        } else {
+            text
+        }
+    }
+
+    /// Returns a report of the statistics of the given ciphertext, such as its index of coincidence, entropy, and most
+    /// likely cipher type. This doesn't attempt to decrypt the ciphertext, and is useful for diagnosing a failed crack.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext to analyze.
+    ///
+    /// # Returns
+    /// The analysis report.
+    pub fn analyze(ciphertext: &str) -> AnalysisReport {
+        AnalysisReport::new(ciphertext)
+    }
+
+    /// Searches for the chain of common encoding layers that best decodes the given ciphertext, such as base 64 on
+    /// top of ROT13. Unlike `crack()`, this doesn't need each intermediate layer to be detectable; Every combination
+    /// of base 64, octal, hex, ROT13, Caesar, Atbash, and reversal up to the given depth is tried, and the chain whose result is
+    /// the most plaintext-like is returned.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext to decode.
+    /// - `max_depth` - The maximum number of layers to remove.
+    ///
+    /// # Returns
+    /// The layers that were removed, in the order they were removed, and the decoded text. If no chain of layers
+    /// scores better than the ciphertext itself, the layer list is empty.
+    ///
+    /// # Performance
+    /// This is exponential in `max_depth`, although layers that fail to decode or produce non-ASCII text are pruned.
+    pub fn try_combinations(ciphertext: &str, max_depth: usize) -> (Vec<Layer>, String) {
+        let mut best = (Vec::new(), ciphertext.to_owned(), cipher_utils::englishness(ciphertext));
+        Self::search_combinations(&mut Vec::new(), ciphertext, max_depth, &mut best);
+        (best.0, best.1)
+    }
+
+    /// Recursively removes every possible layer from the given text, updating `best` whenever a better plaintext is
+    /// found, along with its englishness. Shorter chains win ties. See `try_combinations()`.
+    fn search_combinations(layers: &mut Vec<Layer>, text: &str, depth: usize, best: &mut (Vec<Layer>, String, f64)) {
+        if depth == 0 {
+            return;
+        }
+
+        for layer in Layer::ALL {
+            if layers.last().is_some_and(|previous| !layer.can_follow(*previous)) {
+                continue;
             }
-            CipherType::Base64 => {
-                println!("\t{} cipher type as {}.", "Identified".green().bold(), "base 64".cyan().bold());
-                println!("\t{} as {} encoding...", "Decrypting".bold().green(), "base 64".cyan().bold());
-                let plaintext = Base64::decrypt(ciphertext);
-
-                // Successful Base64 decryption
-                if plaintext.chars().all(|character| character.is_ascii()) {
-                    println!(
-                        "\t{} that {} decryption was successful.\n\t{} for additional encryption layers...",
-                        "Detected".green().bold(),
-                        "base 64".cyan().bold(),
-                        "Checking".green().bold()
-                    );
-                    self.check_for_encryption(&plaintext)?
-                }
-                // Not regular Base64
-                else {
-                    todo!()
-                }
+
+            let Some((layer, decoded)) = layer.decode(text) else {
+                continue;
+            };
+            if decoded.is_empty() || !decoded.is_ascii() {
+                continue;
             }
-            CipherType::Morse => {
-                println!("\t{} cipher type as {}.", "Identified".green().bold(), "morse code".cyan().bold());
-                println!("\t{} as {} encoding...", "Decrypting".bold().green(), "morse code".cyan().bold());
-                let plaintext = MorseCode::decrypt(ciphertext);
-
-                // Successful Base64 decryption
-                if plaintext.chars().all(|character| character.is_ascii()) {
-                    println!(
-                        "\t{} that {} decryption was successful.\n\t{} for additional encryption layers...",
-                        "Detected".green().bold(),
-                        "morse code".cyan().bold(),
-                        "Checking".green().bold()
-                    );
-                    self.check_for_encryption(&plaintext)?
-                }
-                // Not regular Base64
-                else {
-                    todo!()
-                }
+
+            layers.push(layer);
+            let score = cipher_utils::englishness(&decoded);
+            if score > best.2 {
+                *best = (layers.clone(), decoded.clone(), score);
             }
-            CipherType::Substitution => match ciphertext.index_of_coincidence() {
-                (0.04..=0.05) => GronsfeldCracker::new().with_known_alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").decrypt(ciphertext)?,
-                _ => todo!(),
-            },
-            _ => todo!(),
-        })
+            Self::search_combinations(layers, &decoded, depth - 1, best);
+            layers.pop();
+        }
+    }
+
+    pub fn crack(&self, ciphertext: &str) -> anyhow::Result<String> {
+        println!("\n{} cipher...", self.style("Cracking".bold().green()));
+        let (plaintext, steps) = self.crack_explained(ciphertext)?;
+        print!("{}", self.narrate(&steps));
+        Ok(plaintext)
     }
 
-    fn check_for_encryption(&self, plaintext: &str) -> anyhow::Result<String> {
-        let mut plaintext = plaintext.to_owned();
-        while PossiblePlaintext::new(&plaintext).score() < 0.8 {
-            println!(
-                "\t{} that cipher has another layer of encryption. Running through another decryption pass...",
-                "Detected".green().bold(),
+    /// Returns the narrative that `crack()` prints about the layers of encryption that it removed.
+    ///
+    /// # Parameters
+    /// - `steps` - The layers that were removed, in the order they were removed.
+    ///
+    /// # Returns
+    /// The narrative, with a newline after each line.
+    fn narrate(&self, steps: &[CrackStep]) -> String {
+        let mut narrative = String::new();
+        for (index, step) in steps.iter().enumerate() {
+            let name = self.style(step.cipher_type.to_string().cyan().bold());
+            if index > 0 {
+                narrative += &format!(
+                    "\t{} that cipher has another layer of encryption. Running through another decryption pass...\n",
+                    self.style("Detected".green().bold()),
+                );
+            }
+            narrative += &format!("\t{} cipher type as {name}.\n", self.style("Identified".green().bold()));
+            narrative += &format!("\t{} as {name}...\n", self.style("Decrypting".bold().green()));
+            narrative += &format!(
+                "\t{} that {name} decryption was successful, with a plaintext score of {:.3}.\n",
+                self.style("Detected".green().bold()),
+                step.score
             );
-            plaintext = self.crack(&plaintext)?;
         }
 
-        println!("{} additional encryption layers found. {}...\n", "No more".green().bold(), "Exiting".bold().cyan());
-        Ok(plaintext)
+        narrative += &format!(
+            "{} additional encryption layers found. {}...\n\n",
+            self.style("No more".green().bold()),
+            self.style("Exiting".bold().cyan())
+        );
+        narrative
+    }
+
+    /// Cracks the given ciphertext like `crack()`, but instead of printing what it's doing, returns each layer of
+    /// encryption it removed as structured data, such as for teaching tools.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext to crack.
+    ///
+    /// # Returns
+    /// The decrypted text, and the layers that were removed to get it, in the order they were removed. If a layer
+    /// decodes to text that isn't ASCII, it's the last layer removed.
+    ///
+    /// # Errors
+    /// If the type of cipher of a layer can't be identified, or if a layer fails to decrypt.
+    pub fn crack_explained(&self, ciphertext: &str) -> anyhow::Result<(String, Vec<CrackStep>)> {
+        let mut steps = Vec::new();
+        let mut text = if self.strip_framing {
+            cipher_utils::strip_framing(ciphertext)
+        } else {
+            ciphertext.to_owned()
+        };
+        loop {
+            let step = self.crack_layer(&text)?;
+            text = step.output.clone();
+
+            // Encodings are often stacked on top of other ciphers, so their output is checked for another layer. None of the
+            // ciphers produce text outside of ASCII, so non-ASCII output is taken to be the plaintext.
+            let encoding = matches!(step.cipher_type, CipherType::Octal | CipherType::Base64 | CipherType::Morse | CipherType::A1Z26);
+            let done = !encoding || step.score >= Self::PLAINTEXT_SCORE || !step.output.is_ascii();
+            steps.push(step);
+            if done {
+                return Ok((text, steps));
+            }
+        }
+    }
+
+    /// Decrypts the given ciphertext as the given type of cipher, skipping the detection of the cipher type. This is
+    /// useful when the type of cipher is known and only the key needs to be found, or when the ciphertext is
+    /// misdetected as another type. Only one layer of encryption is removed.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext to decrypt.
+    /// - `cipher_type` - The type of cipher that the ciphertext is encrypted with.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the ciphertext fails to decrypt as the given type of cipher, or if cracking that type isn't supported.
+    pub fn crack_as(&self, ciphertext: &str, cipher_type: CipherType) -> anyhow::Result<String> {
+        let text = if self.strip_framing {
+            cipher_utils::strip_framing(ciphertext)
+        } else {
+            ciphertext.to_owned()
+        };
+        self.decrypt_as(&text, cipher_type)
+    }
+
+    /// Identifies the outermost layer of encryption of the given text and removes it.
+    ///
+    /// # Parameters
+    /// - `text` - The text to remove a layer from.
+    ///
+    /// # Returns
+    /// The removed layer, including the text without it.
+    ///
+    /// # Errors
+    /// If the type of cipher can't be identified, or if the text fails to decrypt.
+    fn crack_layer(&self, text: &str) -> anyhow::Result<CrackStep> {
+        let cipher_type = CipherType::best_match(text).ok_or_else(|| anyhow::anyhow!("Unable to identify cipher type."))?;
+
+        // Uppercase Vigenère ciphertext with a key of more than a few letters is as flat as Enigma's, so it's tried first,
+        // which is also much faster than brute forcing the Enigma settings
+        let vigenere = match cipher_type {
+            CipherType::Enigma => self.crack_vigenere(text).ok().filter(|output| cipher_utils::englishness(output) >= Self::PLAINTEXT_SCORE),
+            _ => None,
+        };
+        let (cipher_type, output) = match vigenere {
+            Some(output) => (CipherType::Vigenere, output),
+            None => (cipher_type, self.decrypt_as(text, cipher_type)?),
+        };
+        Ok(CrackStep {
+            cipher_type,
+            index_of_coincidence: text.index_of_coincidence(),
+            score: cipher_utils::englishness(&output),
+            output,
+        })
+    }
+
+    /// Removes one layer of encryption of the given type from the given text.
+    ///
+    /// # Parameters
+    /// - `text` - The text to remove a layer from.
+    /// - `cipher_type` - The type of cipher of the layer.
+    ///
+    /// # Returns
+    /// The text without the layer.
+    ///
+    /// # Errors
+    /// If the text fails to decrypt, or if cracking the type of cipher isn't supported.
+    fn decrypt_as(&self, text: &str, cipher_type: CipherType) -> anyhow::Result<String> {
+        match cipher_type {
+            CipherType::Octal => OctalCipher::decrypt(text),
+            CipherType::A1Z26 => A1Z26::decrypt(text),
+            CipherType::Hex => HexCipher::decrypt(text),
+            CipherType::Base64 => Base64::decrypt(text),
+            CipherType::Morse => Ok(MorseCode::decrypt(text)),
+            CipherType::Vigenere => self.crack_vigenere(text),
+            CipherType::Substitution => self.crack_substitution(text),
+            CipherType::Enigma => EnigmaCracker::new().decrypt(text),
+            CipherType::Transposition => anyhow::bail!("Cracking {cipher_type} ciphers isn't supported yet."),
+        }
+    }
+
+    /// Decrypts the given Vigenere ciphertext, assuming the standard alphabet if the alphabet isn't known. The key is
+    /// recovered from the letter frequencies of each column of the text, which falls back to brute forcing Gronsfeld
+    /// keys if it doesn't give a plaintext.
+    ///
+    /// # Parameters
+    /// - `text` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the text fails to decrypt, or if neither the recovered key nor a Gronsfeld key gives English.
+    fn crack_vigenere(&self, text: &str) -> anyhow::Result<String> {
+        let alphabet = self
+            .alphabet
+            .as_ref()
+            .map_or_else(|| "ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_owned(), |alphabet| alphabet.characters().iter().collect());
+
+        let (_, plaintext) = VigenereCracker::with_known_alphabet(&alphabet)?.crack_key(text)?;
+        if cipher_utils::englishness(&plaintext) >= Self::PLAINTEXT_SCORE {
+            return Ok(plaintext);
+        }
+
+        match text.index_of_coincidence() {
+            (0.04..=0.05) => GronsfeldCracker::new().with_known_alphabet(&alphabet).decrypt(text),
+            index_of_coincidence => anyhow::bail!(
+                "Unable to crack Vigenère ciphertext: The recovered key doesn't give English, and the index of coincidence of {index_of_coincidence:.3} is too far from English for a Gronsfeld key"
+            ),
+        }
+    }
+
+    /// Decrypts the given monoalphabetic substitution ciphertext. Affine ciphers, which include Caesar shifts and
+    /// Atbash, have few enough keys to brute force, so they're tried first, before hill climbing for a general
+    /// substitution key.
+    ///
+    /// # Parameters
+    /// - `text` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the text has no letters to decrypt.
+    fn crack_substitution(&self, text: &str) -> anyhow::Result<String> {
+        let (_, _, plaintext) = AffineCracker::new().crack(text)?;
+        if cipher_utils::englishness(&plaintext) >= Self::PLAINTEXT_SCORE {
+            return Ok(plaintext);
+        }
+
+        SubstitutionCracker::new().decrypt(text)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::CipherCracker;
+    use crate::{CipherCracker, CrackStep, Layer};
+    use a1z26_cipher::A1Z26;
+    use affine_cipher::{Affine, AffineBuilder as _};
     use base64_cipher::Base64;
-    use cipher_utils::score::PossiblePlaintext;
+    use cipher_utils::{cipher_type::CipherType, Analyze as _};
     use gronsfeld::{Gronsfeld, GronsfeldBuilder};
+    use hex_cipher::HexCipher;
     use morse_code_cipher::MorseCode;
     use octal_cipher::OctalCipher;
+    use substitution_cipher::{Substitution, SubstitutionBuilder as _};
+    use vigenere_lib::{Vigenere, VigenereBuilder as _};
 
     static PLAINTEXT: &str = include_str!("../tests/letter.txt");
-    static KEY: &str = "SUPERSECRETKEY";
     static NUMERIC_KEY: &str = "31824";
 
     #[test]
@@ -135,6 +407,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn affine() -> anyhow::Result<()> {
+        let ciphertext = Affine::new().key(11, 6).build()?.encrypt(PLAINTEXT);
+        assert_eq!(Some(CipherType::Substitution), CipherType::best_match(&ciphertext));
+        println!();
+        let plaintext = CipherCracker::new().crack(&ciphertext)?;
+        assert_eq!(PLAINTEXT, plaintext);
+        Ok(())
+    }
+
+    #[test]
+    fn keyword_substitution() -> anyhow::Result<()> {
+        // A keyword alphabet isn't affine, so this falls through to hill climbing instead of panicking. Hill climbing
+        // starts from random keys, so only the shape of its output is checked.
+        let ciphertext = Substitution::new().keyword("ZEBRAS").build()?.encrypt(PLAINTEXT);
+        assert_eq!(Some(CipherType::Substitution), CipherType::best_match(&ciphertext));
+        let plaintext = CipherCracker::new().crack_as(&ciphertext, CipherType::Substitution)?;
+        assert_eq!(ciphertext.len(), plaintext.len());
+        assert!(plaintext
+            .chars()
+            .zip(ciphertext.chars())
+            .all(|(plain, cipher)| plain.is_ascii_alphabetic() == cipher.is_ascii_alphabetic()));
+        Ok(())
+    }
+
+    #[test]
+    fn crack_as() -> anyhow::Result<()> {
+        // Capitalizing every word and removing the spaces leaves only base 64 characters in mixed case, so the
+        // ciphertext is detected as base 64
+        let plaintext = PLAINTEXT
+            .split_whitespace()
+            .map(|word| {
+                let word = word.chars().filter(|character| character.is_ascii_alphabetic()).collect::<String>();
+                word[..1.min(word.len())].to_uppercase() + &word[1.min(word.len())..]
+            })
+            .collect::<String>();
+        let ciphertext = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON").build()?.encrypt(&plaintext)?;
+        assert_eq!(Some(CipherType::Base64), CipherType::best_match(&ciphertext));
+
+        assert_eq!(plaintext, CipherCracker::new().crack_as(&ciphertext, CipherType::Vigenere)?);
+        assert_eq!(PLAINTEXT, CipherCracker::new().crack_as(&Base64::encrypt(PLAINTEXT), CipherType::Base64)?);
+        assert!(CipherCracker::new().crack_as(&ciphertext, CipherType::Transposition).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn a1z26() -> anyhow::Result<()> {
+        let words = PLAINTEXT
+            .split_whitespace()
+            .map(|word| word.chars().filter(char::is_ascii_alphabetic).collect::<String>())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let ciphertext = A1Z26::encrypt_with_delimiter(&words, "-");
+        assert_eq!(Some(CipherType::A1Z26), CipherType::best_match(&ciphertext));
+        println!();
+        let (plaintext, steps) = CipherCracker::new().crack_explained(&ciphertext)?;
+        assert_eq!(words.to_uppercase(), plaintext);
+        assert_eq!(vec![CipherType::A1Z26], steps.iter().map(|step| step.cipher_type).collect::<Vec<_>>());
+        Ok(())
+    }
+
     #[test]
     fn octal() -> anyhow::Result<()> {
         let ciphertext = OctalCipher::encrypt(PLAINTEXT);
@@ -153,6 +487,110 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn analyze() -> anyhow::Result<()> {
+        let ciphertext = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON").build()?.encrypt(PLAINTEXT)?;
+        let report = CipherCracker::analyze(&ciphertext);
+
+        assert_eq!(Some(CipherType::Vigenere), report.cipher_type);
+        assert_eq!(Some(5), report.key_length);
+        assert!((0.035..0.055).contains(&report.index_of_coincidence));
+        assert!((report.index_of_coincidence - ciphertext.index_of_coincidence()).abs() < f64::EPSILON);
+        assert_eq!(26, report.letter_frequencies.len());
+        assert!(report.letter_frequencies.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let report = CipherCracker::analyze(&OctalCipher::encrypt(PLAINTEXT));
+        assert_eq!(Some(CipherType::Octal), report.cipher_type);
+        assert_eq!(None, report.key_length);
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_combinations() {
+        let ciphertext = Base64::encrypt(&cipher_utils::rot13(PLAINTEXT));
+        assert_eq!((vec![Layer::Base64, Layer::Rot13], PLAINTEXT.to_owned()), CipherCracker::try_combinations(&ciphertext, 3));
+
+        let ciphertext = HexCipher::encrypt(&Base64::encrypt(PLAINTEXT));
+        assert_eq!((vec![Layer::Hex, Layer::Base64], PLAINTEXT.to_owned()), CipherCracker::try_combinations(&ciphertext, 3));
+
+        let ciphertext = Base64::encrypt(&cipher_utils::reverse(PLAINTEXT));
+        assert_eq!((vec![Layer::Base64, Layer::Reverse], PLAINTEXT.to_owned()), CipherCracker::try_combinations(&ciphertext, 2));
+
+        // Not deep enough to reach the plaintext
+        assert_ne!(PLAINTEXT, CipherCracker::try_combinations(&ciphertext, 1).1);
+    }
+
+    #[test]
+    fn crack_explained() -> anyhow::Result<()> {
+        let octal = OctalCipher::encrypt(PLAINTEXT);
+        let ciphertext = Base64::encrypt(&octal);
+        let (plaintext, steps) = CipherCracker::new().crack_explained(&ciphertext)?;
+
+        assert_eq!(PLAINTEXT, plaintext);
+        assert_eq!(
+            vec![(CipherType::Base64, octal.as_str()), (CipherType::Octal, PLAINTEXT)],
+            steps.iter().map(|step| (step.cipher_type, step.output.as_str())).collect::<Vec<_>>()
+        );
+        assert!(steps[0].score < 0.8 && steps[1].score >= 0.8);
+        assert!((steps[0].index_of_coincidence - ciphertext.index_of_coincidence()).abs() < f64::EPSILON);
+        assert!(steps.iter().all(|CrackStep { score, .. }| (0. ..=1.).contains(score)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn strip_framing() -> anyhow::Result<()> {
+        let octal = OctalCipher::encrypt(PLAINTEXT);
+        let framed = format!("Challenge 4:\nCiphertext: {octal}\n\n");
+
+        assert_eq!(Some(CipherType::Octal), CipherType::best_match(&cipher_utils::strip_framing(&framed)));
+        assert_ne!(Some(CipherType::Octal), CipherType::best_match(&framed));
+
+        let (plaintext, steps) = CipherCracker::new().strip_framing().crack_explained(&framed)?;
+        assert_eq!(PLAINTEXT, plaintext);
+        assert_eq!(vec![CipherType::Octal], steps.iter().map(|step| step.cipher_type).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn base_64_vigenere() -> anyhow::Result<()> {
+        let vigenere = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON").build()?.encrypt(PLAINTEXT)?;
+        let ciphertext = Base64::encrypt(&vigenere);
+
+        let (plaintext, steps) = CipherCracker::new().with_known_alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ")?.crack_explained(&ciphertext)?;
+        assert_eq!(PLAINTEXT, plaintext);
+        assert_eq!(
+            vec![(CipherType::Base64, vigenere.as_str()), (CipherType::Vigenere, PLAINTEXT)],
+            steps.iter().map(|step| (step.cipher_type, step.output.as_str())).collect::<Vec<_>>()
+        );
+
+        println!();
+        assert_eq!(PLAINTEXT, CipherCracker::new().crack(&ciphertext)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_color() -> anyhow::Result<()> {
+        // The global override is restored right away so that it doesn't leak into other tests; The cracker's own output
+        // stays uncolored regardless
+        let cracker = CipherCracker::new().no_color();
+        colored::control::unset_override();
+
+        let ciphertext = Base64::encrypt(&OctalCipher::encrypt(PLAINTEXT));
+        let (plaintext, steps) = cracker.crack_explained(&ciphertext)?;
+        assert_eq!(PLAINTEXT, plaintext);
+
+        let narrative = cracker.narrate(&steps);
+        assert!(!narrative.contains('\x1b'));
+        assert!(narrative.contains("\tIdentified cipher type as base64.\n"));
+        assert!(narrative.contains("\tIdentified cipher type as octal.\n"));
+        assert_eq!(PLAINTEXT, cracker.crack(&ciphertext)?);
+        Ok(())
+    }
+
     #[test]
     fn gronsfeld() -> anyhow::Result<()> {
         let ciphertext = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_str(NUMERIC_KEY).build()?.encrypt(PLAINTEXT)?;
@@ -163,12 +601,74 @@ mod tests {
         assert_eq!(PLAINTEXT, plaintext);
         Ok(())
     }
+
+    #[test]
+    fn non_ascii_output() -> anyhow::Result<()> {
+        let original = "Grüße aus Köln, wo es im Winter schneit.";
+        let ciphertext = Base64::encrypt(original);
+        let (plaintext, steps) = CipherCracker::new().crack_explained(&ciphertext)?;
+        assert_eq!(original, plaintext);
+        assert_eq!(vec![CipherType::Base64], steps.iter().map(|step| step.cipher_type).collect::<Vec<_>>());
+        assert_eq!(plaintext, CipherCracker::new().crack(&ciphertext)?);
+        Ok(())
+    }
+
+    #[test]
+    fn uppercase_vigenere() -> anyhow::Result<()> {
+        // Uppercase Vigenère with a long key is as flat as Enigma, but is still cracked as Vigenère
+        let letters = PLAINTEXT.chars().filter(char::is_ascii_alphabetic).collect::<String>().to_uppercase();
+        for key in ["KRYPTOS", "CRYPTOGRAPHY"] {
+            let ciphertext = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(key).build()?.encrypt(&letters)?;
+            assert_eq!(Some(CipherType::Enigma), CipherType::best_match(&ciphertext));
+
+            let (plaintext, steps) = CipherCracker::new().crack_explained(&ciphertext)?;
+            assert_eq!(letters, plaintext);
+            assert_eq!(vec![CipherType::Vigenere], steps.iter().map(|step| step.cipher_type).collect::<Vec<_>>());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn failed_vigenere() {
+        // Every letter equally often, which is neither English under any key nor close enough to it for a Gronsfeld key
+        let ciphertext = "ABCDEFGHIJKLMNOPQRSTUVWXYZ".repeat(8);
+        let error = CipherCracker::new().crack_as(&ciphertext, CipherType::Vigenere).unwrap_err();
+        assert!(error.to_string().starts_with("Unable to crack Vigenère ciphertext"));
+    }
+
+    #[test]
+    fn reset() -> anyhow::Result<()> {
+        let mut cracker = CipherCracker::new().strip_framing().with_known_key("LEMON");
+        cracker.set_known_alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ")?;
+        assert_eq!(Some("LEMON"), cracker.key.as_deref());
+        assert!(cracker.alphabet.is_some());
+
+        cracker.reset();
+        assert!(cracker.key.is_none());
+        assert!(cracker.alphabet.is_none());
+        assert!(cracker.strip_framing);
+
+        cracker.set_known_key("ORANGE");
+        assert_eq!(Some("ORANGE"), cracker.key.as_deref());
+
+        Ok(())
+    }
 }
 
 pub mod analysis {
     pub use cipher_utils::*;
 }
 
+#[cfg(feature = "a1z26")]
+pub mod a1z26 {
+    pub use a1z26_cipher::*;
+}
+
+#[cfg(feature = "affine")]
+pub mod affine {
+    pub use affine_cracker::*;
+}
+
 #[cfg(feature = "enigma")]
 pub mod enigma {
     pub use enigma_cracker::*;
@@ -179,6 +679,11 @@ pub mod gronsfeld {
     pub use gronsfeld_cracker::*;
 }
 
+#[cfg(feature = "hex")]
+pub mod hex {
+    pub use hex_cipher::*;
+}
+
 #[cfg(feature = "morse-code")]
 pub mod morse_code {
     pub use morse_code_cipher::*;
@@ -194,6 +699,16 @@ pub mod base64 {
     pub use base64_cipher::*;
 }
 
+#[cfg(feature = "substitution")]
+pub mod substitution {
+    pub use substitution_cracker::*;
+}
+
+#[cfg(feature = "transposition")]
+pub mod transposition {
+    pub use columnar_transposition::*;
+}
+
 #[cfg(feature = "vigenere")]
 pub mod vigenere {
     pub use vigenere_cracker::*;
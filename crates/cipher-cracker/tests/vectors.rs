@@ -0,0 +1,117 @@
+//! Known-answer test vectors for the individual ciphers. Each file in `tests/vectors/` holds one vector per line as
+//! tab-separated `key`, `plaintext`, and `ciphertext` columns, where lines starting with `#` are comments. For each
+//! vector, the plaintext must encrypt to the ciphertext, and the ciphertext must decrypt back to the plaintext.
+
+#![cfg(all(feature = "base64", feature = "enigma", feature = "gronsfeld"))]
+
+use enigma_simulator::EnigmaMachine;
+use gronsfeld::GronsfeldBuilder as _;
+use vigenere_lib::VigenereBuilder as _;
+
+/// A single known-answer test vector.
+struct Vector {
+    /// The key of the cipher, in whatever format the cipher takes, or `-` if it has none.
+    key: String,
+
+    /// The text to encrypt.
+    plaintext: String,
+
+    /// The expected encryption of the plaintext.
+    ciphertext: String,
+}
+
+/// Loads the test vectors of the cipher with the given name from `tests/vectors/<name>.tsv`.
+///
+/// # Parameters
+/// - `name` - The name of the vector file, without its extension.
+///
+/// # Returns
+/// The vectors in the file, in order.
+///
+/// # Errors
+/// If the file can't be read, if it has no vectors, or if a line doesn't have exactly three columns.
+fn vectors(name: &str) -> anyhow::Result<Vec<Vector>> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("vectors").join(format!("{name}.tsv"));
+    let vectors = std::fs::read_to_string(&path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            let [key, plaintext, ciphertext] = line.split('\t').collect::<Vec<_>>()[..] else {
+                anyhow::bail!("Error loading test vectors: Line {} of {} doesn't have exactly three columns", index + 1, path.display());
+            };
+            Ok(Vector {
+                key: key.to_owned(),
+                plaintext: plaintext.to_owned(),
+                ciphertext: ciphertext.to_owned(),
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if vectors.is_empty() {
+        anyhow::bail!("Error loading test vectors: {} has no vectors", path.display());
+    }
+    Ok(vectors)
+}
+
+/// Asserts that every vector in the given file encrypts and decrypts as expected.
+///
+/// # Parameters
+/// - `name` - The name of the vector file, without its extension.
+/// - `encrypt` - Encrypts a plaintext with a key.
+/// - `decrypt` - Decrypts a ciphertext with a key.
+fn check_vectors(name: &str, encrypt: impl Fn(&str, &str) -> anyhow::Result<String>, decrypt: impl Fn(&str, &str) -> anyhow::Result<String>) -> anyhow::Result<()> {
+    for vector in vectors(name)? {
+        assert_eq!(
+            vector.ciphertext,
+            encrypt(&vector.key, &vector.plaintext)?,
+            "{name} vector with key {:?} encrypted incorrectly",
+            vector.key
+        );
+        assert_eq!(
+            vector.plaintext,
+            decrypt(&vector.key, &vector.ciphertext)?,
+            "{name} vector with key {:?} decrypted incorrectly",
+            vector.key
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn vigenere_vectors() -> anyhow::Result<()> {
+    let vigenere = |key: &str| vigenere_lib::Vigenere::new().key(key).alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").build();
+    check_vectors(
+        "vigenere",
+        |key, plaintext| vigenere(key)?.encrypt(plaintext),
+        |key, ciphertext| vigenere(key)?.decrypt(ciphertext),
+    )
+}
+
+#[test]
+fn gronsfeld_vectors() -> anyhow::Result<()> {
+    let gronsfeld = |key: &str| gronsfeld::Gronsfeld::new().key_str(key).alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").build();
+    check_vectors(
+        "gronsfeld",
+        |key, plaintext| gronsfeld(key)?.encrypt(plaintext),
+        |key, ciphertext| gronsfeld(key)?.decrypt(ciphertext),
+    )
+}
+
+#[test]
+fn enigma_vectors() -> anyhow::Result<()> {
+    check_vectors(
+        "enigma",
+        |key, plaintext| Ok(EnigmaMachine::from_key_sheet(key)?.encrypt(plaintext)),
+        |key, ciphertext| Ok(EnigmaMachine::from_key_sheet(key)?.decrypt(ciphertext)),
+    )
+}
+
+#[test]
+fn base64_vectors() -> anyhow::Result<()> {
+    check_vectors(
+        "base64",
+        |_, plaintext| Ok(base64_cipher::Base64::encrypt(plaintext)),
+        |_, ciphertext| base64_cipher::Base64::decrypt(ciphertext),
+    )
+}
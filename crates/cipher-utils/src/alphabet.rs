@@ -4,6 +4,10 @@ use std::ops::RangeBounds;
 pub struct Alphabet {
     characters: Vec<char>,
     cased: bool,
+
+    /// A letter that isn't in the alphabet and the letter it's looked up as, such as `J` and `I` in the 25-letter
+    /// alphabets of square ciphers.
+    merged: Option<(char, char)>,
 }
 
 impl Default for Alphabet {
@@ -16,11 +20,11 @@ impl Alphabet {
     pub fn cased(alphabet: &str) -> anyhow::Result<Self> {
         let mut chars = alphabet.chars().collect::<Vec<_>>();
         chars.dedup();
-        if chars.len() != alphabet.len() {
+        if chars.len() != alphabet.chars().count() {
             anyhow::bail!("Duplicate letter in alphabet: {alphabet}");
         }
 
-        if alphabet.len() != 26 {
+        if alphabet.chars().count() != 26 {
             anyhow::bail!("Invalid alphabet length: {alphabet}");
         }
 
@@ -28,18 +32,22 @@ impl Alphabet {
             anyhow::bail!("Invalid character found in alphabet: {alphabet}");
         }
 
-        Ok(Self { characters: chars, cased: true })
+        Ok(Self {
+            characters: chars,
+            cased: true,
+            merged: None,
+        })
     }
 
     pub fn caseless(alphabet: &str) -> anyhow::Result<Self> {
         let alphabet = alphabet.to_uppercase();
         let mut chars = alphabet.chars().collect::<Vec<_>>();
         chars.dedup();
-        if chars.len() != alphabet.len() {
+        if chars.len() != alphabet.chars().count() {
             anyhow::bail!("Duplicate letter in alphabet: {alphabet}");
         }
 
-        if alphabet.len() != 26 {
+        if alphabet.chars().count() != 26 {
             anyhow::bail!("Invalid alphabet length: {alphabet}");
         }
 
@@ -47,7 +55,75 @@ impl Alphabet {
             anyhow::bail!("Invalid character found in alphabet: {alphabet}");
         }
 
-        Ok(Self { characters: chars, cased: false })
+        Ok(Self {
+            characters: chars,
+            cased: false,
+            merged: None,
+        })
+    }
+
+    /// Creates a caseless keyed alphabet from a keyword. The alphabet starts with the unique letters of the keyword in
+    /// the order they first appear, followed by the remaining letters of the English alphabet in order. For example,
+    /// the keyword `"ZEBRAS"` produces `ZEBRASCDFGHIJKLMNOPQTUVWXY`.
+    ///
+    /// # Parameters
+    /// - `keyword` - The keyword to generate the alphabet from.
+    ///
+    /// # Returns
+    /// The keyed alphabet.
+    ///
+    /// # Errors
+    /// If the keyword contains a character that's not an English letter.
+    pub fn from_keyword(keyword: &str) -> anyhow::Result<Self> {
+        if let Some(character) = keyword.chars().find(|character| !character.is_ascii_alphabetic()) {
+            anyhow::bail!("Invalid character in alphabet keyword: {character:?}");
+        }
+
+        let mut characters = Vec::new();
+        for character in keyword.to_ascii_uppercase().chars().chain('A'..='Z') {
+            if !characters.contains(&character) {
+                characters.push(character);
+            }
+        }
+
+        Ok(Self {
+            characters,
+            cased: false,
+            merged: None,
+        })
+    }
+
+    /// Returns the caseless 25-letter English alphabet without `J`, as used by square-based ciphers such as Playfair,
+    /// Polybius, Bifid, and four-square, which traditionally merge `I` and `J` into a single cell of a 5x5 square.
+    /// Looking up `J` in the returned alphabet gives the index of `I`.
+    ///
+    /// # Returns
+    /// The alphabet `ABCDEFGHIKLMNOPQRSTUVWXYZ`.
+    ///
+    /// # Errors
+    /// This never fails in practice; The `Result` is kept for consistency with the other alphabet constructors.
+    pub fn without_j() -> anyhow::Result<Self> {
+        Self::from_keyword_without_j("")
+    }
+
+    /// Creates a caseless 25-letter keyed alphabet without `J` from a keyword, for filling 5x5 squares. This works like
+    /// `Alphabet::from_keyword()`, except any `J` in the keyword is treated as `I`, and the remaining letters come from
+    /// `Alphabet::without_j()`. For example, the keyword `"JUPITER"` produces `IUPTERABCDFGHKLMNOQSVWXYZ`. The returned
+    /// alphabet merges `J` into `I`, so looking up `J` gives the index of `I`; No other alphabet does this.
+    ///
+    /// # Parameters
+    /// - `keyword` - The keyword to generate the alphabet from.
+    ///
+    /// # Returns
+    /// The keyed 25-letter alphabet.
+    ///
+    /// # Errors
+    /// If the keyword contains a character that's not an English letter.
+    pub fn from_keyword_without_j(keyword: &str) -> anyhow::Result<Self> {
+        let mut alphabet = Self::from_keyword(&keyword.to_ascii_uppercase().replace('J', "I"))?;
+        alphabet.characters.retain(|character| *character != 'J');
+        alphabet.merged = Some(('J', 'I'));
+        Ok(alphabet)
     }
 
     /// Generates an alphabet from a string of text. The created alphabet represents the unique characters
@@ -70,20 +146,53 @@ impl Alphabet {
                 characters.push(character);
             }
         }
-        Self { characters, cased: true }
+        Self {
+            characters,
+            cased: true,
+            merged: None,
+        }
     }
 
+    /// Creates a cased alphabet from a range of ASCII codes, in increasing order. Every code in the range is included,
+    /// which means that ranges below 32 or including 127 contain ASCII control characters; To get only the printable
+    /// characters, use `Alphabet::printable_ascii()`.
+    ///
+    /// # Parameters
+    /// - `range` - The range of ASCII codes to include in the alphabet.
+    ///
+    /// # Returns
+    /// The created alphabet.
+    ///
+    /// # Errors
+    /// If the range includes codes above 127, or if the range is empty or inverted.
     pub fn from_ascii_range<R: RangeBounds<u8> + IntoIterator<Item = u8>>(range: R) -> anyhow::Result<Self> {
-        if range.end_bound() != std::ops::Bound::Included(&127) && range.end_bound() != std::ops::Bound::Excluded(&128) {
+        let is_ascii = match range.end_bound() {
+            std::ops::Bound::Included(end) => *end <= 127,
+            std::ops::Bound::Excluded(end) => *end <= 128,
+            std::ops::Bound::Unbounded => false,
+        };
+        if !is_ascii {
             anyhow::bail!("Error creating alphabet from ASCII range: Upper bound must be at most 127.");
         }
 
-        let mut characters = Vec::new();
-        for code in range {
-            characters.push(code as char);
+        let characters = range.into_iter().map(|code| code as char).collect::<Vec<_>>();
+        if characters.is_empty() {
+            anyhow::bail!("Error creating alphabet from ASCII range: Range is empty.");
         }
 
-        Ok(Self { characters, cased: true })
+        Ok(Self {
+            characters,
+            cased: true,
+            merged: None,
+        })
+    }
+
+    /// Returns a cased alphabet of the 95 printable ASCII characters, from the space (32) to the tilde (126).
+    ///
+    /// # Returns
+    /// The printable ASCII alphabet.
+    pub fn printable_ascii() -> Self {
+        Self::from_ascii_range(32..=126).unwrap()
     }
 
     /// Returns the expected index of coincidence for a truly random string of characters of
@@ -99,10 +208,27 @@ impl Alphabet {
         &self.characters
     }
 
+    /// Generates a random key of the given length, made of letters of this alphabet chosen uniformly and independently.
+    ///
+    /// # Parameters
+    /// - `length` - The number of letters in the key.
+    /// - `rng` - The random number generator to choose the letters with.
+    ///
+    /// # Returns
+    /// The generated key.
+    pub fn random_key(&self, length: usize, rng: &mut impl rand::Rng) -> String {
+        (0..length).map(|_| self.characters[rng.gen_range(0..self.characters.len())]).collect()
+    }
+
     pub fn index_of(&self, mut character: char) -> Option<AlphabetIndex> {
         if !self.cased {
             character = character.to_ascii_uppercase();
         }
+        if let Some((merged, into)) = self.merged {
+            if character == merged {
+                character = into;
+            }
+        }
         self.characters.iter().position(|char| char == &character).map(|index| AlphabetIndex(index as u8 + 1))
     }
 
@@ -114,6 +240,43 @@ impl Alphabet {
         Alphabet::of_cased(&self.characters.iter().chain(other.characters.iter()).collect::<String>())
     }
 
+    /// Returns whether this alphabet is exactly the 26 English letters in some order. The constructors only check that
+    /// an alphabet has 26 alphabetic characters, so alphabets with non-English letters such as `'É'` are valid
+    /// alphabets, but not valid keys for the classic ciphers, which map each of `A-Z` to each other.
+    ///
+    /// # Returns
+    /// Whether this alphabet is a permutation of `A-Z`, ignoring case.
+    pub fn is_standard_permutation(&self) -> bool {
+        let mut letters = self.characters.iter().map(|character| character.to_ascii_uppercase()).collect::<Vec<_>>();
+        letters.sort_unstable();
+        letters.into_iter().eq('A'..='Z')
+    }
+
+    /// Returns the number of positions at which this alphabet and the given alphabet have the same character. For
+    /// example, two identical 26-letter alphabets have 26 fixed points, and swapping two letters of one of them
+    /// leaves 24. This is useful for measuring how close a candidate substitution key is to another key.
+    ///
+    /// # Parameters
+    /// - `other` - The alphabet to compare against.
+    ///
+    /// # Returns
+    /// The number of positions where the two alphabets agree.
+    pub fn fixed_points(&self, other: &Alphabet) -> usize {
+        self.characters.iter().zip(other.characters.iter()).filter(|(first, second)| first == second).count()
+    }
+
+    /// Returns the number of positions at which this alphabet and the given alphabet differ. For two 26-letter
+    /// alphabets, this is `26 - fixed_points`. See `fixed_points()`.
+    ///
+    /// # Parameters
+    /// - `other` - The alphabet to compare against.
+    ///
+    /// # Returns
+    /// The number of positions where the two alphabets disagree, counting any extra characters of the longer alphabet.
+    pub fn distance(&self, other: &Alphabet) -> usize {
+        self.characters.len().max(other.characters.len()) - self.fixed_points(other)
+    }
+
     pub fn shift(&self, shift: u8) -> Self {
         let mut characters = String::new();
         for index in 1..=26 {
@@ -211,3 +374,90 @@ impl std::ops::Sub<u32> for AlphabetIndex {
         AlphabetIndex(((self.0 as i32 - rhs as i32 + 26) % 26) as u8)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::Alphabet;
+
+    #[test]
+    fn from_keyword() {
+        assert_eq!(Alphabet::caseless("ZEBRASCDFGHIJKLMNOPQTUVWXY").unwrap(), Alphabet::from_keyword("zebras").unwrap());
+        assert_eq!(Alphabet::default(), Alphabet::from_keyword("").unwrap());
+        assert!(Alphabet::from_keyword("two words").is_err());
+    }
+
+    #[test]
+    fn without_j() {
+        let alphabet = Alphabet::without_j().unwrap();
+        assert_eq!("ABCDEFGHIKLMNOPQRSTUVWXYZ", alphabet.characters().iter().collect::<String>());
+        assert_eq!(alphabet.index_of('I'), alphabet.index_of('j'));
+
+        let square = Alphabet::from_keyword_without_j("jupiter").unwrap();
+        assert_eq!("IUPTERABCDFGHKLMNOQSVWXYZ", square.characters().iter().collect::<String>());
+        assert_eq!(
+            square.characters().len(),
+            Alphabet::of_cased(&square.characters().iter().collect::<String>()).characters().len()
+        );
+        assert_eq!(Some(1), square.index_of('J').map(|index| *index));
+        assert!(Alphabet::from_keyword_without_j("two words").is_err());
+
+        // Only the square constructors merge J into I, not every alphabet that happens to lack J
+        assert_eq!(Some(10), Alphabet::default().index_of('J').map(|index| *index));
+        let mut without_j = Alphabet::default();
+        without_j.characters.retain(|character| *character != 'J');
+        assert_eq!(None, without_j.index_of('J'));
+        assert_ne!(alphabet, without_j);
+    }
+
+    #[test]
+    fn fixed_points() {
+        let alphabet = Alphabet::default();
+        assert_eq!(26, alphabet.fixed_points(&Alphabet::default()));
+        assert_eq!(0, alphabet.distance(&Alphabet::default()));
+
+        let swapped = Alphabet::caseless("BACDEFGHIJKLMNOPQRSTUVWXYZ").unwrap();
+        assert_eq!(24, alphabet.fixed_points(&swapped));
+        assert_eq!(2, alphabet.distance(&swapped));
+        assert_eq!(0, alphabet.fixed_points(&alphabet.shift(1)));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn from_ascii_range() {
+        assert_eq!(128, Alphabet::from_ascii_range(0..128).unwrap().characters().len());
+        assert_eq!(26, Alphabet::from_ascii_range(b'a'..=b'z').unwrap().characters().len());
+        assert!(Alphabet::from_ascii_range(0..=200).is_err());
+        assert!(Alphabet::from_ascii_range(100..50).is_err());
+        assert!(Alphabet::from_ascii_range(50..50).is_err());
+
+        let printable = Alphabet::printable_ascii();
+        assert_eq!(95, printable.characters().len());
+        assert!(printable.characters().iter().all(|character| !character.is_ascii_control()));
+    }
+
+    #[test]
+    fn random_key() {
+        use rand::SeedableRng as _;
+
+        let alphabet = Alphabet::from_keyword("ZEBRAS").unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let key = alphabet.random_key(1000, &mut rng);
+        assert_eq!(1000, key.chars().count());
+        assert!(key.chars().all(|letter| alphabet.characters().contains(&letter)));
+        assert_eq!(26, Alphabet::of_cased(&key).characters().len());
+        assert_ne!(key, alphabet.random_key(1000, &mut rng));
+        assert_eq!("", alphabet.random_key(0, &mut rng));
+    }
+
+    #[test]
+    fn standard_permutation() {
+        assert!(Alphabet::default().is_standard_permutation());
+        assert!(Alphabet::caseless("zebrascdfghijklmnopqtuvwxy").unwrap().is_standard_permutation());
+        assert!(Alphabet::from_keyword("KRYPTOS").unwrap().is_standard_permutation());
+        assert!(!Alphabet::without_j().unwrap().is_standard_permutation());
+
+        let accented = Alphabet::cased("ABCDEFGHIJKLMNOPQRSTUVWXYÉ").unwrap();
+        assert_eq!(26, accented.characters().len());
+        assert!(!accented.is_standard_permutation());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::cipher::Cipher;
+
+/// The measured encryption and decryption speed of a cipher. See `benchmark_cipher()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThroughputReport {
+    /// The number of times the sample was encrypted and decrypted.
+    pub iterations: usize,
+
+    /// The number of bytes of the sample.
+    pub sample_bytes: usize,
+
+    /// The total time spent encrypting, across every iteration.
+    pub encrypt_time: std::time::Duration,
+
+    /// The total time spent decrypting, across every iteration.
+    pub decrypt_time: std::time::Duration,
+}
+
+impl ThroughputReport {
+    /// Returns the number of bytes of plaintext encrypted per second.
+    pub fn encrypt_bytes_per_second(&self) -> f64 {
+        Self::bytes_per_second(self.sample_bytes * self.iterations, self.encrypt_time)
+    }
+
+    /// Returns the number of bytes of ciphertext decrypted per second. Ciphers whose ciphertext is longer than their
+    /// plaintext, such as encodings, are still measured by the plaintext's length, so that ciphers are compared on how
+    /// quickly they process the same message.
+    pub fn decrypt_bytes_per_second(&self) -> f64 {
+        Self::bytes_per_second(self.sample_bytes * self.iterations, self.decrypt_time)
+    }
+
+    /// Returns the number of bytes processed per second, which is infinite if no measurable time passed.
+    fn bytes_per_second(bytes: usize, time: std::time::Duration) -> f64 {
+        bytes as f64 / time.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for ThroughputReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Encrypt: {:.2} MB/s, Decrypt: {:.2} MB/s ({} iterations of {} bytes)",
+            self.encrypt_bytes_per_second() / 1_000_000.,
+            self.decrypt_bytes_per_second() / 1_000_000.,
+            self.iterations,
+            self.sample_bytes
+        )
+    }
+}
+
+/// Measures how quickly the given cipher encrypts and decrypts the given sample, so that different ciphers can be
+/// compared on the same hardware. The sample is encrypted `iterations` times, and then its ciphertext is decrypted
+/// `iterations` times.
+///
+/// Remember to benchmark in `release` mode, as debug builds can be more than 10x slower.
+///
+/// # Parameters
+/// - `cipher` - The cipher to benchmark.
+/// - `sample` - The plaintext to encrypt. Longer samples give more stable measurements.
+/// - `iterations` - The number of times to encrypt and decrypt the sample.
+///
+/// # Returns
+/// The measured throughput.
+///
+/// # Errors
+/// If `iterations` is `0`, or if the cipher fails to encrypt the sample or decrypt its ciphertext.
+///
+/// # Performance
+/// This is `O(n * iterations)` for a sample of length `n`, times the cost of the cipher itself.
+pub fn benchmark_cipher(cipher: &dyn Cipher, sample: &str, iterations: usize) -> anyhow::Result<ThroughputReport> {
+    if iterations == 0 {
+        anyhow::bail!("Error benchmarking cipher: At least one iteration is required");
+    }
+
+    let ciphertext = cipher.encrypt(sample)?;
+
+    let start = std::time::Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(cipher.encrypt(std::hint::black_box(sample))?);
+    }
+    let encrypt_time = start.elapsed();
+
+    let start = std::time::Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(cipher.decrypt(std::hint::black_box(&ciphertext))?);
+    }
+    let decrypt_time = start.elapsed();
+
+    Ok(ThroughputReport {
+        iterations,
+        sample_bytes: sample.len(),
+        encrypt_time,
+        decrypt_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{benchmark_cipher, cipher::Reverse};
+
+    #[test]
+    fn benchmark() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let report = benchmark_cipher(&Reverse, plaintext, 20)?;
+
+        assert_eq!(20, report.iterations);
+        assert_eq!(plaintext.len(), report.sample_bytes);
+        assert!(report.encrypt_bytes_per_second() > 0.);
+        assert!(report.decrypt_bytes_per_second() > 0.);
+        assert!(report.to_string().starts_with("Encrypt: "));
+
+        assert!(benchmark_cipher(&Reverse, plaintext, 0).is_err());
+
+        Ok(())
+    }
+}
@@ -44,6 +44,36 @@ impl CharacterSet {
     pub fn is_alphanumeric(&self) -> bool {
         self.characters.iter().all(|character| character.is_alphanumeric())
     }
+
+    /// Returns the characters that are in both this set and the given set.
+    pub fn intersection(&self, other: &Self) -> Self {
+        CharacterSet {
+            characters: self.characters.intersection(&other.characters).copied().collect(),
+        }
+    }
+
+    /// Returns the characters that are in this set but not in the given set, such as the characters of a ciphertext
+    /// that fall outside an expected alphabet.
+    pub fn difference(&self, other: &Self) -> Self {
+        CharacterSet {
+            characters: self.characters.difference(&other.characters).copied().collect(),
+        }
+    }
+
+    /// Returns whether this set and the given set have no characters in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.characters.is_disjoint(&other.characters)
+    }
+
+    /// Returns the number of characters in this set.
+    pub fn len(&self) -> usize {
+        self.characters.len()
+    }
+
+    /// Returns whether this set has no characters.
+    pub fn is_empty(&self) -> bool {
+        self.characters.is_empty()
+    }
 }
 
 impl std::ops::Add for CharacterSet {
@@ -68,3 +98,42 @@ lazy_static::lazy_static! {
     pub static ref OCTAL: CharacterSet = CharacterSet::of("01234567");
     pub static ref BINARY: CharacterSet = CharacterSet::of("01");
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::character_set::{self, CharacterSet};
+
+    #[test]
+    fn intersection() {
+        let set = CharacterSet::raw("hello world");
+        assert_eq!(CharacterSet::raw("lo"), set.intersection(&CharacterSet::raw("lots")));
+        assert_eq!(set.intersection(&character_set::HEX), CharacterSet::raw("ed"));
+        assert!(set.intersection(&character_set::NUMERIC).is_empty());
+    }
+
+    #[test]
+    fn difference() {
+        let ciphertext = CharacterSet::raw("aGVsbG8gd29ybGQ=!?");
+        assert_eq!(CharacterSet::raw("!?"), ciphertext.difference(&character_set::BASE_64));
+        assert!(character_set::OCTAL.difference(&character_set::HEX).is_empty());
+        assert_eq!(CharacterSet::raw("89"), character_set::NUMERIC.difference(&character_set::OCTAL));
+    }
+
+    #[test]
+    fn is_disjoint() {
+        assert!(character_set::NUMERIC.is_disjoint(&character_set::ALPHABETIC));
+        assert!(character_set::MORSE.is_disjoint(&character_set::ALPHANUMERIC));
+        assert!(!character_set::HEX.is_disjoint(&character_set::ALPHABETIC));
+        assert!(CharacterSet::raw("").is_disjoint(&character_set::BASE_64));
+    }
+
+    #[test]
+    fn len() {
+        assert_eq!(26, character_set::LOWERCASE_ALPHABETIC.len());
+        assert_eq!(65, character_set::BASE_64.len());
+        assert_eq!(3, CharacterSet::raw("aab cc").len());
+        assert_eq!(0, CharacterSet::raw(" \n").len());
+        assert!(CharacterSet::raw(" \n").is_empty());
+        assert!(!character_set::BINARY.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+/// A cipher with fixed settings that can encrypt and decrypt text. This is the interface shared by the cipher crates,
+/// which allows them to be used interchangeably, such as when chaining ciphers or testing them with `check_cipher()`.
+///
+/// Some ciphers, such as Enigma and Atbash, are reciprocal, meaning encryption and decryption are the same operation.
+/// Most are not; In particular, transposition ciphers such as columnar transposition have a decryption that's genuinely
+/// different from their encryption, so implementors must never alias one to the other unless the cipher is reciprocal.
+pub trait Cipher {
+    /// Encrypts the given plaintext.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    ///
+    /// # Errors
+    /// If the plaintext can't be encrypted with this cipher, such as if it contains characters the cipher doesn't support.
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String>;
+
+    /// Decrypts the given ciphertext.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the ciphertext isn't valid for this cipher.
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String>;
+
+    /// Returns whether this cipher is reciprocal, meaning encrypting and decrypting are the same operation. This is `false`
+    /// unless overridden.
+    fn is_reciprocal(&self) -> bool {
+        false
+    }
+}
+
+/// A "cipher" that reverses the text, which puzzles often use as one layer of several. This is reciprocal. See
+/// `cipher_utils::reverse()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reverse;
+
+impl Cipher for Reverse {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        Ok(crate::reverse(plaintext))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(crate::reverse(ciphertext))
+    }
+
+    fn is_reciprocal(&self) -> bool {
+        true
+    }
+}
+
+/// A sequence of ciphers applied one after another, which is itself a cipher. Encrypting applies each cipher in the order
+/// they were added, and decrypting undoes them in the reverse order.
+#[derive(Default)]
+pub struct CipherPipeline {
+    /// The ciphers of the pipeline, in encryption order.
+    ciphers: Vec<Box<dyn Cipher>>,
+}
+
+impl CipherPipeline {
+    /// Creates a new empty pipeline, which leaves text unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cipher to the end of this pipeline.
+    ///
+    /// # Parameters
+    /// - `cipher` - The cipher to apply after the current ones when encrypting.
+    ///
+    /// # Returns
+    /// This pipeline with the cipher added.
+    pub fn then<C: Cipher + 'static>(mut self, cipher: C) -> Self {
+        self.ciphers.push(Box::new(cipher));
+        self
+    }
+
+    /// Returns the number of ciphers in this pipeline.
+    pub fn len(&self) -> usize {
+        self.ciphers.len()
+    }
+
+    /// Returns whether this pipeline has no ciphers.
+    pub fn is_empty(&self) -> bool {
+        self.ciphers.is_empty()
+    }
+}
+
+impl Cipher for CipherPipeline {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        self.ciphers.iter().try_fold(plaintext.to_owned(), |text, cipher| cipher.encrypt(&text))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        self.ciphers.iter().rev().try_fold(ciphertext.to_owned(), |text, cipher| cipher.decrypt(&text))
+    }
+
+    fn is_reciprocal(&self) -> bool {
+        self.ciphers.iter().all(|cipher| cipher.is_reciprocal()) && self.ciphers.len() <= 1
+    }
+}
+
+/// Checks that the given cipher behaves correctly on the given plaintext. This is a shared test harness for cipher crates;
+/// It checks that decrypting the encrypted plaintext gives back the plaintext, and that encrypting and decrypting are
+/// genuinely different operations unless the cipher says it's reciprocal.
+///
+/// # Parameters
+/// - `cipher` - The cipher to check.
+/// - `plaintext` - A plaintext to check the cipher with. This should be long enough that encrypting and decrypting it
+///   give different results for a non-reciprocal cipher.
+///
+/// # Errors
+/// If the cipher fails to encrypt or decrypt the plaintext, if the round trip doesn't give back the plaintext, or if
+/// a non-reciprocal cipher encrypts and decrypts the plaintext identically.
+pub fn check_cipher<C: Cipher>(cipher: &C, plaintext: &str) -> anyhow::Result<()> {
+    let ciphertext = cipher.encrypt(plaintext)?;
+    let decrypted = cipher.decrypt(&ciphertext)?;
+    if decrypted != plaintext {
+        anyhow::bail!("Decrypting the ciphertext didn't give back the plaintext: Expected {plaintext:?}, got {decrypted:?}");
+    }
+
+    let reciprocal = cipher.decrypt(plaintext)? == ciphertext;
+    if reciprocal != cipher.is_reciprocal() {
+        if reciprocal {
+            anyhow::bail!("Encrypting and decrypting {plaintext:?} gave the same result, but the cipher isn't reciprocal");
+        }
+        anyhow::bail!("Encrypting and decrypting {plaintext:?} gave different results, but the cipher is reciprocal");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cipher::{check_cipher, Cipher, CipherPipeline, Reverse};
+
+    struct Rot13;
+
+    impl Cipher for Rot13 {
+        fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+            Ok(crate::rot13(plaintext))
+        }
+
+        fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+            Ok(crate::rot13(ciphertext))
+        }
+
+        fn is_reciprocal(&self) -> bool {
+            true
+        }
+    }
+
+    struct Caesar(u8);
+
+    impl Cipher for Caesar {
+        fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+            Ok(crate::rotn(plaintext, self.0))
+        }
+
+        fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+            Ok(crate::rotn(ciphertext, 26 - self.0))
+        }
+    }
+
+    /// A broken cipher that aliases decryption to encryption.
+    struct Aliased(u8);
+
+    impl Cipher for Aliased {
+        fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+            Ok(crate::rotn(plaintext, self.0))
+        }
+
+        fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    #[test]
+    fn check_cipher_harness() {
+        let plaintext = include_str!("../tests/letter.txt");
+        assert!(check_cipher(&Rot13, plaintext).is_ok());
+        assert!(check_cipher(&Caesar(3), plaintext).is_ok());
+        assert!(check_cipher(&Aliased(3), plaintext).is_err());
+        assert!(check_cipher(&Aliased(13), plaintext).is_err());
+    }
+
+    #[test]
+    fn reverse_pipeline() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        check_cipher(&Reverse, plaintext)?;
+        check_cipher(&Reverse, "héllo")?;
+        assert_eq!("olléh", Reverse.encrypt("héllo")?);
+
+        let pipeline = CipherPipeline::new().then(Caesar(3)).then(Reverse);
+        assert_eq!(2, pipeline.len());
+        assert_eq!("urrk", pipeline.encrypt("hoor")?);
+        assert_eq!("hoor", pipeline.decrypt("urrk")?);
+        check_cipher(&pipeline, plaintext)?;
+
+        assert!(CipherPipeline::new().is_empty());
+        assert_eq!(plaintext, CipherPipeline::new().encrypt(plaintext)?);
+
+        Ok(())
+    }
+}
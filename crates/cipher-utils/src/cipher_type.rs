@@ -3,16 +3,86 @@ use crate::{
     Analyze,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CipherType {
     Transposition,
     Substitution,
+
+    /// A polyalphabetic cipher over a keyed alphabet, such as Vigenere, Gronsfeld, or running-key. The
+    /// alphabet itself may be scrambled.
+    Vigenere,
+
+    /// An Enigma machine, or any other cipher whose ciphertext is uppercase letters with a near-random index of
+    /// coincidence. Enigma ciphertext is traditionally sent in groups of five letters.
+    Enigma,
+
     Base64,
     Morse,
     Hex,
     Octal,
+
+    /// Letters replaced with their positions in the alphabet, i.e., `8-9` or `8 9` for `HI`.
+    A1Z26,
 }
 
 impl CipherType {
+    /// Returns every cipher type, in declaration order. This is useful for building menus or listing the cipher types
+    /// that can be detected.
+    pub fn all() -> &'static [CipherType] {
+        &[
+            Self::Transposition,
+            Self::Substitution,
+            Self::Vigenere,
+            Self::Enigma,
+            Self::Base64,
+            Self::Morse,
+            Self::Hex,
+            Self::Octal,
+            Self::A1Z26,
+        ]
+    }
+
+    /// Returns the lowercase name of this cipher type, which is how it's displayed and parsed.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Transposition => "transposition",
+            Self::Substitution => "substitution",
+            Self::Vigenere => "vigenere",
+            Self::Enigma => "enigma",
+            Self::Base64 => "base64",
+            Self::Morse => "morse",
+            Self::Hex => "hex",
+            Self::Octal => "octal",
+            Self::A1Z26 => "a1z26",
+        }
+    }
+
+    /// The index of coincidence below which uppercase ciphertext is considered Enigma rather than Vigenere. Uniformly
+    /// random letters have an index of coincidence of about `0.0385`, and Enigma's is indistinguishable from random,
+    /// while Vigenere ciphertext is a little higher. Uppercase Vigenere ciphertext with a key of more than about five
+    /// letters looks random too, so it's detected as Enigma, and callers should try cracking it as Vigenere first.
+    const ENIGMA_MAX_INDEX_OF_COINCIDENCE: f64 = 0.041;
+
+    /// The number of letters needed for uppercase ciphertext to be detected as Enigma. The index of coincidence of
+    /// shorter texts varies too much to tell apart from other ciphers.
+    const ENIGMA_MIN_LETTERS: usize = 60;
+
+    /// Returns whether the given text is made only of numbers in `[1, 26]` separated by whitespace or dashes, at least
+    /// one of which is below `20`. Octal codes have three digits and the decimal codes of printable characters are all
+    /// above `26`, but the hex codes of printable characters start at `20`, so text such as `"20 21 26"` could be the
+    /// hex codes of `" !&"`. A number below `20` without a leading zero can't be the hex code of a printable character.
+    fn is_a1z26(ciphertext: &str) -> bool {
+        let numbers = ciphertext
+            .split(|character: char| character.is_whitespace() || character == '-')
+            .filter(|number| !number.is_empty())
+            .collect::<Vec<_>>();
+        !numbers.is_empty()
+            && numbers
+                .iter()
+                .all(|number| number.len() <= 2 && number.chars().all(|digit| digit.is_ascii_digit()) && (1..=26).contains(&number.parse::<u8>().unwrap()))
+            && numbers.iter().any(|number| !number.starts_with('0') && number.parse::<u8>().unwrap() < 20)
+    }
+
     pub fn best_match(ciphertext: &str) -> Option<Self> {
         let raw = CharacterSet::raw(ciphertext);
 
@@ -20,6 +90,10 @@ impl CipherType {
             return Some(Self::Morse);
         }
 
+        if Self::is_a1z26(ciphertext) {
+            return Some(Self::A1Z26);
+        }
+
         if character_set::OCTAL.is_superset_of(&raw) {
             return Some(Self::Octal);
         }
@@ -29,17 +103,32 @@ impl CipherType {
         }
 
         let alphanumeric = CharacterSet::of(ciphertext);
+        let capitals = ciphertext.chars().filter(|char| char.is_uppercase()).count();
+        let lowercase = ciphertext.chars().filter(|char| char.is_lowercase()).count();
 
-        if character_set::ALPHANUMERIC.is_superset_of(&alphanumeric) {
-            let capitals = ciphertext.chars().filter(|char| char.is_uppercase()).count();
-            let lowercase = ciphertext.chars().filter(|char| char.is_lowercase()).count();
+        // Letters only, and either mostly one case or containing characters Base64 never uses - a keyed
+        // alphabetic cipher. These don't depend on the order of the alphabet, so scrambled alphabets are fine.
+        if alphanumeric.is_alphabetic() {
+            // Only uppercase letters, optionally in groups, with near-random letter frequencies
+            if lowercase == 0
+                && capitals >= Self::ENIGMA_MIN_LETTERS
+                && ciphertext.chars().all(|character| character.is_ascii_uppercase() || character.is_whitespace())
+                && ciphertext.index_of_coincidence() < Self::ENIGMA_MAX_INDEX_OF_COINCIDENCE
+            {
+                return Some(Self::Enigma);
+            }
+
+            let single_case = (capitals as f64) < 0.1 * lowercase as f64 || (lowercase as f64) < 0.1 * capitals as f64;
+            if (single_case || !character_set::BASE_64.is_superset_of(&raw)) && ciphertext.index_of_coincidence() < 0.055 {
+                return Some(Self::Vigenere);
+            }
+        }
 
-            if (capitals as f64) < 0.1 * lowercase as f64 {
-                if (0.6..0.75).contains(&ciphertext.index_of_coincidence()) {
-                    return Some(Self::Transposition);
-                } else {
-                    return Some(Self::Substitution);
-                }
+        if character_set::ALPHANUMERIC.is_superset_of(&alphanumeric) && (capitals as f64) < 0.1 * lowercase as f64 {
+            if (0.6..0.75).contains(&ciphertext.index_of_coincidence()) {
+                return Some(Self::Transposition);
+            } else {
+                return Some(Self::Substitution);
             }
         }
 
@@ -49,4 +138,162 @@ impl CipherType {
 
         None
     }
+
+    /// Identifies the cipher type of the given ciphertext like `CipherType::best_match()`, but tolerates a small
+    /// fraction of stray characters, such as from corruption or copying errors. If all but at most `max_stray_fraction`
+    /// of the ciphertext's non-whitespace characters are morse code, octal, hex, or base 64 characters, then the stray
+    /// characters are removed before matching. Base 64 is only considered for text without spaces, since prose has a
+    /// similar share of punctuation.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext to identify the cipher type of.
+    /// - `max_stray_fraction` - The largest fraction of the non-whitespace characters that may fall outside the
+    ///   character set of an encoding, i.e., `0.02` for 2%.
+    ///
+    /// # Returns
+    /// The best matching cipher type, or `None` if none could be identified.
+    ///
+    /// # Performance
+    /// This is `O(n)`.
+    pub fn best_match_tolerant(ciphertext: &str, max_stray_fraction: f64) -> Option<Self> {
+        let raw = CharacterSet::raw(ciphertext);
+        let length = ciphertext.chars().filter(|character| !character.is_whitespace()).count();
+
+        let mut encodings = vec![&*character_set::MORSE, &*character_set::OCTAL, &*character_set::HEX];
+        if !ciphertext.contains(' ') {
+            encodings.push(&*character_set::BASE_64);
+        }
+
+        for encoding in encodings {
+            let strays = raw.difference(encoding);
+            let stray_count = ciphertext.chars().filter(|character| strays.contains(*character)).count();
+            if !strays.is_empty() && stray_count as f64 <= max_stray_fraction * length as f64 {
+                return Self::best_match(&ciphertext.chars().filter(|character| !strays.contains(*character)).collect::<String>());
+            }
+        }
+
+        Self::best_match(ciphertext)
+    }
+}
+
+impl std::fmt::Display for CipherType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for CipherType {
+    type Err = anyhow::Error;
+
+    /// Parses a cipher type from its name, as displayed by `CipherType`'s `Display` implementation. Parsing is
+    /// case-insensitive and ignores surrounding whitespace.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim().to_lowercase();
+        Self::all()
+            .iter()
+            .find(|cipher_type| cipher_type.name() == name)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unknown cipher type: {name:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use crate::cipher_type::CipherType;
+
+    #[test]
+    fn names() {
+        for cipher_type in CipherType::all() {
+            assert_eq!(*cipher_type, CipherType::from_str(&cipher_type.to_string()).unwrap());
+        }
+        assert_eq!(9, CipherType::all().len());
+        assert_eq!("base64", CipherType::Base64.to_string());
+        assert_eq!(CipherType::Substitution, " Substitution ".parse().unwrap());
+        assert!(CipherType::from_str("rot13").is_err());
+    }
+
+    #[test]
+    fn best_match_tolerant() {
+        let base64 = "VGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZywgYW5kIHRoZW4gaXQgcnVucyBhd2F5IGludG8gdGhlIGZvcmVzdC4gVGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZywgYW5kIHRoZW4gaXQgcnVucyBhd2F5IGludG8gdGhlIGZvcmVzdC4g";
+        assert_eq!(Some(CipherType::Base64), CipherType::best_match(base64));
+
+        // One stray character in every 50 characters, which is 2% of text without whitespace
+        let with_strays = |text: &str, stray: &str| {
+            text.chars()
+                .collect::<Vec<_>>()
+                .chunks(49)
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join(stray)
+        };
+        let noisy_base64 = with_strays(base64, "*");
+        assert_eq!(Some(CipherType::Base64), CipherType::best_match_tolerant(&noisy_base64, 0.02));
+
+        let octal = "124 150 145 040 161 165 151 143 153 040 142 162 157 167 156 040 146 157 170 040 152 165 155 160 163 040 157 166 145 162 040 164 150 145 040 154 141 172 171 040 144 157 147";
+        let noisy_octal = with_strays(octal, "9");
+        assert_eq!(Some(CipherType::Octal), CipherType::best_match(octal));
+        assert_ne!(Some(CipherType::Octal), CipherType::best_match(&noisy_octal));
+        assert_eq!(Some(CipherType::Octal), CipherType::best_match_tolerant(&noisy_octal, 0.05));
+        assert_ne!(Some(CipherType::Octal), CipherType::best_match_tolerant(&noisy_octal, 0.001));
+
+        let morse = "- .... . / --.- ..- .. -.-. -.- / -... .-. --- .-- -. / ..-. --- -..- / .--- ..- -- .--. ... / --- ...- . .-. / - .... . / .-.. .- --.. -.-- / -.. --- --.";
+        assert_eq!(Some(CipherType::Morse), CipherType::best_match_tolerant(&with_strays(morse, "_"), 0.05));
+
+        // Text that's already clean is matched exactly
+        let vigenere = include_str!("../tests/vigenere_letter.txt");
+        assert_eq!(CipherType::best_match(vigenere), CipherType::best_match_tolerant(vigenere, 0.02));
+    }
+
+    #[test]
+    fn scrambled_alphabet_vigenere() {
+        let ciphertext = include_str!("../tests/vigenere_letter.txt");
+        assert_eq!(Some(CipherType::Vigenere), CipherType::best_match(ciphertext));
+
+        let grouped = ciphertext
+            .chars()
+            .filter(|character| character.is_alphabetic())
+            .map(|character| character.to_ascii_uppercase())
+            .collect::<Vec<_>>()
+            .chunks(5)
+            .map(|group| group.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(Some(CipherType::Vigenere), CipherType::best_match(&grouped));
+    }
+
+    #[test]
+    fn grouped_enigma() {
+        // The letters of `letter.txt` on an Enigma I with rotors I II III, reflector B, ring settings 1 1 1, ring positions
+        // 1 1 1, and no plugboard
+        let ciphertext = include_str!("../tests/enigma_letter.txt");
+        assert_eq!(Some(CipherType::Enigma), CipherType::best_match(ciphertext));
+        assert_eq!(Some(CipherType::Enigma), CipherType::best_match(&ciphertext.replace(' ', "")));
+
+        // Too short for its letter frequencies to mean anything
+        assert_ne!(Some(CipherType::Enigma), CipherType::best_match(&ciphertext[..29]));
+        assert_ne!(Some(CipherType::Substitution), CipherType::best_match(ciphertext));
+
+        // Lowercase ciphertext isn't Enigma
+        assert_ne!(Some(CipherType::Enigma), CipherType::best_match(&ciphertext.to_lowercase()));
+    }
+
+    #[test]
+    fn a1z26() {
+        assert_eq!(Some(CipherType::A1Z26), CipherType::best_match("8-5-12-12-15 23-15-18-12-4"));
+        assert_eq!(Some(CipherType::A1Z26), CipherType::best_match("20 8 5 17 21 9 3 11 2 18 15 23 14 6 15 24"));
+        assert_eq!(Some(CipherType::A1Z26), CipherType::best_match("1 2 3 4 5 6 7"));
+
+        // Octal and decimal character codes are out of range
+        assert_eq!(Some(CipherType::Octal), CipherType::best_match("124 150 145 040 161 165 151 143 153"));
+        assert_eq!(Some(CipherType::Hex), CipherType::best_match("84 104 101 32 113 117 105 99 107"));
+        assert_eq!(Some(CipherType::Hex), CipherType::best_match("8 5 12 27"));
+        assert_eq!(Some(CipherType::Hex), CipherType::best_match("8 5 012"));
+
+        // Printable characters have hex codes from 20, so numbers that are all 20 or more may not be A1Z26
+        assert_ne!(Some(CipherType::A1Z26), CipherType::best_match("20 21 26"));
+        assert_eq!(Some(CipherType::Hex), CipherType::best_match("09 0a"));
+        assert_eq!(Some(CipherType::A1Z26), CipherType::best_match("20 21 26 5"));
+    }
 }
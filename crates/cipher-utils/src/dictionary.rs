@@ -1,5 +1,11 @@
+use itertools::Itertools as _;
+
 lazy_static::lazy_static! {
     static ref WORDS: Vec<&'static str> = include_str!("../data/most_common_words.txt").split_whitespace().collect();
+
+    /// The zero-based rank of each word in [WORDS], used for constant-time lookups.
+    static ref WORD_RANKS: std::collections::HashMap<&'static str, usize> =
+        WORDS.iter().enumerate().rev().map(|(rank, word)| (*word, rank)).collect();
 }
 
 /// Returns whether the given word is in the dictionary of the 10,000 most common
@@ -9,10 +15,9 @@ lazy_static::lazy_static! {
 /// - `word` - The word to check whether it's common.
 ///
 /// # Performance
-/// This is `O(n)` for a dictionary of `n` words. The one used here is about 10,000
-/// words long, so this map have significant impacts on performance
+/// This is `O(1)`.
 pub fn is_common_word(word: &str) -> bool {
-    WORDS.contains(&word)
+    WORD_RANKS.contains_key(word)
 }
 
 /// Returns a reference to the `n` most common words in English (see [WORDS] for more
@@ -42,14 +47,9 @@ pub fn n_most_common(n: usize) -> &'static [&'static str] {
 /// The score, in `[0, 1]`, of the given word.
 ///
 /// # Performance
-/// This is `O(n)` for a dictionary of `n` words. The one used here is about 10,000
-/// words long, so this map have significant impacts on performance
+/// This is `O(1)`.
 pub fn commonality_score(word: &str) -> f64 {
-    WORDS
-        .iter()
-        .position(|english| english == &word)
-        .map(|position| 1. - position as f64 / WORDS.len() as f64)
-        .unwrap_or(0.)
+    WORD_RANKS.get(word).map(|position| 1. - *position as f64 / WORDS.len() as f64).unwrap_or(0.)
 }
 
 /// Returns the average [commonality_score] of each word in the given text. "Words"
@@ -62,9 +62,7 @@ pub fn commonality_score(word: &str) -> f64 {
 /// The average commonality score of the words in the given text.
 ///
 /// # Performance
-/// This is `O(n)` for a text with `n` words, and [commonality] score is is `O(k)`
-/// for a dictionary of `k` words, so this is effectively ~`O(10,000n)`. This may
-/// significantly impact performance.
+/// This is `O(n)` for a text with `n` words.
 pub fn average_commonality_score(text: &str) -> f64 {
     let words = text
         .to_lowercase()
@@ -74,3 +72,116 @@ pub fn average_commonality_score(text: &str) -> f64 {
 
     words.iter().map(|word| commonality_score(word)).fold(0., |accumulator, current| accumulator + current) / words.len() as f64
 }
+
+/// Returns the fraction of the words in the given text that are in the dictionary of the 10,000 most common English
+/// words. "Words" are separated by whitespace, punctuation is removed, and words are compared case-insensitively;
+/// Tokens without any letters, such as numbers, aren't counted. This is a cheaper signal than
+/// [average_commonality_score], and is often enough to reject a candidate decryption before scoring it fully.
+///
+/// # Parameters
+/// - `text` - The text to get the word hit rate of.
+///
+/// # Returns
+/// The fraction of the words that are dictionary words in `[0, 1]`, or `0` if the text has no words.
+///
+/// # Performance
+/// This is `O(n)` for a text of length `n`.
+pub fn word_hit_rate(text: &str) -> f64 {
+    let (mut words, mut hits) = (0, 0);
+    for token in text.split_whitespace() {
+        let word = token.chars().filter(|character| character.is_alphabetic()).flat_map(char::to_lowercase).collect::<String>();
+        if word.is_empty() {
+            continue;
+        }
+        words += 1;
+        if is_common_word(&word) {
+            hits += 1;
+        }
+    }
+
+    if words == 0 {
+        return 0.;
+    }
+    hits as f64 / words as f64
+}
+
+/// Inserts spaces into a text that has none, segmenting it into the most likely sequence of dictionary words.
+/// This allows word-based analysis such as [average_commonality_score] to be used on texts like transposition
+/// outputs, where the word boundaries have been removed.
+///
+/// Each word is given a cost based on its rank in the dictionary (following Zipf's law, so that common words are
+/// cheap and rare words are expensive), and the segmentation with the lowest total cost is chosen. Characters that
+/// can't be made part of any dictionary word are kept as single-character "words" with a very high cost.
+///
+/// # Parameters
+/// - `text` - The text to split into words.
+///
+/// # Returns
+/// The given text with spaces inserted between the inferred words. The characters themselves are unchanged.
+///
+/// # Performance
+/// This is `O(nm)` for a text of length `n` and a maximum dictionary word length of `m`.
+pub fn infer_word_splits(text: &str) -> String {
+    let characters = text.chars().collect::<Vec<_>>();
+    let lowercase = characters.iter().map(|character| character.to_ascii_lowercase()).collect::<Vec<_>>();
+
+    let longest_word = WORDS.iter().map(|word| word.len()).max().unwrap_or(1);
+    let log_words = (WORDS.len() as f64).ln();
+    let unknown_cost = (WORDS.len() as f64 * WORDS.len() as f64 * log_words).ln();
+
+    // best[end] holds the lowest cost of segmenting the first `end` characters, and the start of the last word.
+    let mut best: Vec<(f64, usize)> = vec![(0., 0)];
+    for end in 1..=characters.len() {
+        let mut best_split = (best[end - 1].0 + unknown_cost, end - 1);
+        for start in end.saturating_sub(longest_word)..end {
+            let word = lowercase[start..end].iter().collect::<String>();
+            if let Some(rank) = WORD_RANKS.get(word.as_str()) {
+                let cost = best[start].0 + ((*rank + 1) as f64 * log_words).ln();
+                if cost < best_split.0 {
+                    best_split = (cost, start);
+                }
+            }
+        }
+        best.push(best_split);
+    }
+
+    let mut words = Vec::new();
+    let mut end = characters.len();
+    while end > 0 {
+        let start = best[end].1;
+        words.push(characters[start..end].iter().collect::<String>());
+        end = start;
+    }
+
+    words.into_iter().rev().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dictionary;
+
+    #[test]
+    fn infer_word_splits() {
+        assert_eq!("the quick brown fox", dictionary::infer_word_splits("thequickbrownfox"));
+        assert_eq!("THE QUICK BROWN FOX", dictionary::infer_word_splits("THEQUICKBROWNFOX"));
+        assert_eq!("", dictionary::infer_word_splits(""));
+        assert!(dictionary::average_commonality_score(&dictionary::infer_word_splits("thequickbrownfox")) > 0.5);
+    }
+
+    #[test]
+    fn word_hit_rate() {
+        let english = include_str!("../tests/letter.txt");
+        let gibberish = crate::rotn(english, 7);
+
+        let (english, gibberish) = (dictionary::word_hit_rate(english), dictionary::word_hit_rate(&gibberish));
+        assert!(english > 0.7, "Expected most English words to be in the dictionary, got {english}");
+        assert!(gibberish < 0.3, "Expected few gibberish words to be in the dictionary, got {gibberish}");
+
+        assert_eq!(1., dictionary::word_hit_rate("The, quick BROWN fox! 42"));
+        assert_eq!(0.5, dictionary::word_hit_rate("the qxzv"));
+        assert_eq!(0., dictionary::word_hit_rate(""));
+        assert_eq!(0., dictionary::word_hit_rate("12 34"));
+        assert!(dictionary::is_common_word("the") && !dictionary::is_common_word("qxzv"));
+        assert_eq!(1., dictionary::commonality_score("the"));
+    }
+}
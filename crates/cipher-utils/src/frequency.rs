@@ -141,11 +141,85 @@ pub fn mapped_to_english(text: &str) -> String {
         .collect()
 }
 
+/// Guesses the decryption of a monoalphabetic substitution cipher by frequency rank: The most common letter of the text
+/// is replaced with `E`, the next most common with `T`, and so on through `ETAOINSHRDLCUMWFGYPBVKJXQZ`. This is the
+/// textbook first pass at a substitution cipher; It's rarely fully correct, but the most common letters usually are,
+/// which gives a partially readable hint to start from. Letters with the same count are ranked alphabetically.
+///
+/// Letters are counted case-insensitively, casing is preserved, and characters that aren't English letters are left
+/// unchanged.
+///
+/// # Parameters
+/// - `ciphertext` - The text to guess the decryption of.
+///
+/// # Returns
+/// The guessed decryption.
+///
+/// # Performance
+/// This is `O(n)`.
+pub fn rank_substitution_guess(ciphertext: &str) -> String {
+    apply_frequency_mapping(ciphertext, &rank_substitution_mapping(ciphertext))
+}
+
+/// Returns the mapping that `frequency::rank_substitution_guess()` decrypts with, from each uppercase letter of the
+/// ciphertext to the uppercase English letter of the same frequency rank. This is a starting point for solving a
+/// substitution cipher by hand; The mapping can be inspected and corrected letter by letter, and then applied with
+/// `frequency::apply_frequency_mapping()`.
+///
+/// # Parameters
+/// - `ciphertext` - The text to guess the mapping of.
+///
+/// # Returns
+/// The guessed mapping, which has an entry for each letter that appears in the ciphertext.
+///
+/// # Performance
+/// This is `O(n)`.
+pub fn rank_substitution_mapping(ciphertext: &str) -> std::collections::HashMap<char, char> {
+    let counts = ciphertext.chars().filter(char::is_ascii_alphabetic).map(|letter| letter.to_ascii_uppercase()).counts();
+    counts
+        .iter()
+        .sorted_by(|first, other| other.1.cmp(first.1).then(first.0.cmp(other.0)))
+        .map(|(letter, _)| *letter)
+        .zip(ENGLISH_FREQUENCY_ORDER.chars())
+        .collect()
+}
+
+/// Replaces each letter of the given ciphertext according to the given mapping, such as one built by
+/// `frequency::rank_substitution_mapping()` and then refined by hand. Letters are mapped case-insensitively and keep
+/// their casing, and letters without an entry in the mapping are left unchanged, so a partial mapping gives a
+/// partially decrypted text.
+///
+/// # Parameters
+/// - `ciphertext` - The text to map.
+/// - `mapping` - The mapping from ciphertext letters to plaintext letters, in either case.
+///
+/// # Returns
+/// The mapped text.
+///
+/// # Performance
+/// This is `O(n)`.
+pub fn apply_frequency_mapping(ciphertext: &str, mapping: &std::collections::HashMap<char, char>) -> String {
+    ciphertext
+        .chars()
+        .map(|character| {
+            let mapped = mapping.get(&character.to_ascii_uppercase()).or_else(|| mapping.get(&character.to_ascii_lowercase()));
+            match mapped {
+                Some(letter) if character.is_ascii_lowercase() => letter.to_ascii_lowercase(),
+                Some(letter) if character.is_ascii_uppercase() => letter.to_ascii_uppercase(),
+                _ => character,
+            }
+        })
+        .collect()
+}
+
+/// The English letters from most to least common.
+const ENGLISH_FREQUENCY_ORDER: &str = "ETAOINSHRDLCUMWFGYPBVKJXQZ";
+
 /// Returns the English character whose frequency is closest to the given frequency percentage.
 ///
 /// # Parameters
 /// - `frequency` - The frequency to get the closest character of. This should be a small number for
-/// accurate results, i.e., around the range `0.00074 - 0.127`
+///   accurate results, i.e., around the range `0.00074 - 0.127`
 pub fn closest_english_letter(frequency: f64) -> char {
     ENGLISH_LOWERCASE_FREQUENCY
         .iter()
@@ -167,47 +241,242 @@ pub fn closest_english_letter(frequency: f64) -> char {
 /// # Returns
 /// The frequency distribution fitness score, in `(0, 1]`.
 pub fn distribution_score(text: &str) -> f64 {
-    let frequency_map = frequency::of(text);
-    let frequencies = frequency_map.iter().map(|item| item.1).sorted_by(|item, other| item.total_cmp(other)).rev();
-    let english_frequencies = ENGLISH_LOWERCASE_FREQUENCY.values().sorted_by(|item, other| item.total_cmp(other)).rev();
-    let mut differences = Vec::new();
-    for (frequency, english_frequency) in frequencies.zip(english_frequencies) {
-        differences.push(1. - (frequency - english_frequency).abs() / 0.99926);
-    }
+    FrequencyProfile::english().distribution_score(text)
+}
 
-    differences.iter().fold(0., |accumulator, current| accumulator + current) / differences.len() as f64
+/// Returns a frequency map of the bigrams in the given text. Only adjacent pairs of letters are counted; All other
+/// characters are ignored, and the text is treated as case-insensitive. Each bigram is mapped to the percentage
+/// of all bigrams in the text that it makes up.
+///
+/// # Parameters
+/// - `text` - The text to get the bigram frequencies of.
+///
+/// # Performance
+/// This is `O(n)`.
+///
+/// # Returns
+/// A map of lowercase bigrams and the percentage of the text's bigrams they make up.
+pub fn bigrams_of(text: &str) -> std::collections::HashMap<String, f64> {
+    let letters = text.to_lowercase().chars().filter(|character| character.is_alphabetic()).collect::<Vec<_>>();
+    let total = letters.len().saturating_sub(1);
+    letters
+        .windows(2)
+        .map(|bigram| bigram.iter().collect::<String>())
+        .counts()
+        .into_iter()
+        .map(|(bigram, count)| (bigram, count as f64 / total as f64))
+        .collect()
 }
 
+/// Returns a score for how closely the bigram frequencies of the given text match English. This measures the overlap
+/// between the text's frequencies of the most common English bigrams and their expected frequencies, so unlike
+/// `distribution_score()`, it depends on the order of the letters and not just their counts; Shuffling a text will
+/// preserve its letter frequencies but lower its bigram score.
+///
+/// # Parameters
+/// - `text` - The text to get the bigram score of.
+///
+/// # Returns
+/// The bigram fitness score, in `[0, 1]`.
 pub fn bigram_distribution_score(text: &str) -> f64 {
-    let frequency_map = frequency::of(text);
-    let frequencies = frequency_map.iter().map(|item| item.1).sorted_by(|item, other| item.total_cmp(other)).rev();
-    let english_frequencies = ENGLISH_BIGRAM_FREQUENCY.values().sorted_by(|item, other| item.total_cmp(other)).rev();
-    let mut differences = Vec::new();
-    for (frequency, english_frequency) in frequencies.zip(english_frequencies) {
-        differences.push(1. - (frequency - english_frequency).abs() / 0.99926);
-    }
-
-    differences.iter().fold(0., |accumulator, current| accumulator + current) / differences.len() as f64
+    FrequencyProfile::english().bigram_distribution_score(text)
 }
 
+/// Returns a score in `[0, 1]` for how closely the frequency of each letter in the given text matches its frequency in
+/// English. Unlike `distribution_score()`, this compares each letter to its own expected frequency, so substituting
+/// letters lowers the score.
+///
+/// # Parameters
+/// - `text` - The text to get the character score of.
+///
+/// # Returns
+/// The character frequency fitness score, or `0` if the text has no letters.
 pub fn character_score(text: &str) -> f64 {
-    let scores = frequency::of(text)
-        .into_iter()
-        .filter_map(|(character, frequency)| {
-            ENGLISH_FREQUENCY
-                .get(&character)
-                .map(|english_frequency| 1. - (frequency - english_frequency).abs() / 0.99926)
-        })
-        .collect::<Vec<_>>();
+    FrequencyProfile::english().character_score(text)
+}
 
-    if scores.len() == 0 {
-        return 0.;
+/// Returns the log-probability of the given text being English, based on the frequencies of its quadgrams (sequences
+/// of four letters). This is the sum of the base-10 log-probability of each quadgram in the text, where quadgrams that
+/// aren't in the table are given a small floor probability. Higher (less negative) scores are more English-like.
+///
+/// Only letters are considered, case-insensitively, and quadgrams span word boundaries, so texts with and without
+/// spaces score the same. Because the score is a sum, only texts of the same length should be compared directly.
+///
+/// # Parameters
+/// - `text` - The text to score.
+///
+/// # Returns
+/// The quadgram log-probability of the text, which is `0` for texts with fewer than four letters.
+///
+/// # Performance
+/// This is `O(n)`.
+pub fn quadgram_score(text: &str) -> f64 {
+    FrequencyProfile::english().quadgram_score(text)
+}
+
+/// A set of letter, bigram, and quadgram frequency tables for a language, used to score how much a text looks like
+/// that language. The built-in English profile is returned by `FrequencyProfile::english()`, and the scoring functions
+/// of this module, such as `frequency::distribution_score()`, use it. Custom profiles can be created for other
+/// languages or corpora with `FrequencyProfile::new()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyProfile {
+    /// The frequency of each lowercase letter.
+    monograms: std::collections::HashMap<char, f64>,
+
+    /// The frequency of each lowercase bigram. This doesn't need to include every bigram; Only the most common are
+    /// needed for scoring.
+    bigrams: std::collections::HashMap<String, f64>,
+
+    /// The base-10 log-probability of each uppercase quadgram.
+    quadgrams: std::collections::HashMap<[u8; 4], f64>,
+
+    /// The log-probability given to quadgrams that aren't in `quadgrams`.
+    quadgram_floor: f64,
+}
+
+impl FrequencyProfile {
+    /// Creates a new frequency profile from letter and bigram frequencies. The keys are case-insensitive. The profile has
+    /// no quadgram table, so its `quadgram_score()` is the same for all texts of the same length; To add one, use
+    /// `FrequencyProfile::with_quadgram_counts()`.
+    ///
+    /// # Parameters
+    /// - `monograms` - The frequency of each letter, as a fraction of all letters.
+    /// - `bigrams` - The frequency of each bigram, as a fraction of all bigrams.
+    ///
+    /// # Returns
+    /// The created profile.
+    pub fn new(monograms: std::collections::HashMap<char, f64>, bigrams: std::collections::HashMap<String, f64>) -> Self {
+        Self {
+            monograms: monograms
+                .into_iter()
+                .flat_map(|(letter, frequency)| letter.to_lowercase().map(move |letter| (letter, frequency)))
+                .collect(),
+            bigrams: bigrams.into_iter().map(|(bigram, frequency)| (bigram.to_lowercase(), frequency)).collect(),
+            quadgrams: std::collections::HashMap::new(),
+            quadgram_floor: 0.,
+        }
     }
 
-    scores.iter().fold(0., |accumulator, current| accumulator + current) / scores.len() as f64
+    /// Returns this profile with its quadgram table replaced by one built from the given counts. Quadgrams that aren't
+    /// English letters are ignored.
+    ///
+    /// # Parameters
+    /// - `counts` - The number of times each quadgram appears in a corpus.
+    ///
+    /// # Returns
+    /// The profile with the new quadgram table.
+    pub fn with_quadgram_counts(mut self, counts: &std::collections::HashMap<String, usize>) -> Self {
+        let total = counts.values().sum::<usize>().max(1) as f64;
+        self.quadgrams = counts
+            .iter()
+            .filter_map(|(quadgram, count)| {
+                let quadgram: [u8; 4] = quadgram.to_ascii_uppercase().as_bytes().try_into().ok()?;
+                quadgram.iter().all(u8::is_ascii_uppercase).then(|| (quadgram, (*count as f64 / total).log10()))
+            })
+            .collect();
+        self.quadgram_floor = (0.01 / total).log10();
+        self
+    }
+
+    /// Returns the built-in English frequency profile.
+    ///
+    /// # Performance
+    /// This is `O(1)`.
+    pub fn english() -> &'static FrequencyProfile {
+        &ENGLISH_PROFILE
+    }
+
+    /// Returns the frequency of each lowercase letter in this profile.
+    pub fn monograms(&self) -> &std::collections::HashMap<char, f64> {
+        &self.monograms
+    }
+
+    /// Returns the frequency of each lowercase bigram in this profile.
+    pub fn bigrams(&self) -> &std::collections::HashMap<String, f64> {
+        &self.bigrams
+    }
+
+    /// See `frequency::distribution_score()`.
+    pub fn distribution_score(&self, text: &str) -> f64 {
+        let frequency_map = frequency::of(text);
+        let frequencies = frequency_map.iter().map(|item| item.1).sorted_by(|item, other| item.total_cmp(other)).rev();
+        let profile_frequencies = self.monograms.values().sorted_by(|item, other| item.total_cmp(other)).rev();
+        let mut differences = Vec::new();
+        for (frequency, profile_frequency) in frequencies.zip(profile_frequencies) {
+            differences.push(1. - (frequency - profile_frequency).abs() / 0.99926);
+        }
+
+        differences.iter().fold(0., |accumulator, current| accumulator + current) / differences.len() as f64
+    }
+
+    /// See `frequency::character_score()`.
+    pub fn character_score(&self, text: &str) -> f64 {
+        let scores = frequency::of(text)
+            .into_iter()
+            .filter_map(|(character, frequency)| self.monograms.get(&character).map(|profile_frequency| 1. - (frequency - profile_frequency).abs() / 0.99926))
+            .collect::<Vec<_>>();
+
+        if scores.is_empty() {
+            return 0.;
+        }
+
+        scores.iter().fold(0., |accumulator, current| accumulator + current) / scores.len() as f64
+    }
+
+    /// See `frequency::bigram_distribution_score()`.
+    pub fn bigram_distribution_score(&self, text: &str) -> f64 {
+        let frequencies = frequency::bigrams_of(text);
+        let overlap = self
+            .bigrams
+            .iter()
+            .map(|(bigram, profile_frequency)| frequencies.get(bigram).unwrap_or(&0.).min(*profile_frequency))
+            .sum::<f64>();
+
+        overlap / self.bigrams.values().sum::<f64>()
+    }
+
+    /// See `frequency::quadgram_score()`.
+    pub fn quadgram_score(&self, text: &str) -> f64 {
+        let letters = text
+            .bytes()
+            .filter(|byte| byte.is_ascii_alphabetic())
+            .map(|byte| byte.to_ascii_uppercase())
+            .collect::<Vec<_>>();
+        letters
+            .windows(4)
+            .map(|quadgram| {
+                self.quadgrams
+                    .get(&[quadgram[0], quadgram[1], quadgram[2], quadgram[3]])
+                    .copied()
+                    .unwrap_or(self.quadgram_floor)
+            })
+            .sum()
+    }
 }
 
 lazy_static::lazy_static! {
+    /// The base-10 log-probabilities of the 10,000 most common English quadgrams, loaded from `data/english_quadgrams.txt`.
+    static ref ENGLISH_QUADGRAMS: std::collections::HashMap<[u8; 4], f64> = {
+        let total = *ENGLISH_QUADGRAM_TOTAL;
+        include_str!("../data/english_quadgrams.txt")
+            .lines()
+            .filter_map(|line| {
+                let (quadgram, count) = line.split_once(' ')?;
+                let quadgram: [u8; 4] = quadgram.as_bytes().try_into().ok()?;
+                Some((quadgram, (count.parse::<f64>().ok()? / total).log10()))
+            })
+            .collect()
+    };
+
+    /// The total of the quadgram counts in `data/english_quadgrams.txt`.
+    static ref ENGLISH_QUADGRAM_TOTAL: f64 = include_str!("../data/english_quadgrams.txt")
+        .lines()
+        .filter_map(|line| line.split_once(' ')?.1.parse::<f64>().ok())
+        .sum();
+
+    /// The log-probability given to quadgrams that aren't in the quadgram table.
+    static ref ENGLISH_QUADGRAM_FLOOR: f64 = (0.01 / *ENGLISH_QUADGRAM_TOTAL).log10();
+
     static ref ENGLISH_LOWERCASE_FREQUENCY: std::collections::HashMap<char, f64> = std::collections::HashMap::from([
         ('a', 0.082),
         ('b', 0.015),
@@ -236,88 +505,19 @@ lazy_static::lazy_static! {
         ('y', 0.020),
         ('z', 0.00074),
     ]);
-    static ref ENGLISH_UPPERCASE_FREQUENCY: std::collections::HashMap<char, f64> = std::collections::HashMap::from([
-        ('A', 0.082),
-        ('B', 0.015),
-        ('C', 0.028),
-        ('D', 0.043),
-        ('E', 0.127),
-        ('F', 0.022),
-        ('G', 0.020),
-        ('H', 0.061),
-        ('I', 0.070),
-        ('J', 0.0015),
-        ('K', 0.0077),
-        ('L', 0.040),
-        ('M', 0.024),
-        ('N', 0.067),
-        ('O', 0.075),
-        ('P', 0.019),
-        ('Q', 0.00095),
-        ('R', 0.060),
-        ('S', 0.063),
-        ('T', 0.091),
-        ('U', 0.028),
-        ('V', 0.0098),
-        ('W', 0.024),
-        ('X', 0.0015),
-        ('Y', 0.020),
-        ('Z', 0.00074)
-    ]);
-    static ref ENGLISH_FREQUENCY: std::collections::HashMap<char, f64> = std::collections::HashMap::from([
-        ('a', 0.082),
-        ('b', 0.015),
-        ('c', 0.028),
-        ('d', 0.043),
-        ('e', 0.127),
-        ('f', 0.022),
-        ('g', 0.020),
-        ('h', 0.061),
-        ('i', 0.070),
-        ('j', 0.0015),
-        ('k', 0.0077),
-        ('l', 0.040),
-        ('m', 0.024),
-        ('n', 0.067),
-        ('o', 0.075),
-        ('p', 0.019),
-        ('q', 0.00095),
-        ('r', 0.060),
-        ('s', 0.063),
-        ('t', 0.091),
-        ('u', 0.028),
-        ('v', 0.0098),
-        ('w', 0.024),
-        ('x', 0.0015),
-        ('y', 0.020),
-        ('z', 0.00074),
-        ('A', 0.082),
-        ('B', 0.015),
-        ('C', 0.028),
-        ('D', 0.043),
-        ('E', 0.127),
-        ('F', 0.022),
-        ('G', 0.020),
-        ('H', 0.061),
-        ('I', 0.070),
-        ('J', 0.0015),
-        ('K', 0.0077),
-        ('L', 0.040),
-        ('M', 0.024),
-        ('N', 0.067),
-        ('O', 0.075),
-        ('P', 0.019),
-        ('Q', 0.00095),
-        ('R', 0.060),
-        ('S', 0.063),
-        ('T', 0.091),
-        ('U', 0.028),
-        ('V', 0.0098),
-        ('W', 0.024),
-        ('X', 0.0015),
-        ('Y', 0.020),
-        ('Z', 0.00074)
-    ]);
+    static ref ENGLISH_UPPERCASE_FREQUENCY: std::collections::HashMap<char, f64> = ENGLISH_LOWERCASE_FREQUENCY
+        .iter()
+        .map(|(letter, frequency)| (letter.to_ascii_uppercase(), *frequency))
+        .collect();
+    static ref ENGLISH_FREQUENCY: std::collections::HashMap<char, f64> =
+        ENGLISH_LOWERCASE_FREQUENCY.iter().chain(ENGLISH_UPPERCASE_FREQUENCY.iter()).map(|(letter, frequency)| (*letter, *frequency)).collect();
+
+    static ref ENGLISH_PROFILE: FrequencyProfile = FrequencyProfile {
+        monograms: ENGLISH_LOWERCASE_FREQUENCY.clone(),
+        bigrams: ENGLISH_BIGRAM_FREQUENCY.iter().map(|(bigram, frequency)| (bigram.to_string(), *frequency)).collect(),
+        quadgrams: ENGLISH_QUADGRAMS.clone(),
+        quadgram_floor: *ENGLISH_QUADGRAM_FLOOR,
+    };
 
     // https://en.wikipedia.org/wiki/Bigram
     static ref ENGLISH_BIGRAM_FREQUENCY: std::collections::HashMap<&'static str, f64> = std::collections::HashMap::from([
@@ -368,3 +568,128 @@ lazy_static::lazy_static! {
 
 /// A list of all two-letter English words from most to least common.
 pub static TWO_LETTER_ENGLISH_WORDS: &[&str] = &["of", "to, in, it, is, be, as, at, so, we, he, by, or, on, do, if, me, my, up, an, go, no, us", "am"];
+
+#[cfg(test)]
+mod tests {
+    use crate::frequency;
+
+    #[test]
+    fn custom_profile() {
+        let text = include_str!("../tests/letter.txt");
+        let english = frequency::FrequencyProfile::english();
+        // Floating point sums over hash maps depend on iteration order, so these can differ in the last bits
+        assert!((frequency::distribution_score(text) - english.distribution_score(text)).abs() < 1e-9);
+        assert!((frequency::character_score(text) - english.character_score(text)).abs() < 1e-9);
+        assert!((frequency::bigram_distribution_score(text) - english.bigram_distribution_score(text)).abs() < 1e-9);
+        assert_eq!(frequency::quadgram_score(text), english.quadgram_score(text));
+
+        // A "language" where every word is made of Z and Q
+        let profile = frequency::FrequencyProfile::new(
+            std::collections::HashMap::from([('Z', 0.75), ('q', 0.25)]),
+            std::collections::HashMap::from([("ZZ".to_owned(), 0.5), ("zq".to_owned(), 0.25), ("qz".to_owned(), 0.25)]),
+        )
+        .with_quadgram_counts(&std::collections::HashMap::from([("ZZZQ".to_owned(), 3), ("ZZQZ".to_owned(), 1)]));
+        assert_eq!(Some(&0.75), profile.monograms().get(&'z'));
+
+        let zq = "ZZZQ ZZZQ ZZZQ ZZZQ";
+        assert!(profile.character_score(zq) > english.character_score(zq));
+        assert!(profile.distribution_score(zq) > english.distribution_score(zq));
+        assert!(profile.bigram_distribution_score(zq) > 0.9);
+        assert_eq!(0., english.bigram_distribution_score(zq));
+        assert!(profile.quadgram_score(zq) > profile.quadgram_score(text.get(0..zq.len()).unwrap()));
+        assert!(english.quadgram_score(text) > english.quadgram_score(&zq.repeat(text.len() / zq.len())));
+    }
+
+    #[test]
+    fn quadgram_score() {
+        let english = "Attack the east wall of the castle at dawn, and bring every soldier you can find";
+        let random = "Xqzv jkwp mbfy gzxq vplk wjqz hxcv bnmq zkjx fwpq ylgh qxzv tkjm wqpz xvbj fqzk";
+        assert_eq!(english.replace(|character: char| !character.is_alphabetic(), "").len(), random.replace(' ', "").len());
+
+        let english_score = frequency::quadgram_score(english);
+        let random_score = frequency::quadgram_score(random);
+        assert!(english_score < 0.);
+        assert!(
+            english_score > random_score + 100.,
+            "Expected English ({english_score}) to score far above random text ({random_score})"
+        );
+
+        // Spacing and case don't matter
+        assert_eq!(english_score, frequency::quadgram_score(&english.to_uppercase().replace(' ', "")));
+        assert_eq!(0., frequency::quadgram_score("THE"));
+    }
+
+    #[test]
+    fn rank_substitution_guess() {
+        let text = include_str!("../tests/letter.txt");
+        let key = "QWERTYUIOPASDFGHJKLZXCVBNM".as_bytes();
+        let ciphertext = text
+            .chars()
+            .map(|character| match character {
+                'A'..='Z' => key[(character as u8 - b'A') as usize] as char,
+                'a'..='z' => key[(character as u8 - b'a') as usize].to_ascii_lowercase() as char,
+                _ => character,
+            })
+            .collect::<String>();
+
+        let guess = frequency::rank_substitution_guess(&ciphertext);
+        assert_eq!(text.len(), guess.len());
+        assert!(guess
+            .chars()
+            .zip(text.chars())
+            .all(|(guessed, original)| guessed.is_ascii_alphabetic() == original.is_ascii_alphabetic() && guessed.is_uppercase() == original.is_uppercase()));
+
+        // The most common letters are ranked correctly, so a good share of the text is already right
+        let letters = text.chars().filter(char::is_ascii_alphabetic).count();
+        let correct = guess
+            .chars()
+            .zip(text.chars())
+            .filter(|(guessed, original)| original.is_ascii_alphabetic() && guessed == original)
+            .count();
+        assert!(
+            correct as f64 > 0.3 * letters as f64,
+            "Expected a partially correct guess, got {correct} of {letters} letters: {guess}"
+        );
+        assert!(correct < letters);
+
+        assert_eq!("Eete, t!", frequency::rank_substitution_guess("Xxqx, q!"));
+    }
+
+    #[test]
+    fn apply_frequency_mapping() {
+        let ciphertext = "Xxqx, qzx Xqz!";
+        let mut mapping = frequency::rank_substitution_mapping(ciphertext);
+        assert_eq!(Some(&'E'), mapping.get(&'X'));
+        assert_eq!(frequency::rank_substitution_guess(ciphertext), frequency::apply_frequency_mapping(ciphertext, &mapping));
+
+        // Correcting one letter by hand changes only that letter
+        mapping.insert('Z', 'H');
+        assert_eq!("Eete, the Eth!", frequency::apply_frequency_mapping(ciphertext, &mapping));
+
+        // Only mapped letters change, in either case
+        let partial = std::collections::HashMap::from([('X', 'e'), ('q', 'T')]);
+        assert_eq!("Eete, tze Etz!", frequency::apply_frequency_mapping(ciphertext, &partial));
+        assert_eq!(ciphertext, frequency::apply_frequency_mapping(ciphertext, &std::collections::HashMap::new()));
+    }
+
+    #[test]
+    fn bigram_score_depends_on_order() {
+        let text = include_str!("../tests/letter.txt");
+
+        // Reorder the letters with a fixed stride, which keeps every letter's frequency but destroys the bigrams.
+        let letters = text.chars().filter(|character| character.is_alphabetic()).collect::<Vec<_>>();
+        let stride = [7, 11, 13].into_iter().find(|stride| letters.len() % stride != 0).unwrap();
+        let shuffled = (0..letters.len()).map(|index| letters[index * stride % letters.len()]).collect::<String>();
+
+        assert_eq!(frequency::counts(&letters.iter().collect::<String>()), frequency::counts(&shuffled));
+        assert_eq!(frequency::distribution_score(&letters.iter().collect::<String>()), frequency::distribution_score(&shuffled));
+
+        let english_score = frequency::bigram_distribution_score(text);
+        let shuffled_score = frequency::bigram_distribution_score(&shuffled);
+        assert!(english_score > 0.7, "Expected English to score highly, got {english_score}");
+        assert!(
+            english_score > shuffled_score + 0.2,
+            "Expected English ({english_score}) to score well above shuffled text ({shuffled_score})"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+/// A natural language that texts can be detected as. See `detect_language()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    German,
+    French,
+}
+
+impl Language {
+    /// All of the languages that can be detected.
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::French];
+
+    /// Returns the 100 most common letter trigrams of this language, from most to least common. Trigrams are counted across
+    /// word boundaries, with all non-letters removed, accents stripped, and umlauts and `ß` written as `ae`, `oe`, `ue`,
+    /// and `ss`, as they would be on an Enigma machine.
+    ///
+    /// # Returns
+    /// The trigram profile of this language.
+    pub fn trigrams(&self) -> &'static [&'static str] {
+        match self {
+            Self::English => ENGLISH_TRIGRAMS,
+            Self::German => GERMAN_TRIGRAMS,
+            Self::French => FRENCH_TRIGRAMS,
+        }
+    }
+}
+
+/// The number of a text's most common trigrams that are compared against each language's profile.
+const TEXT_PROFILE_LENGTH: usize = 300;
+
+/// The minimum number of letters needed in a text to detect its language.
+const MINIMUM_LETTERS: usize = 20;
+
+/// Detects the natural language of the given text by comparing its most common trigrams against the trigram profiles of
+/// each known language, using the "out-of-place" measure: The further a language's common trigrams are from the same
+/// rank in the text, the less likely the text is that language.
+///
+/// Only the letters of the text are considered, so this works for texts without spaces, such as decrypted Enigma
+/// messages, though longer texts give more reliable results.
+///
+/// # Parameters
+/// - `text` - The text to detect the language of.
+///
+/// # Returns
+/// The most likely language of the text, or `None` if the text is too short or doesn't resemble any known language,
+/// such as a ciphertext.
+///
+/// # Performance
+/// This is `O(n)` for a text of length `n`.
+pub fn detect_language(text: &str) -> Option<Language> {
+    let letters = normalize(text);
+    if letters.len() < MINIMUM_LETTERS {
+        return None;
+    }
+
+    let mut counts = std::collections::HashMap::new();
+    for trigram in letters.windows(3) {
+        *counts.entry(trigram).or_insert(0usize) += 1;
+    }
+    let mut ranked = counts.into_iter().collect::<Vec<_>>();
+    ranked.sort_by(|first, other| other.1.cmp(&first.1).then(first.0.cmp(other.0)));
+    let ranks = ranked
+        .into_iter()
+        .take(TEXT_PROFILE_LENGTH)
+        .enumerate()
+        .map(|(rank, (trigram, _))| (trigram.iter().collect::<String>(), rank))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let (language, distance) = Language::ALL
+        .into_iter()
+        .map(|language| {
+            let distance = language
+                .trigrams()
+                .iter()
+                .enumerate()
+                .map(|(rank, trigram)| ranks.get(*trigram).map_or(TEXT_PROFILE_LENGTH, |text_rank| rank.abs_diff(*text_rank)))
+                .sum::<usize>();
+            (language, distance)
+        })
+        .min_by_key(|(_, distance)| *distance)?;
+
+    // Texts that share few common trigrams with every language, like ciphertexts, are unknown.
+    let maximum_distance = language.trigrams().len() * TEXT_PROFILE_LENGTH;
+    (distance < maximum_distance * 3 / 4).then_some(language)
+}
+
+/// Converts the given text into lowercase ASCII letters for trigram counting, removing everything else.
+fn normalize(text: &str) -> Vec<char> {
+    let mut letters = Vec::new();
+    for character in text.to_lowercase().chars() {
+        match character {
+            'a'..='z' => letters.push(character),
+            'ä' | 'æ' => letters.extend(['a', 'e']),
+            'ö' | 'œ' => letters.extend(['o', 'e']),
+            'ü' => letters.extend(['u', 'e']),
+            'ß' => letters.extend(['s', 's']),
+            'à' | 'â' | 'á' => letters.push('a'),
+            'ç' => letters.push('c'),
+            'è' | 'é' | 'ê' | 'ë' => letters.push('e'),
+            'î' | 'ï' | 'í' => letters.push('i'),
+            'ô' | 'ó' => letters.push('o'),
+            'ù' | 'û' | 'ú' => letters.push('u'),
+            'ÿ' => letters.push('y'),
+            _ => {}
+        }
+    }
+    letters
+}
+
+// Generated from English, German, and French software documentation and licenses.
+static ENGLISH_TRIGRAMS: &[&str] = &[
+    "the", "ion", "ing", "tio", "and", "ent", "for", "int", "sth", "ter", "ati", "eth", "all", "est", "use", "ate", "nth", "ere", "tha", "ons", "res", "fth", "ess", "hes", "con",
+    "ile", "thi", "her", "rea", "his", "fil", "ted", "ect", "ont", "are", "tin", "ith", "pro", "sin", "ame", "ers", "oft", "set", "ste", "din", "edi", "oth", "not", "wit", "cti",
+    "ort", "hen", "ist", "hec", "hat", "com", "eco", "str", "ese", "ore", "ver", "ret", "tth", "sto", "lin", "tor", "sta", "ifi", "ble", "ine", "ert", "ead", "tur", "nte", "spe",
+    "rin", "ser", "rth", "tem", "cal", "edt", "sed", "hef", "abl", "ces", "men", "pec", "les", "nam", "ede", "yst", "cha", "nst", "efi", "eci", "era", "esa", "out", "orm", "val",
+];
+
+static GERMAN_TRIGRAMS: &[&str] = &[
+    "die", "ein", "der", "nde", "end", "ich", "den", "ion", "ver", "tei", "ers", "erd", "rde", "ten", "ine", "che", "ert", "ate", "ung", "nge", "ste", "dat", "ere", "ist", "sch",
+    "ter", "ier", "tio", "ess", "nen", "ies", "ens", "wer", "und", "gen", "ebe", "ndi", "ese", "ind", "ben", "ren", "wir", "cht", "nda", "das", "aus", "ird", "sse", "all", "zei",
+    "ent", "enn", "ena", "eit", "ell", "and", "ede", "ene", "sta", "eic", "rei", "enu", "erw", "enw", "omp", "wen", "kom", "mit", "nte", "ang", "hen", "sie", "ern", "lte", "rte",
+    "ber", "tdi", "mpr", "era", "ass", "sei", "sio", "auf", "nst", "ied", "von", "ati", "men", "lle", "her", "ode", "ser", "ite", "etz", "rwe", "pti", "one", "ket", "for", "eda",
+];
+
+static FRENCH_TRIGRAMS: &[&str] = &[
+    "ion", "les", "ent", "tio", "que", "res", "ede", "ess", "est", "com", "fic", "des", "ier", "ich", "eco", "chi", "our", "sde", "tre", "ati", "par", "con", "lis", "pre", "ons",
+    "ers", "men", "ont", "hie", "eme", "the", "uti", "omp", "eur", "nde", "til", "dec", "esp", "ire", "ela", "ele", "ili", "esd", "pou", "ser", "dan", "and", "sle", "ond", "nte",
+    "ces", "ise", "lle", "ter", "ssi", "del", "rle", "rde", "sio", "onn", "ver", "ans", "ant", "ese", "cha", "pti", "epa", "mpr", "for", "ere", "ble", "nes", "sse", "pas", "tde",
+    "omm", "opt", "efi", "une", "ite", "cti", "tes", "aqu", "uel", "ile", "esc", "aut", "iqu", "ave", "sta", "son", "ees", "uet", "eso", "ale", "qui", "spa", "ste", "dep", "def",
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{detect_language, Language};
+
+    static GERMAN: &str = "Die Enigma ist eine Rotor-Schlüsselmaschine, die im Zweiten Weltkrieg zur Verschlüsselung des \
+        Nachrichtenverkehrs des deutschen Militärs verwendet wurde. Auch andere Dienststellen, wie Polizei, Geheimdienste, \
+        diplomatische Dienste, Reichspost und Reichsbahn setzten sie zur geheimen Kommunikation ein. Trotz mannigfaltiger \
+        Verbesserungen der Verschlüsselungsqualität der Maschine gelang es den Alliierten, die deutschen Funksprüche nahezu \
+        kontinuierlich zu entziffern.";
+
+    static FRENCH: &str = "Enigma est une machine électromécanique portative servant au chiffrement et au déchiffrement de \
+        l'information. Elle fut inventée par l'Allemand Arthur Scherbius, reprenant un brevet du Néerlandais Hugo Koch, datant \
+        de 1919. Elle a été utilisée principalement par les forces armées allemandes pendant la Seconde Guerre mondiale, et le \
+        déchiffrement des messages a joué un rôle important dans l'issue de la guerre.";
+
+    #[test]
+    fn detect_languages() {
+        assert_eq!(Some(Language::English), detect_language(include_str!("../tests/letter.txt")));
+        assert_eq!(Some(Language::German), detect_language(GERMAN));
+        assert_eq!(Some(Language::French), detect_language(FRENCH));
+
+        // Without spaces or punctuation, as in a decrypted Enigma message
+        let enigma_style = GERMAN.to_uppercase().replace(|character: char| !character.is_alphabetic(), "");
+        assert_eq!(Some(Language::German), detect_language(&enigma_style));
+
+        assert_eq!(None, detect_language("Hallo"));
+        assert_eq!(None, detect_language(include_str!("../tests/vigenere_letter.txt")));
+    }
+}
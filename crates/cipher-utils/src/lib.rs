@@ -1,14 +1,154 @@
 pub mod alphabet;
+mod benchmark;
 pub mod character_set;
+pub mod cipher;
 pub mod cipher_type;
 pub mod dictionary;
+pub mod progress;
 pub mod score;
 pub mod tabula_recta;
+pub mod unknown_char_policy;
 
 /// The `frequency` module, providing various utilities relating to frequency analysis.
 pub mod frequency;
 
+/// Natural language detection, for choosing which language's statistics to score texts with.
+pub mod language;
+
+pub use benchmark::{benchmark_cipher, ThroughputReport};
+pub use language::detect_language;
+pub use score::englishness;
+
+/// Shifts each English letter of the given text forward by 13, preserving case and leaving all other characters
+/// unchanged. ROT13 is its own inverse, so this both encrypts and decrypts.
+///
+/// # Parameters
+/// - `text` - The text to shift.
+///
+/// # Returns
+/// The shifted text.
+pub fn rot13(text: &str) -> String {
+    rotn(text, 13)
+}
+
+/// Reverses the given text by Unicode scalar value, so multibyte characters such as `'é'` stay intact. Reversing is its
+/// own inverse.
+///
+/// # Parameters
+/// - `text` - The text to reverse.
+///
+/// # Returns
+/// The reversed text.
+pub fn reverse(text: &str) -> String {
+    text.chars().rev().collect()
+}
+
+/// Shifts each English letter of the given text forward by `n`, wrapping from `Z` to `A`, preserving case and leaving
+/// all other characters unchanged. This is a Caesar cipher over `A-Z` and `a-z`; To undo a shift of `n`, shift by
+/// `26 - n`.
+///
+/// # Parameters
+/// - `text` - The text to shift.
+/// - `n` - The amount to shift each letter by. Shifts of 26 or more wrap around.
+///
+/// # Returns
+/// The shifted text.
+pub fn rotn(text: &str, n: u8) -> String {
+    let n = n % 26;
+    text.chars()
+        .map(|character| match character {
+            'A'..='Z' => ((character as u8 - b'A' + n) % 26 + b'A') as char,
+            'a'..='z' => ((character as u8 - b'a' + n) % 26 + b'a') as char,
+            _ => character,
+        })
+        .collect()
+}
+
+/// Removes the framing that ciphertext files often wrap around the ciphertext itself, which would otherwise skew
+/// frequency analysis and cipher type detection. This removes:
+///
+/// - Leading label lines, such as `"Ciphertext:"` or `"Message 2 of 3:"`
+/// - A label prefix on the first line of the ciphertext, such as the `"Ciphertext: "` of `"Ciphertext: KDZV..."`
+/// - Line numbers such as `"1. "`, `"2) "`, `"3: "`, or `"4| "`, but only if every line has one
+///
+/// Whitespace is also normalized: Runs of spaces and tabs are collapsed into a single space, lines are trimmed, and
+/// blank lines are removed. A label is up to four words of letters and digits, starting with a letter and followed by
+/// a colon.
+///
+/// # Parameters
+/// - `text` - The text to remove the framing from.
+///
+/// # Returns
+/// The text without its framing, with its lines separated by `\n`.
+pub fn strip_framing(text: &str) -> String {
+    let lines = text
+        .lines()
+        .map(|line| line.split_whitespace().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let mut lines = lines.into_iter().skip_while(|line| label_length(line) == Some(line.len())).collect::<Vec<_>>();
+
+    if let Some(first) = lines.first_mut() {
+        if let Some(length) = label_length(first) {
+            *first = first[length..].trim_start().to_owned();
+        }
+    }
+
+    if !lines.is_empty() && lines.iter().all(|line| line_number_length(line).is_some()) {
+        for line in &mut lines {
+            *line = line[line_number_length(line).unwrap()..].to_owned();
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the length of the label at the start of the given line, including its colon, or `None` if the line doesn't
+/// start with a label. See `strip_framing()`.
+fn label_length(line: &str) -> Option<usize> {
+    let colon = line.find(':')?;
+    let label = &line[..colon];
+    let is_label = label.starts_with(|character: char| character.is_ascii_alphabetic())
+        && label.chars().all(|character| character.is_ascii_alphanumeric() || character == ' ')
+        && label.split(' ').count() <= 4;
+    is_label.then_some(colon + 1)
+}
+
+/// Returns the length of the line number at the start of the given line, including its punctuation and the space after
+/// it, or `None` if the line doesn't start with a line number. See `strip_framing()`.
+fn line_number_length(line: &str) -> Option<usize> {
+    let digits = line.chars().take_while(|character| character.is_ascii_digit()).count();
+    let rest = &line[digits..];
+    (digits > 0 && [". ", ") ", ": ", "| "].iter().any(|separator| rest.starts_with(separator))).then_some(digits + 2)
+}
+
+/// Normalizes the given text so that ciphers working over plain letters can handle it: Compatibility forms such as
+/// full-width letters (`'Ａ'`) and ligatures (`'ﬁ'`) are decomposed into their plain equivalents, and if
+/// `strip_diacritics` is set, accented letters lose their diacritics, so `"Café"` becomes `"Cafe"`. Characters with no
+/// plain decomposition, such as `'ß'` or non-Latin letters, are kept as they are.
+///
+/// This is only available with the `normalization` feature.
+///
+/// # Parameters
+/// - `text` - The text to normalize.
+/// - `strip_diacritics` - Whether to remove accents, for ciphers that only handle `A-Z`. Ciphers with their own
+///   alphabet, such as one containing `'É'`, should keep them.
+///
+/// # Returns
+/// The NFKD normalization of the text with combining marks removed if `strip_diacritics` is set, or its NFKC
+/// normalization otherwise.
+#[cfg(feature = "normalization")]
+pub fn normalize_for_cipher(text: &str, strip_diacritics: bool) -> String {
+    use unicode_normalization::{char::is_combining_mark, UnicodeNormalization as _};
+    if strip_diacritics {
+        text.nfkd().filter(|character| !is_combining_mark(*character)).collect()
+    } else {
+        text.nfkc().collect()
+    }
+}
+
 use alphabet::Alphabet;
+use itertools::Itertools as _;
 
 pub trait Analyze {
     fn index_of_coincidence(&self) -> f64;
@@ -18,8 +158,115 @@ pub trait Analyze {
         self.index_of_coincidence()
     }
 
+    /// Returns the Shannon entropy of this text's characters, in bits per character. Whitespace is ignored. Plain
+    /// English letters have an entropy of around 4.1 bits, while uniformly random letters approach `log2(26) ≈ 4.7`.
+    ///
+    /// # Returns
+    /// The entropy, or `0` for an empty text.
+    ///
+    /// # Performance
+    /// This is `O(n)`.
+    fn entropy(&self) -> f64;
+
     /// Returns an `Alphabet` containing the unique characters of this string in-order.
     fn alphabet(&self) -> Alphabet;
+
+    /// Returns the average index of coincidence of this text's columns for each candidate period from 1 to
+    /// `max_period`. For a given period `p`, the letters of the text are split into `p` columns, where
+    /// column `i` holds every `p`th letter starting from letter `i`, and the index of coincidence of each
+    /// column is averaged. Non-alphabetic characters are ignored.
+    ///
+    /// For a polyalphabetic cipher such as Vigenere, each column of the correct period is a simple shift
+    /// of the plaintext, so the curve peaks near English's index of coincidence at the key length and its
+    /// multiples, and stays near random everywhere else.
+    ///
+    /// # Parameters
+    /// - `max_period` - The largest period to compute the column index of coincidence for.
+    ///
+    /// # Returns
+    /// A list of `(period, average column index of coincidence)` pairs, in order of increasing period.
+    ///
+    /// # Performance
+    /// This is `O(nm)` for a text of length `n` and a `max_period` of `m`.
+    fn period_ioc_profile(&self, max_period: usize) -> Vec<(usize, f64)>;
+
+    /// Returns the result of Friedman's kappa test for each candidate period from 1 to `max_period`. For a given period
+    /// `p`, the kappa value is the fraction of letters that match the letter `p` positions later, comparing the text
+    /// against a copy of itself shifted by `p`. Non-alphabetic characters are ignored and letters are compared
+    /// case-insensitively.
+    ///
+    /// For a polyalphabetic cipher such as Vigenere, letters a multiple of the key length apart are encrypted with the
+    /// same key letter, so they coincide as often as English letters do, and the kappa value peaks near English's index
+    /// of coincidence. Since every letter is compared rather than just the letters in a column, this is less sensitive
+    /// to noise than `period_ioc_profile()`, and gives a second, independent estimate of the key length.
+    ///
+    /// # Parameters
+    /// - `max_period` - The largest period to compute the kappa value for.
+    ///
+    /// # Returns
+    /// A list of `(period, kappa)` pairs, in order of increasing period. Periods that are at least as long as the text
+    /// have a kappa value of `0`.
+    ///
+    /// # Performance
+    /// This is `O(nm)` for a text of length `n` and a `max_period` of `m`.
+    fn kappa_test(&self, max_period: usize) -> Vec<(usize, f64)>;
+
+    /// Returns the blocks of this text that appear more than once, along with how many times each appears. The text is
+    /// split into consecutive, non-overlapping blocks of `block_size` characters, ignoring whitespace, and a trailing
+    /// block that's shorter than `block_size` is dropped.
+    ///
+    /// Repeated blocks show that the same plaintext block was encrypted to the same ciphertext block, as with a codebook
+    /// or an ECB-like scheme, which a cipher that mixes in its position, such as Vigenere, rarely does.
+    ///
+    /// # Parameters
+    /// - `block_size` - The number of characters in each block.
+    ///
+    /// # Returns
+    /// A list of `(block, count)` pairs for the blocks that appear more than once, from most to least frequent, with
+    /// ties in the order the blocks first appear. This is empty if `block_size` is `0`.
+    ///
+    /// # Performance
+    /// This is `O(n)`.
+    fn repeated_blocks(&self, block_size: usize) -> Vec<(String, usize)>;
+
+    /// Returns the number of character positions at which this text and the given text differ. Only the first
+    /// `n` characters are compared, where `n` is the length of the shorter text.
+    ///
+    /// # Parameters
+    /// - `other` - The text to compare against.
+    ///
+    /// # Returns
+    /// The number of positions with differing characters.
+    fn hamming_letters(&self, other: &str) -> usize;
+
+    /// Returns the number of bits that differ between the bytes of this text and the bytes of the given text.
+    /// Only the first `n` bytes are compared, where `n` is the byte length of the shorter text.
+    ///
+    /// # Parameters
+    /// - `other` - The text to compare against.
+    ///
+    /// # Returns
+    /// The bitwise Hamming distance between the two texts.
+    fn hamming_bits(&self, other: &str) -> usize;
+
+    /// Returns the index of mutual coincidence of this text and the given text, which is the probability that the two
+    /// texts have the same letter at the same position. Non-alphabetic characters are ignored and letters are compared
+    /// case-insensitively, and only the first `n` letters are compared, where `n` is the number of letters in the text
+    /// with fewer letters.
+    ///
+    /// Two texts encrypted with the same key at the same alignment ("in depth") match wherever their plaintexts do, so
+    /// their index is near English's index of coincidence, while misaligned texts stay near random. Sliding one text
+    /// against the other finds the offset that puts them in depth.
+    ///
+    /// # Parameters
+    /// - `other` - The text to compare against.
+    ///
+    /// # Returns
+    /// The index of mutual coincidence, or `0` if either text has no letters.
+    ///
+    /// # Performance
+    /// This is `O(n)`.
+    fn mutual_index_of_coincidence(&self, other: &str) -> f64;
 }
 
 impl<T: AsRef<str>> Analyze for T {
@@ -49,7 +296,282 @@ impl<T: AsRef<str>> Analyze for T {
         ic
     }
 
+    fn entropy(&self) -> f64 {
+        let counts = self.as_ref().chars().filter(|character| !character.is_whitespace()).counts();
+        let total = counts.values().sum::<usize>() as f64;
+        counts
+            .values()
+            .map(|count| {
+                let probability = *count as f64 / total;
+                -probability * probability.log2()
+            })
+            .sum()
+    }
+
     fn alphabet(&self) -> Alphabet {
         Alphabet::of_cased(self.as_ref())
     }
+
+    fn period_ioc_profile(&self, max_period: usize) -> Vec<(usize, f64)> {
+        let letters = self.as_ref().chars().filter(|character| character.is_alphabetic()).collect::<Vec<_>>();
+        (1..=max_period)
+            .map(|period| {
+                let mut columns = vec![String::new(); period];
+                for (index, letter) in letters.iter().enumerate() {
+                    columns[index % period].push(*letter);
+                }
+                let average = columns.iter().map(|column| column.index_of_coincidence()).sum::<f64>() / period as f64;
+                (period, average)
+            })
+            .collect()
+    }
+
+    fn kappa_test(&self, max_period: usize) -> Vec<(usize, f64)> {
+        let letters = self
+            .as_ref()
+            .chars()
+            .filter(|character| character.is_alphabetic())
+            .flat_map(|letter| letter.to_lowercase())
+            .collect::<Vec<_>>();
+        (1..=max_period)
+            .map(|period| {
+                if period >= letters.len() {
+                    return (period, 0.);
+                }
+                let coincidences = letters.iter().zip(&letters[period..]).filter(|(first, second)| first == second).count();
+                (period, coincidences as f64 / (letters.len() - period) as f64)
+            })
+            .collect()
+    }
+
+    fn repeated_blocks(&self, block_size: usize) -> Vec<(String, usize)> {
+        if block_size == 0 {
+            return Vec::new();
+        }
+
+        let characters = self.as_ref().chars().filter(|character| !character.is_whitespace()).collect::<Vec<_>>();
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut indices: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        for block in characters.chunks_exact(block_size) {
+            let block = block.iter().collect::<String>();
+            match indices.get(&block) {
+                Some(index) => counts[*index].1 += 1,
+                None => {
+                    indices.insert(block.clone(), counts.len());
+                    counts.push((block, 1));
+                }
+            }
+        }
+
+        counts.retain(|(_, count)| *count > 1);
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts
+    }
+
+    fn hamming_letters(&self, other: &str) -> usize {
+        self.as_ref().chars().zip(other.chars()).filter(|(first, second)| first != second).count()
+    }
+
+    fn hamming_bits(&self, other: &str) -> usize {
+        self.as_ref().bytes().zip(other.bytes()).map(|(first, second)| (first ^ second).count_ones() as usize).sum()
+    }
+
+    fn mutual_index_of_coincidence(&self, other: &str) -> f64 {
+        let letters = |text: &str| {
+            text.chars()
+                .filter(|character| character.is_alphabetic())
+                .flat_map(|letter| letter.to_lowercase())
+                .collect::<Vec<_>>()
+        };
+        let (first, second) = (letters(self.as_ref()), letters(other));
+        let compared = first.len().min(second.len());
+        if compared == 0 {
+            return 0.;
+        }
+        first.iter().zip(&second).filter(|(first, second)| first == second).count() as f64 / compared as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Analyze as _;
+
+    /// Encrypts the given text with a standard A-Z Vigenere cipher.
+    fn vigenere(text: &str, key: &str) -> String {
+        let key = key.bytes().map(|byte| byte - b'A').collect::<Vec<_>>();
+        text.to_uppercase()
+            .chars()
+            .filter(|character| character.is_ascii_alphabetic())
+            .enumerate()
+            .map(|(index, letter)| ((letter as u8 - b'A' + key[index % key.len()]) % 26 + b'A') as char)
+            .collect()
+    }
+
+    #[test]
+    fn period_ioc_profile() {
+        let ciphertext = vigenere(include_str!("../tests/letter.txt"), "LEMON");
+        let profile = ciphertext.period_ioc_profile(20);
+
+        assert_eq!(20, profile.len());
+        assert!(profile.iter().enumerate().all(|(index, (period, _))| *period == index + 1));
+
+        let (best_period, _) = profile.iter().max_by(|first, other| first.1.total_cmp(&other.1)).unwrap();
+        assert_eq!(0, best_period % 5);
+
+        for (period, ioc) in &profile {
+            if period % 5 == 0 {
+                assert!(*ioc > 0.055, "Expected a peak at period {period}, got {ioc}");
+            } else {
+                assert!(*ioc < 0.05, "Expected no peak at period {period}, got {ioc}");
+            }
+        }
+    }
+
+    #[test]
+    fn kappa_test() {
+        let ciphertext = vigenere(include_str!("../tests/letter.txt"), "LEMON");
+        let kappas = ciphertext.kappa_test(20);
+
+        assert_eq!(20, kappas.len());
+        assert!(kappas.iter().enumerate().all(|(index, (period, _))| *period == index + 1));
+
+        let (best_period, _) = kappas.iter().max_by(|first, other| first.1.total_cmp(&other.1)).unwrap();
+        assert_eq!(0, best_period % 5);
+
+        for (period, kappa) in &kappas {
+            if period % 5 == 0 {
+                assert!(*kappa > 0.055, "Expected a peak at period {period}, got {kappa}");
+            } else {
+                assert!(*kappa < 0.055, "Expected no peak at period {period}, got {kappa}");
+            }
+        }
+
+        assert_eq!(vec![(1, 0.), (2, 0.)], "A".kappa_test(2));
+    }
+
+    #[test]
+    fn repeated_blocks() {
+        let text = "ABCD WXYZ ABCD EFGH ABCD EFGH IJKL AB";
+        assert_eq!(vec![("ABCD".to_owned(), 3), ("EFGH".to_owned(), 2)], text.repeated_blocks(4));
+
+        // Misaligned repeats aren't blocks
+        assert!("ABCXABCX".repeated_blocks(3).is_empty());
+        assert_eq!(vec![("ABCX".to_owned(), 2)], "ABCXABCX".repeated_blocks(4));
+        assert!("ABCDEFGHIJKL".repeated_blocks(4).is_empty());
+        assert!(text.repeated_blocks(0).is_empty());
+        assert!("".repeated_blocks(4).is_empty());
+    }
+
+    #[test]
+    fn mutual_index_of_coincidence() {
+        // Two messages taken from different places in the same Vigenere keystream. Since the second message starts 3
+        // letters into the key, skipping the first 4 of its letters puts it in depth with the first.
+        let ciphertext = vigenere(include_str!("../tests/letter.txt"), "KEYWORD");
+        let half = ciphertext.len() / 14 * 7;
+        let first = &ciphertext[..half];
+        let second = &ciphertext[half + 3..];
+
+        let profile = (0..14).map(|offset| (offset, first.mutual_index_of_coincidence(&second[offset..]))).collect::<Vec<_>>();
+        let (aligned, misaligned): (Vec<_>, Vec<_>) = profile.iter().partition(|(offset, _)| offset % 7 == 4);
+        let lowest_peak = aligned.iter().map(|(_, index)| *index).fold(f64::INFINITY, f64::min);
+        let highest_other = misaligned.iter().map(|(_, index)| *index).fold(0., f64::max);
+        assert!(lowest_peak > 0.05, "Expected peaks at offsets 4 and 11, got {profile:?}");
+        assert!(highest_other < lowest_peak, "Expected peaks only at offsets 4 and 11, got {profile:?}");
+
+        assert_eq!(1., "Hello, World".mutual_index_of_coincidence("HELLOWORLD!!"));
+        assert_eq!(0.5, "ABCD".mutual_index_of_coincidence("ABXYZ"));
+        assert_eq!(0., "".mutual_index_of_coincidence("ABC"));
+    }
+
+    #[test]
+    fn rot13() {
+        let text = include_str!("../tests/letter.txt");
+        assert_eq!(text, crate::rot13(&crate::rot13(text)));
+        assert_eq!(crate::rotn(text, 13), crate::rot13(text));
+        assert_eq!("Uryyb, Jbeyq! 123", crate::rot13("Hello, World! 123"));
+        assert_eq!("Bcd Zab", crate::rotn("Abc Yza", 1));
+        assert_eq!(text, crate::rotn(&crate::rotn(text, 3), 23));
+        assert_eq!(text, crate::rotn(text, 52));
+    }
+
+    #[test]
+    fn reverse() {
+        assert_eq!("olléh", crate::reverse("héllo"));
+        assert_eq!("héllo", crate::reverse(&crate::reverse("héllo")));
+        assert_eq!("", crate::reverse(""));
+
+        let text = include_str!("../tests/letter.txt");
+        assert_eq!(text, crate::reverse(&crate::reverse(text)));
+    }
+
+    #[test]
+    fn entropy() {
+        assert_eq!(0., "".entropy());
+        assert_eq!(0., "AAAA".entropy());
+        assert_eq!(2., "ABCD ABCD".entropy());
+        assert!((4.0..4.3).contains(
+            &include_str!("../tests/letter.txt")
+                .to_lowercase()
+                .chars()
+                .filter(|character| character.is_alphabetic())
+                .collect::<String>()
+                .entropy()
+        ));
+    }
+
+    #[test]
+    fn hamming_distance() {
+        assert_eq!(0, "ATTACK AT DAWN".hamming_letters("ATTACK AT DAWN"));
+        assert_eq!(0, "ATTACK AT DAWN".hamming_bits("ATTACK AT DAWN"));
+
+        assert_eq!(5, "ABCDE".hamming_letters("VWXYZ"));
+        assert_eq!(37, "this is a test".hamming_bits("wokka wokka!!!"));
+
+        assert_eq!(3, "KAROLIN".hamming_letters("KATHRIN HAS A LONGER NAME"));
+        assert_eq!(1, "SHORT".hamming_letters("SHIRTS"));
+        assert_eq!(0, "".hamming_letters("NOT EMPTY"));
+        assert_eq!(3, "ab".hamming_bits("ca and more"));
+    }
+
+    #[test]
+    #[cfg(feature = "normalization")]
+    fn normalize_for_cipher() {
+        assert_eq!("Cafe creme", crate::normalize_for_cipher("Café crème", true));
+        assert_eq!("Ecole, naive, Angstrom", crate::normalize_for_cipher("École, naïve, Ångström", true));
+        assert_eq!("HELLO world 123", crate::normalize_for_cipher("ＨＥＬＬＯ ｗｏｒｌｄ １２３", true));
+        assert_eq!("finesse", crate::normalize_for_cipher("ﬁnesse", true));
+        assert_eq!("straße", crate::normalize_for_cipher("straße", true));
+        assert_eq!("", crate::normalize_for_cipher("", true));
+
+        assert_eq!("Pnsr", crate::rot13(&crate::normalize_for_cipher("Ｃａｆé", true)));
+
+        // Without stripping, accents are kept, but in their composed form, and compatibility forms are still replaced
+        assert_eq!("Café crème", crate::normalize_for_cipher("Café crème", false));
+        assert_eq!("Café", crate::normalize_for_cipher("Cafe\u{301}", false));
+        assert_eq!(4, crate::normalize_for_cipher("Cafe\u{301}", false).chars().count());
+        assert_eq!("HELLO wörld", crate::normalize_for_cipher("ＨＥＬＬＯ ｗöｒｌｄ", false));
+        assert_eq!("finesse", crate::normalize_for_cipher("ﬁnesse", false));
+        assert_eq!("", crate::normalize_for_cipher("", false));
+    }
+
+    #[test]
+    fn strip_framing() {
+        use crate::cipher_type::CipherType;
+
+        let octal = "110 145 154 154 157 040 167 157 162 154 144\n041 041";
+        let framed = format!("Puzzle 7:\n\nCiphertext:   {}\n", octal.replace('\n', "  \n\t"));
+        assert_eq!(octal, crate::strip_framing(&framed));
+        assert_eq!(Some(CipherType::Octal), CipherType::best_match(&crate::strip_framing(&framed)));
+        assert_ne!(Some(CipherType::Octal), CipherType::best_match(&framed));
+
+        let morse = ".... . .-.. .-.. --- / .-- --- .-. .-.. -..";
+        let numbered = "1. .... . .-.. .-.. ---\n2. / .-- --- .-. .-.. -..";
+        assert_eq!(Some(CipherType::Morse), CipherType::best_match(&crate::strip_framing(numbered)));
+        assert_eq!(morse, crate::strip_framing(numbered).replace('\n', " "));
+
+        // Not every line is numbered, so the numbers are kept
+        assert_eq!("1. ABC\nDEF", crate::strip_framing("1. ABC\nDEF"));
+        assert_eq!("KDZV KMNT", crate::strip_framing("  KDZV   KMNT  "));
+        assert_eq!("", crate::strip_framing("Ciphertext:\n\n"));
+    }
 }
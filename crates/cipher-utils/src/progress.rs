@@ -0,0 +1,123 @@
+/// A tracker for the progress of a long-running search, such as a brute-force cracker, which estimates how much time is
+/// left from how fast iterations have been completed so far.
+#[derive(Debug, Clone)]
+pub struct ProgressTracker {
+    /// When the tracker was created.
+    start: std::time::Instant,
+
+    /// The number of completed iterations.
+    current: usize,
+
+    /// The total number of iterations.
+    total: usize,
+}
+
+impl ProgressTracker {
+    /// Creates a new tracker with no completed iterations, starting the clock.
+    ///
+    /// # Parameters
+    /// - `total` - The total number of iterations.
+    ///
+    /// # Returns
+    /// The created tracker.
+    pub fn new(total: usize) -> Self {
+        Self {
+            start: std::time::Instant::now(),
+            current: 0,
+            total,
+        }
+    }
+
+    /// Marks one more iteration as completed.
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1).min(self.total);
+    }
+
+    /// Returns the number of completed iterations.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Returns the total number of iterations.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns whether every iteration has been completed.
+    pub fn is_complete(&self) -> bool {
+        self.current == self.total
+    }
+
+    /// Returns the time since the tracker was created.
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the fraction of iterations that have been completed, from `0` to `1`. A tracker with no iterations is
+    /// always complete.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.;
+        }
+        self.current as f64 / self.total as f64
+    }
+
+    /// Estimates the time until every iteration is completed, assuming the remaining iterations take as long on average
+    /// as the completed ones.
+    ///
+    /// # Returns
+    /// The estimated remaining time, which is zero if no iterations have been completed yet, since there's nothing to
+    /// estimate from, or if every iteration has been completed.
+    pub fn estimated_remaining(&self) -> std::time::Duration {
+        if self.current == 0 {
+            return std::time::Duration::ZERO;
+        }
+        self.elapsed().mul_f64((self.total - self.current) as f64 / self.current as f64)
+    }
+}
+
+impl std::fmt::Display for ProgressTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}%", 100. * self.fraction())?;
+        if self.current != 0 && !self.is_complete() {
+            let seconds = self.estimated_remaining().as_secs();
+            write!(f, ", about {}m {}s remaining", seconds / 60, seconds % 60)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::progress::ProgressTracker;
+
+    #[test]
+    fn estimated_remaining() {
+        let mut tracker = ProgressTracker::new(10);
+        assert_eq!(0., tracker.fraction());
+        assert_eq!(std::time::Duration::ZERO, tracker.estimated_remaining());
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        tracker.advance();
+        tracker.advance();
+        let early = tracker.estimated_remaining();
+        assert!(early > std::time::Duration::ZERO);
+        assert_eq!(0.2, tracker.fraction());
+
+        for _ in 0..6 {
+            tracker.advance();
+        }
+        let late = tracker.estimated_remaining();
+        assert!(late > std::time::Duration::ZERO);
+        assert!(late < early);
+        assert_eq!("80.00%, about 0m 0s remaining", tracker.to_string());
+
+        tracker.advance();
+        tracker.advance();
+        tracker.advance();
+        assert!(tracker.is_complete());
+        assert_eq!(10, tracker.current());
+        assert_eq!(std::time::Duration::ZERO, tracker.estimated_remaining());
+        assert_eq!("100.00%", tracker.to_string());
+    }
+}
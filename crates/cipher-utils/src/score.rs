@@ -5,8 +5,129 @@ use crate::{dictionary, frequency, Analyze};
 /// A possible plaintext. The `PossiblePlaintext` struct provides utilities for analyzing
 /// and scoring texts that may be plaintexts. This is useful for brute-forcing ciphers, when
 /// you need a system to find the decryption outputs that are most likely to be correct.
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-pub struct PossiblePlaintext(String);
+///
+/// The score of a `PossiblePlaintext` is computed the first time it's needed and then cached, so comparing or sorting
+/// plaintexts only scores each one once.
+#[derive(Debug, Clone)]
+pub struct PossiblePlaintext {
+    /// The text of the possible plaintext.
+    text: String,
+
+    /// The score of the text, once it's been computed.
+    score: std::sync::OnceLock<f64>,
+}
+
+/// The index of coincidence of English text.
+const ENGLISH_INDEX_OF_COINCIDENCE: f64 = 0.0667;
+
+/// The index of coincidence of uniformly random letters.
+const RANDOM_INDEX_OF_COINCIDENCE: f64 = 0.0385;
+
+/// The typical average quadgram log-probability per quadgram of English text.
+const ENGLISH_QUADGRAM: f64 = -4.5;
+
+/// The typical average quadgram log-probability per quadgram of uniformly random letters.
+const RANDOM_QUADGRAM: f64 = -9.5;
+
+/// Returns how English-like the given text is, on a scale from `0` (random letters) to `1` (typical English). This is the
+/// canonical score that the crackers use to compare candidate decryptions, so that they agree on which candidate is best.
+///
+/// The score is the average of four fitnesses in `[0, 1]`, each of which is `1` for typical English:
+///
+/// - Index of coincidence, which is `0` at or beyond the distance between English's and random letters'
+/// - Monogram frequencies, which is `1` minus the total variation distance from English's letter frequencies
+/// - Bigram frequencies; See `frequency::bigram_distribution_score()`
+/// - Quadgram log-probability per quadgram, which is `0` for random letters; See `frequency::quadgram_score()`
+///
+/// Only English letters are considered, case-insensitively, so spacing and punctuation don't affect the score. The
+/// index of coincidence and monogram fitnesses don't depend on the order of the letters, so a shuffled English text
+/// still scores in between English and random letters.
+///
+/// # Parameters
+/// - `text` - The text to score.
+///
+/// # Returns
+/// The englishness of the text in `[0, 1]`, which is `0` for texts with no letters.
+///
+/// # Performance
+/// This is `O(n)`.
+pub fn englishness(text: &str) -> f64 {
+    let letters = text
+        .chars()
+        .filter(|character| character.is_ascii_alphabetic())
+        .map(|character| character.to_ascii_lowercase())
+        .collect::<String>();
+    if letters.is_empty() {
+        return 0.;
+    }
+
+    let index_of_coincidence = 1. - ((letters.index_of_coincidence() - ENGLISH_INDEX_OF_COINCIDENCE).abs() / (ENGLISH_INDEX_OF_COINCIDENCE - RANDOM_INDEX_OF_COINCIDENCE)).min(1.);
+
+    let counts = letters.chars().counts();
+    let monogram = 1.
+        - frequency::english_lowercase()
+            .iter()
+            .map(|(letter, expected)| (*counts.get(letter).unwrap_or(&0) as f64 / letters.len() as f64 - expected).abs())
+            .sum::<f64>()
+            / 2.;
+
+    let bigram = frequency::bigram_distribution_score(&letters);
+
+    let quadgram = if letters.len() < 4 {
+        0.
+    } else {
+        let average = frequency::quadgram_score(&letters) / (letters.len() - 3) as f64;
+        ((average - RANDOM_QUADGRAM) / (ENGLISH_QUADGRAM - RANDOM_QUADGRAM)).clamp(0., 1.)
+    };
+
+    (index_of_coincidence + monogram.clamp(0., 1.) + bigram + quadgram) / 4.
+}
+
+/// The components of the score of a `PossiblePlaintext`, as returned by `PossiblePlaintext::score_breakdown()`. The
+/// score itself is the average of every component except `quadgram`; See `ScoreBreakdown::total()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreBreakdown {
+    /// How close the index of coincidence of the text is to English's, in `[0, 1]`.
+    pub index_of_coincidence: f64,
+
+    /// How closely the frequency of each letter matches its frequency in English, in `[0, 1]`. See
+    /// `frequency::character_score()`.
+    pub monogram_character: f64,
+
+    /// How closely the distribution of letter frequencies matches English's, regardless of which letter has which
+    /// frequency, in `(0, 1]`. See `frequency::distribution_score()`.
+    pub monogram_distribution: f64,
+
+    /// How closely the bigram frequencies match English's, in `[0, 1]`. See `frequency::bigram_distribution_score()`.
+    pub bigram_distribution: f64,
+
+    /// How common the words of the text are, in `[0, 1]`, or `None` if the text has no spaces to split words on. See
+    /// `dictionary::average_commonality_score()`.
+    pub word_commonality: Option<f64>,
+
+    /// The average quadgram log-probability per quadgram, which is around `-4.5` for English and lower for random text,
+    /// or `0` if the text has fewer than four letters. This doesn't contribute to the score. See
+    /// `frequency::quadgram_score()`.
+    pub quadgram: f64,
+}
+
+impl ScoreBreakdown {
+    /// Returns the score these components make up, which is the average of every component except `quadgram`. This is
+    /// the value returned by `PossiblePlaintext::score()`.
+    pub fn total(&self) -> f64 {
+        let scores = [self.index_of_coincidence, self.monogram_character, self.monogram_distribution, self.bigram_distribution]
+            .into_iter()
+            .chain(self.word_commonality)
+            .collect::<Vec<_>>();
+        scores.iter().sum::<f64>() / scores.len() as f64
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The number of times a score has been computed on this thread, for testing the score cache.
+    static SCORE_COMPUTATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
 
 impl PossiblePlaintext {
     /// Creates a new `PossiblePlaintext` with the given text as the possible plaintext.
@@ -17,7 +138,10 @@ impl PossiblePlaintext {
     /// # Returns
     /// The created `PossiblePlaintext` object
     pub fn new(plaintext: &str) -> Self {
-        Self(plaintext.to_owned())
+        Self {
+            text: plaintext.to_owned(),
+            score: std::sync::OnceLock::new(),
+        }
     }
 
     /// Returns the "score" of this plaintext. The score is based on cryptographic analysis, and a higher score
@@ -29,21 +153,43 @@ impl PossiblePlaintext {
     /// - Bigram Frequency
     /// - Trigram Frequency
     /// - Quadram Frequency
+    ///
+    /// # Performance
+    /// The score is only computed on the first call; Later calls are `O(1)`.
     pub fn score(&self) -> f64 {
-        let ioc_score = 1. - (self.0.index_of_coincidence() - 0.0667).abs() / 0.9333;
-        let frequency_distribution_score = frequency::distribution_score(&self.0);
-        let frequency_character_score = frequency::character_score(&self.0);
-        let bigram_distribution_score = frequency::bigram_distribution_score(&self.0);
+        *self.score.get_or_init(|| self.compute_score())
+    }
 
-        let mut scores = vec![ioc_score, frequency_character_score, frequency_distribution_score, bigram_distribution_score];
+    /// Computes the score of this plaintext without using the cache. See `score()`.
+    fn compute_score(&self) -> f64 {
+        #[cfg(test)]
+        SCORE_COMPUTATIONS.with(|computations| computations.set(computations.get() + 1));
 
-        // Multiple words - check for commonality
-        if self.0.contains(' ') {
-            let word_score = dictionary::average_commonality_score(&self.0);
-            scores.push(word_score);
-        }
+        self.score_breakdown().total()
+    }
 
-        scores.iter().fold(0., |accumulator, current| accumulator + current) / scores.len() as f64
+    /// Returns each of the components that make up the score of this plaintext, such as for understanding why a
+    /// plaintext scored as it did or for tuning a cracker. See `ScoreBreakdown`.
+    ///
+    /// # Returns
+    /// The breakdown of this plaintext's score.
+    ///
+    /// # Performance
+    /// Unlike `score()`, this isn't cached, and computes every component each time it's called.
+    pub fn score_breakdown(&self) -> ScoreBreakdown {
+        let letters = self.text.chars().filter(|character| character.is_ascii_alphabetic()).count();
+        ScoreBreakdown {
+            index_of_coincidence: 1. - (self.text.index_of_coincidence() - 0.0667).abs() / 0.9333,
+            monogram_character: frequency::character_score(&self.text),
+            monogram_distribution: frequency::distribution_score(&self.text),
+            bigram_distribution: frequency::bigram_distribution_score(&self.text),
+            word_commonality: self.text.contains(' ').then(|| dictionary::average_commonality_score(&self.text)),
+            quadgram: if letters < 4 {
+                0.
+            } else {
+                frequency::quadgram_score(&self.text) / (letters - 3) as f64
+            },
+        }
     }
 
     /// Returns the original text of this plaintext.
@@ -51,11 +197,11 @@ impl PossiblePlaintext {
     /// # Returns
     /// A reference to the stored text in this plaintext.
     pub fn text(&self) -> &str {
-        &self.0
+        &self.text
     }
 
     /// Returns the best plaintext from the given slice based on cryptographic analysis. To get the best `n` plaintexts,
-    /// use `Plaintexts::best_n`.
+    /// use `PossiblePlaintext::best_n`.
     ///
     /// # Parameters
     /// - `plaintexts` - The plaintexts to find the best of
@@ -65,7 +211,7 @@ impl PossiblePlaintext {
     pub fn best<T: AsRef<str>>(plaintexts: &[T]) -> Option<String> {
         plaintexts
             .iter()
-            .map(|plaintext| Self(plaintext.as_ref().to_owned()))
+            .map(|plaintext| Self::new(plaintext.as_ref()))
             .max()
             .map(|plaintext| plaintext.text().to_owned())
     }
@@ -91,12 +237,49 @@ impl PossiblePlaintext {
             anyhow::bail!("Attempted to get the best {n} plaintexts of an empty plaintext list.");
         }
 
-        let sorted = plaintexts.iter().map(|plaintext| Self(plaintext.as_ref().to_owned())).sorted().rev().collect_vec();
+        let sorted = plaintexts.iter().map(|plaintext| Self::new(plaintext.as_ref())).sorted().rev().collect_vec();
         sorted
-            .get(sorted.len() - n..sorted.len())
+            .get(..n)
             .ok_or_else(|| anyhow::anyhow!("Error getting best n plaintexts: Index {n} is out of range of {} plaintexts", plaintexts.len()))
             .map(|ok| ok.iter().map(|plaintext| plaintext.text().to_owned()).collect())
     }
+
+    /// Returns the best plaintext from the given slice by combining several scoring functions with a vote, which is more
+    /// reliable than any one of them when they disagree. Each scorer ranks the candidates from best (rank `0`) to
+    /// worst, with candidates that score the same sharing the better rank, and the candidate with the lowest average
+    /// rank wins. Ties go to the candidate that comes first.
+    ///
+    /// Since only the ranks are compared, the scorers don't need to share a scale; For example, the index of
+    /// coincidence, `frequency::quadgram_score()`, and `englishness()` can be combined directly.
+    ///
+    /// # Parameters
+    /// - `candidates` - The plaintexts to find the best of.
+    /// - `scorers` - The scoring functions to vote with, each returning a higher score for a more likely plaintext.
+    ///
+    /// # Returns
+    /// The best plaintext of the given slice, or `None` if either slice is empty.
+    ///
+    /// # Performance
+    /// This is `O(sc²)` for `s` scorers and `c` candidates, plus the cost of scoring each candidate with each scorer.
+    pub fn best_by_ensemble<T: AsRef<str>>(candidates: &[T], scorers: &[&dyn Fn(&str) -> f64]) -> Option<String> {
+        if scorers.is_empty() {
+            return None;
+        }
+
+        let mut rank_totals = vec![0; candidates.len()];
+        for scorer in scorers {
+            let scores = candidates.iter().map(|candidate| scorer(candidate.as_ref())).collect_vec();
+            for (total, score) in rank_totals.iter_mut().zip(&scores) {
+                *total += scores.iter().filter(|other| *other > score).count();
+            }
+        }
+
+        rank_totals
+            .iter()
+            .enumerate()
+            .min_by_key(|(index, total)| (**total, *index))
+            .map(|(index, _)| candidates[index].as_ref().to_owned())
+    }
 }
 
 impl std::cmp::PartialOrd for PossiblePlaintext {
@@ -110,3 +293,116 @@ impl std::cmp::Ord for PossiblePlaintext {
         self.score().total_cmp(&other.score())
     }
 }
+
+impl std::cmp::PartialEq for PossiblePlaintext {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl std::cmp::Eq for PossiblePlaintext {}
+
+impl std::hash::Hash for PossiblePlaintext {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools as _;
+
+    use crate::score::{PossiblePlaintext, SCORE_COMPUTATIONS};
+
+    #[test]
+    fn scores_are_computed_once() {
+        let plaintexts = include_str!("../tests/letter.txt")
+            .split('.')
+            .filter(|sentence| !sentence.trim().is_empty())
+            .map(PossiblePlaintext::new)
+            .collect_vec();
+        assert!(plaintexts.len() > 5);
+
+        SCORE_COMPUTATIONS.with(|computations| computations.set(0));
+        let sorted = plaintexts.iter().sorted().collect_vec();
+        assert_eq!(plaintexts.len(), SCORE_COMPUTATIONS.with(|computations| computations.get()));
+        assert!(sorted.windows(2).all(|pair| pair[0].score() <= pair[1].score()));
+
+        // Clones keep the cached score
+        let best = (*sorted.last().unwrap()).clone();
+        assert_eq!(best.score(), plaintexts.iter().max().unwrap().score());
+        assert_eq!(plaintexts.len(), SCORE_COMPUTATIONS.with(|computations| computations.get()));
+    }
+
+    #[test]
+    fn score_breakdown() {
+        let letter = PossiblePlaintext::new(include_str!("../tests/letter.txt"));
+        let breakdown = letter.score_breakdown();
+
+        for component in [
+            breakdown.index_of_coincidence,
+            breakdown.monogram_character,
+            breakdown.monogram_distribution,
+            breakdown.bigram_distribution,
+        ] {
+            assert!((0.5..=1.).contains(&component), "Expected an English-like component, got {component}");
+        }
+        assert!(breakdown.word_commonality.is_some_and(|score| (0. ..=1.).contains(&score)));
+        assert!((-5.5..-3.5).contains(&breakdown.quadgram));
+        // The components sum over hash maps, so computing them again can differ in the last bits
+        assert!((letter.score() - breakdown.total()).abs() < 1e-12);
+
+        let gibberish = PossiblePlaintext::new("QXZJVKWQPZXJQKVWZXQJ").score_breakdown();
+        assert_eq!(None, gibberish.word_commonality);
+        assert!(gibberish.quadgram < breakdown.quadgram);
+        assert!(gibberish.monogram_character < breakdown.monogram_character);
+        assert!(gibberish.total() < breakdown.total());
+    }
+
+    #[test]
+    fn best_by_ensemble() {
+        use crate::{frequency, Analyze as _};
+
+        let english = "Meet me by the old oak tree at midnight and bring the map";
+        let shuffled = "Mtee em yb eht dol kao etre ta mdinihgt nda gbrin eth pam";
+        let repetitive = "Eeee ee ee eee eee eee eeee ee eeeeeeee eee eeeee eee eee";
+        let candidates = [shuffled, repetitive, english];
+
+        let index_of_coincidence = |text: &str| text.index_of_coincidence();
+        assert_eq!(Some(repetitive.to_owned()), PossiblePlaintext::best_by_ensemble(&candidates, &[&index_of_coincidence]));
+
+        let scorers: [&dyn Fn(&str) -> f64; 3] = [&index_of_coincidence, &frequency::quadgram_score, &crate::englishness];
+        assert_eq!(Some(english.to_owned()), PossiblePlaintext::best_by_ensemble(&candidates, &scorers));
+
+        assert_eq!(None, PossiblePlaintext::best_by_ensemble::<&str>(&[], &scorers));
+        assert_eq!(None, PossiblePlaintext::best_by_ensemble(&candidates, &[]));
+    }
+
+    #[test]
+    fn englishness() {
+        use rand::{seq::SliceRandom as _, Rng as _, SeedableRng as _};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(26);
+        let english = include_str!("../tests/letter.txt");
+        let mut characters = english.chars().collect_vec();
+        characters.shuffle(&mut rng);
+        let shuffled = characters.into_iter().collect::<String>();
+        let random = english
+            .chars()
+            .map(|character| if character.is_ascii_alphabetic() { rng.gen_range('a'..='z') } else { character })
+            .collect::<String>();
+
+        let (english, shuffled, random) = (crate::englishness(english), crate::englishness(&shuffled), crate::englishness(&random));
+        assert!(english > 0.85, "Expected English to score highly, got {english}");
+        assert!(
+            english > shuffled && shuffled > random,
+            "Expected English > shuffled > random, got {english}, {shuffled}, {random}"
+        );
+        assert!(random < 0.35, "Expected random letters to score poorly, got {random}");
+
+        assert_eq!(0., crate::englishness(""));
+        assert_eq!(0., crate::englishness("1234, 5678!"));
+        assert_eq!(crate::englishness("Hello, World!"), crate::englishness("helloworld"));
+        assert!((0. ..=1.).contains(&crate::englishness("eeee")));
+    }
+}
@@ -19,6 +19,32 @@ pub fn tabula_recta<T: Borrow<Alphabet>>(alphabet: T) -> std::collections::HashM
     rows
 }
 
+/// Formats the tabula recta of the given alphabet as a multi-line string, with one line per row, for display in a UI
+/// or file. There's no newline after the last line.
+///
+/// # Parameters
+/// - `alphabet` - The alphabet to generate the tabula recta of.
+/// - `headers` - Whether to label the grid with a header line of the column letters, and each row with its row letter,
+///   separated from the grid by a space.
+///
+/// # Returns
+/// The formatted tabula recta.
+pub fn tabula_recta_string<T: Borrow<Alphabet>>(alphabet: T, headers: bool) -> String {
+    let alphabet = alphabet.borrow();
+    let tabula_recta = tabula_recta(alphabet);
+    let letters = (1..=26).map(|index| *alphabet.letter_at(AlphabetIndex::new(index).unwrap())).collect::<Vec<_>>();
+
+    let mut lines = Vec::new();
+    if headers {
+        lines.push(format!("  {}", letters.iter().collect::<String>()));
+    }
+    for row in &letters {
+        let grid_row = letters.iter().map(|column| tabula_recta.at(row, column).unwrap()).collect::<String>();
+        lines.push(if headers { format!("{row} {grid_row}") } else { grid_row });
+    }
+    lines.join("\n")
+}
+
 pub trait TabulaRecta {
     fn at(&self, row: &char, column: &char) -> Option<&char>;
 }
@@ -33,7 +59,7 @@ impl TabulaRecta for std::collections::HashMap<char, std::collections::HashMap<c
 mod tests {
     use crate::{
         alphabet::{Alphabet, AlphabetIndex},
-        tabula_recta::{tabula_recta, TabulaRecta as _},
+        tabula_recta::{tabula_recta, tabula_recta_string, TabulaRecta as _},
     };
 
     /// Run with `-- --nocapture` to avoid Rust suppressing the output.
@@ -51,4 +77,22 @@ mod tests {
             println!()
         }
     }
+
+    #[test]
+    fn tabula_recta_as_string() {
+        let grid = tabula_recta_string(Alphabet::default(), false);
+        let rows = grid.lines().collect::<Vec<_>>();
+        assert_eq!(26, rows.len());
+        assert!(rows.iter().all(|row| row.chars().count() == 26));
+        assert_eq!("ABCDEFGHIJKLMNOPQRSTUVWXYZ", rows[0]);
+        assert_eq!("ZABCDEFGHIJKLMNOPQRSTUVWXY", rows[25]);
+
+        let labeled = tabula_recta_string(Alphabet::default(), true);
+        let rows = labeled.lines().collect::<Vec<_>>();
+        assert_eq!(27, rows.len());
+        assert_eq!("  ABCDEFGHIJKLMNOPQRSTUVWXYZ", rows[0]);
+        assert_eq!("B BCDEFGHIJKLMNOPQRSTUVWXYZA", rows[2]);
+        assert!(rows.iter().all(|row| row.chars().count() == 28));
+        assert!(!labeled.ends_with('\n'));
+    }
 }
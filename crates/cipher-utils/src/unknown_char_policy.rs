@@ -0,0 +1,34 @@
+/// What a cipher should do when it encounters a character that isn't part of its alphabet, such as
+/// whitespace, digits, or punctuation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnknownCharPolicy {
+    /// Leave the character in the output unchanged. This is the default.
+    #[default]
+    Passthrough,
+
+    /// Remove the character from the output.
+    Strip,
+
+    /// Fail the entire encryption or decryption with an error.
+    Error,
+}
+
+impl UnknownCharPolicy {
+    /// Applies this policy to a character that's not in a cipher's alphabet.
+    ///
+    /// # Parameters
+    /// - `character` - The unknown character.
+    ///
+    /// # Returns
+    /// `Some(character)` if the character should be kept in the output, or `None` if it should be removed.
+    ///
+    /// # Errors
+    /// If this policy is `UnknownCharPolicy::Error`.
+    pub fn apply(&self, character: char) -> anyhow::Result<Option<char>> {
+        match self {
+            Self::Passthrough => Ok(Some(character)),
+            Self::Strip => Ok(None),
+            Self::Error => anyhow::bail!("Character not in alphabet: {character:?}"),
+        }
+    }
+}
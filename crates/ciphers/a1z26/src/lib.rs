@@ -0,0 +1,156 @@
+/// The A1Z26 cipher, which replaces each letter with its position in the alphabet, i.e., `A` with `1` and `Z` with `26`.
+/// Characters that aren't letters pass through unchanged, and decrypted letters are uppercase.
+pub struct A1Z26;
+
+impl A1Z26 {
+    /// Encrypts the given text into space-separated letter numbers, i.e., `"Hi!"` becomes `"8 9!"`. Characters that
+    /// aren't letters, including the spaces between words, pass through unchanged, so the spaces between words can't be
+    /// told apart from the spaces between letters; Use `A1Z26::encrypt_with_delimiter()` with `"-"` to keep them.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    pub fn encrypt(plaintext: &str) -> String {
+        Self::encrypt_with_delimiter(plaintext, " ")
+    }
+
+    /// Encrypts the given text into letter numbers, with the given delimiter between the numbers of adjacent letters,
+    /// i.e., `"Hello World"` with the delimiter `"-"` becomes `"8-5-12-12-15 23-15-18-12-4"`.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    /// - `delimiter` - The delimiter to put between the numbers of adjacent letters.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    pub fn encrypt_with_delimiter(plaintext: &str, delimiter: &str) -> String {
+        let mut ciphertext = String::new();
+        let mut previous_was_letter = false;
+        for character in plaintext.chars() {
+            if character.is_ascii_alphabetic() {
+                if previous_was_letter {
+                    ciphertext.push_str(delimiter);
+                }
+                ciphertext.push_str(&(character.to_ascii_uppercase() as u8 - b'A' + 1).to_string());
+                previous_was_letter = true;
+            } else {
+                ciphertext.push(character);
+                previous_was_letter = false;
+            }
+        }
+        ciphertext
+    }
+
+    /// Decrypts the given letter numbers. If any two numbers are separated by a dash, then dashes separate the letters of a
+    /// word and whitespace separates words, as in `"8-9 20-8-5-18-5"`; Otherwise, whitespace between two numbers
+    /// separates letters, as in `"8 9"`. Every other character passes through unchanged.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text, in uppercase.
+    ///
+    /// # Errors
+    /// If any number isn't in `[1, 26]`.
+    pub fn decrypt(ciphertext: &str) -> anyhow::Result<String> {
+        let characters = ciphertext.chars().collect::<Vec<_>>();
+        let dashed = characters
+            .windows(3)
+            .any(|window| window[0].is_ascii_digit() && window[1] == '-' && window[2].is_ascii_digit());
+        if dashed {
+            Self::decode(ciphertext, |separator| separator == "-")
+        } else {
+            Self::decode(ciphertext, |separator| separator.trim().is_empty())
+        }
+    }
+
+    /// Decrypts letter numbers that are separated by the given delimiter, such as `","` or `"-"`. Whitespace around each
+    /// delimiter is ignored, and every other character passes through unchanged.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    /// - `delimiter` - The delimiter between the numbers of adjacent letters.
+    ///
+    /// # Returns
+    /// The decrypted text, in uppercase.
+    ///
+    /// # Errors
+    /// If any number isn't in `[1, 26]`.
+    pub fn decrypt_with_delimiter(ciphertext: &str, delimiter: &str) -> anyhow::Result<String> {
+        Self::decode(ciphertext, |separator| separator.trim() == delimiter.trim())
+    }
+
+    /// Replaces each number in the given text with its letter, and removes the text between two numbers if it's a
+    /// separator. See `decrypt()`.
+    fn decode(ciphertext: &str, is_separator: impl Fn(&str) -> bool) -> anyhow::Result<String> {
+        let mut plaintext = String::new();
+        let mut rest = ciphertext;
+        let mut after_number = false;
+        while !rest.is_empty() {
+            let digits = rest.find(|character: char| !character.is_ascii_digit()).unwrap_or(rest.len());
+            if digits > 0 {
+                let number = &rest[..digits];
+                match number.parse::<u8>() {
+                    Ok(number @ 1..=26) => plaintext.push((b'A' + number - 1) as char),
+                    _ => anyhow::bail!("Error decrypting A1Z26: {number} is not in [1, 26]"),
+                }
+                rest = &rest[digits..];
+                after_number = true;
+                continue;
+            }
+
+            let other = rest.find(|character: char| character.is_ascii_digit()).unwrap_or(rest.len());
+            let text = &rest[..other];
+            rest = &rest[other..];
+            if !(after_number && !rest.is_empty() && is_separator(text)) {
+                plaintext.push_str(text);
+            }
+            after_number = false;
+        }
+        Ok(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::A1Z26;
+
+    #[test]
+    fn encrypt_decrypt() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+
+        assert_eq!("1 2 3 24 25 26", A1Z26::encrypt("abcXYZ"));
+        assert_eq!("8 5 12 12 15, 23 15 18 12 4!", A1Z26::encrypt("Hello, World!"));
+        assert_eq!("HELLO, WORLD!", A1Z26::decrypt("8 5 12 12 15, 23 15 18 12 4!")?);
+
+        // Spaces between words are only kept with another delimiter
+        assert_eq!("HELLOWORLD", A1Z26::decrypt(&A1Z26::encrypt("Hello World"))?);
+        assert_eq!("8-5-12-12-15 23-15-18-12-4", A1Z26::encrypt_with_delimiter("Hello World", "-"));
+        assert_eq!("HELLO WORLD", A1Z26::decrypt("8-5-12-12-15 23-15-18-12-4")?);
+
+        // Hyphens can't be told apart from the delimiter
+        let unhyphenated = plaintext.replace('-', " ");
+        assert_eq!(unhyphenated.to_uppercase(), A1Z26::decrypt(&A1Z26::encrypt_with_delimiter(&unhyphenated, "-"))?);
+
+        let letters = plaintext.chars().filter(char::is_ascii_alphabetic).collect::<String>().to_uppercase();
+        assert_eq!(letters, A1Z26::decrypt(&A1Z26::encrypt(&letters))?);
+
+        assert!(A1Z26::decrypt("8 27").is_err());
+        assert!(A1Z26::decrypt("0").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn delimiters() -> anyhow::Result<()> {
+        assert_eq!("8,9", A1Z26::encrypt_with_delimiter("hi", ","));
+        assert_eq!("HI", A1Z26::decrypt_with_delimiter("8,9", ",")?);
+        assert_eq!("HI THERE", A1Z26::decrypt_with_delimiter("8, 9 20, 8, 5, 18, 5", ",")?);
+        assert_eq!("H-I", A1Z26::decrypt_with_delimiter("8-9", ",")?);
+
+        Ok(())
+    }
+}
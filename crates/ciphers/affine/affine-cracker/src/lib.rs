@@ -0,0 +1,111 @@
+use affine_cipher::{Affine, AffineBuilder as _, MULTIPLIERS};
+
+/// A cracker for affine ciphers. There are only 312 valid keys, so every one of them is tried, and the decryption that
+/// reads most like English is kept.
+#[derive(Debug, Default, Clone)]
+pub struct AffineCracker;
+
+impl AffineCracker {
+    /// The number of words a ciphertext needs before decryptions are filtered by their word hit rate. Shorter texts
+    /// have too few words for the hit rate to be meaningful.
+    const MIN_WORDS: usize = 4;
+
+    /// The word hit rate below which a decryption is rejected without scoring it fully, when the ciphertext has enough
+    /// words. English text has a hit rate of around `0.85`, and text decrypted with the wrong key around `0.2`. See
+    /// `cipher_utils::dictionary::word_hit_rate()`.
+    const MIN_WORD_HIT_RATE: f64 = 0.4;
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Cracks the given ciphertext and returns the most English-like decryption.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the ciphertext has no English letters.
+    pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(self.crack(ciphertext)?.2)
+    }
+
+    /// Cracks the given ciphertext by decrypting it with every key, and scoring each decryption by its englishness.
+    /// See `cipher_utils::englishness()`. Affine ciphers keep spaces, so when the ciphertext has several words,
+    /// decryptions with few dictionary words are rejected before being scored, unless every decryption would be.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The recovered key as `(a, b)`, along with the decrypted text.
+    ///
+    /// # Errors
+    /// If the ciphertext has no English letters, in which case every key gives the same decryption.
+    ///
+    /// # Performance
+    /// This is `O(n)`, but with a large constant, as the text is decrypted and scored 312 times.
+    pub fn crack(&self, ciphertext: &str) -> anyhow::Result<(u8, u8, String)> {
+        if !ciphertext.chars().any(|character| character.is_ascii_alphabetic()) {
+            anyhow::bail!("Error cracking Affine: The ciphertext has no letters");
+        }
+
+        let mut decryptions = Vec::with_capacity(MULTIPLIERS.len() * 26);
+        for a in MULTIPLIERS {
+            for b in 0..26 {
+                decryptions.push((a, b, Affine::new().key(a, b).build()?.decrypt(ciphertext)));
+            }
+        }
+
+        if ciphertext.split_whitespace().count() >= Self::MIN_WORDS {
+            let plausible = decryptions
+                .iter()
+                .filter(|(_, _, plaintext)| cipher_utils::dictionary::word_hit_rate(plaintext) >= Self::MIN_WORD_HIT_RATE)
+                .cloned()
+                .collect::<Vec<_>>();
+            if !plausible.is_empty() {
+                decryptions = plausible;
+            }
+        }
+
+        let mut best: Option<(u8, u8, String, f64)> = None;
+        for (a, b, plaintext) in decryptions {
+            let score = cipher_utils::englishness(&plaintext);
+            if best.as_ref().is_none_or(|(_, _, _, best_score)| score > *best_score) {
+                best = Some((a, b, plaintext, score));
+            }
+        }
+
+        let (a, b, plaintext, _) = best.unwrap();
+        Ok((a, b, plaintext))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AffineCracker;
+    use affine_cipher::{Affine, AffineBuilder as _};
+
+    #[test]
+    fn crack() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let ciphertext = Affine::new().key(7, 19).build()?.encrypt(plaintext);
+
+        assert_eq!((7, 19, plaintext.to_owned()), AffineCracker::new().crack(&ciphertext)?);
+        assert_eq!(plaintext, AffineCracker::new().decrypt(&ciphertext)?);
+
+        let short = Affine::new().key(5, 8).build()?.encrypt("Meet me by the old oak tree at midnight");
+        assert_eq!("Meet me by the old oak tree at midnight", AffineCracker::new().decrypt(&short)?);
+
+        // Too short for the word hit rate to be used
+        let single_word = Affine::new().key(3, 1).build()?.encrypt("Extraordinarily");
+        assert_eq!("Extraordinarily", AffineCracker::new().decrypt(&single_word)?);
+
+        assert!(AffineCracker::new().crack("123 456").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+/// The multipliers that are coprime to 26, and so are the only valid values of `a` in an affine key.
+pub const MULTIPLIERS: [u8; 12] = [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25];
+
+/// An affine cipher. Each letter with index `x` in the alphabet is encrypted as the letter with index `(a * x + b) % 26`,
+/// where `a` is coprime to 26 so that every letter can be decrypted again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affine {
+    /// The multiplier of the key, which is coprime to 26.
+    a: u8,
+
+    /// The shift of the key, in `[0, 26)`.
+    b: u8,
+}
+
+impl Affine {
+    /// Returns the number of valid keys of an affine cipher, which is every multiplier coprime to 26 with every shift.
+    ///
+    /// # Returns
+    /// The number of keys, which is `12 * 26 = 312`.
+    pub const fn keyspace_size() -> u128 {
+        MULTIPLIERS.len() as u128 * 26
+    }
+
+    /// Returns the key of this cipher.
+    ///
+    /// # Returns
+    /// The key as an `(a, b)` pair.
+    pub fn key(&self) -> (u8, u8) {
+        (self.a, self.b)
+    }
+
+    /// Encrypts the given plaintext. Casing is preserved, and characters that aren't English letters are left unchanged.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        Self::map_letters(plaintext, |index| ((self.a as u16 * index as u16 + self.b as u16) % 26) as u8)
+    }
+
+    /// Decrypts the given ciphertext. Casing is preserved, and characters that aren't English letters are left unchanged.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    pub fn decrypt(&self, ciphertext: &str) -> String {
+        let inverse = Self::inverse(self.a);
+        Self::map_letters(ciphertext, |index| (inverse as u16 * (index + 26 - self.b) as u16 % 26) as u8)
+    }
+
+    /// Returns the multiplicative inverse of the given multiplier modulo 26.
+    ///
+    /// # Parameters
+    /// - `a` - The multiplier to invert, which must be coprime to 26.
+    ///
+    /// # Returns
+    /// The number `n` in `[0, 26)` such that `a * n % 26 == 1`.
+    fn inverse(a: u8) -> u8 {
+        (1..26).find(|n| a as u16 * n % 26 == 1).unwrap() as u8
+    }
+
+    /// Replaces each English letter of the given text with the letter at the index that the given function maps its
+    /// index to, preserving its case.
+    ///
+    /// # Parameters
+    /// - `text` - The text to map the letters of.
+    /// - `map` - The function from the index of each letter to the index of its replacement.
+    ///
+    /// # Returns
+    /// The mapped text.
+    fn map_letters(text: &str, map: impl Fn(u8) -> u8) -> String {
+        text.chars()
+            .map(|character| {
+                if !character.is_ascii_alphabetic() {
+                    return character;
+                }
+                let base = if character.is_ascii_uppercase() { b'A' } else { b'a' };
+                (base + map(character as u8 - base)) as char
+            })
+            .collect()
+    }
+}
+
+pub trait AffineBuilder {
+    /// Sets the key of the cipher. `a` must be coprime to 26, i.e., one of `MULTIPLIERS`, and `b` must be less than 26.
+    fn key(self, a: u8, b: u8) -> impl AffineBuilder;
+
+    fn build(self) -> anyhow::Result<Affine>;
+}
+
+#[derive(Debug, Default)]
+struct IncompleteAffine {
+    key: Option<(u8, u8)>,
+}
+
+impl AffineBuilder for anyhow::Result<IncompleteAffine> {
+    fn key(self, a: u8, b: u8) -> impl AffineBuilder {
+        if let Ok(mut affine) = self {
+            if !MULTIPLIERS.contains(&a) {
+                anyhow::bail!("Invalid key: {a} is not coprime to 26");
+            }
+            if b >= 26 {
+                anyhow::bail!("Invalid key: {b} is not less than 26");
+            }
+            affine.key = Some((a, b));
+            Ok(affine)
+        } else {
+            self
+        }
+    }
+
+    fn build(self) -> anyhow::Result<Affine> {
+        if let Ok(affine) = self {
+            let Some((a, b)) = affine.key else {
+                anyhow::bail!("Error building Affine: No key provided.");
+            };
+
+            Ok(Affine { a, b })
+        } else {
+            Err(self.unwrap_err())
+        }
+    }
+}
+
+impl Affine {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> impl AffineBuilder {
+        Ok(IncompleteAffine::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Affine, AffineBuilder as _, MULTIPLIERS};
+
+    #[test]
+    fn encrypt_decrypt() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let affine = Affine::new().key(5, 8).build()?;
+
+        assert_eq!("Ihhwvc swfrcp", affine.encrypt("Affine cipher"));
+        assert_eq!(plaintext, affine.decrypt(&affine.encrypt(plaintext)));
+        assert_eq!((5, 8), affine.key());
+
+        for a in MULTIPLIERS {
+            let affine = Affine::new().key(a, 25).build()?;
+            assert_eq!(plaintext, affine.decrypt(&affine.encrypt(plaintext)));
+        }
+
+        assert_eq!(312, Affine::keyspace_size());
+
+        assert!(Affine::new().build().is_err());
+        assert!(Affine::new().key(13, 8).build().is_err());
+        assert!(Affine::new().key(5, 26).build().is_err());
+
+        Ok(())
+    }
+}
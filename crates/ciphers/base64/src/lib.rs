@@ -6,13 +6,98 @@ const CHARACTERS: &[u8] = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0
 
 impl Base64 {
     pub fn encrypt(plaintext: &str) -> String {
+        Self::standard().encrypt(plaintext)
+    }
+
+    /// Encodes the given text in base 64, with a newline after every `line_width` characters of output, as in PEM
+    /// (64 characters per line) or MIME (76 characters per line). There's no newline after the last line. Since
+    /// `Base64::decrypt()` ignores whitespace, the wrapped text decodes back to the original.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encode.
+    /// - `line_width` - The number of characters in each line. If this is `0`, the output isn't wrapped.
+    ///
+    /// # Returns
+    /// The wrapped base 64 text.
+    pub fn encrypt_wrapped(plaintext: &str, line_width: usize) -> String {
+        let ciphertext = Self::encrypt(plaintext);
+        if line_width == 0 {
+            return ciphertext;
+        }
+        ciphertext.chars().chunks(line_width).into_iter().map(|line| line.collect::<String>()).join("\n")
+    }
+
+    /// Decodes the given base 64 text as UTF-8. Whitespace is ignored, and the trailing `=` padding is optional.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The base 64 text to decode.
+    ///
+    /// # Returns
+    /// The decoded text.
+    ///
+    /// # Errors
+    /// If the text contains a character that isn't whitespace, padding, or in the base 64 alphabet, or if it contains
+    /// padding anywhere but the end, or if its last group of four characters has only one character, or if the
+    /// decoded bytes aren't valid UTF-8.
+    pub fn decrypt(ciphertext: &str) -> anyhow::Result<String> {
+        Self::standard().decrypt(ciphertext)
+    }
+
+    /// Creates a base 64 codec that uses the given alphabet in place of the standard `A-Za-z0-9+/`, such as bcrypt's
+    /// `./A-Za-z0-9`. The `n`th character of the alphabet encodes the 6-bit value `n`; Everything else about the
+    /// encoding, including the `=` padding, is the same as standard base 64.
+    ///
+    /// # Parameters
+    /// - `alphabet` - The 64 characters to encode with, in order.
+    ///
+    /// # Returns
+    /// The created codec.
+    ///
+    /// # Errors
+    /// If the alphabet doesn't have exactly 64 unique characters, or if it contains whitespace or `=`, which would be
+    /// ambiguous when decoding.
+    pub fn with_alphabet(alphabet: &str) -> anyhow::Result<CustomBase64> {
+        let characters = alphabet.chars().collect::<Vec<_>>();
+        if characters.len() != 64 || !characters.iter().all_unique() {
+            anyhow::bail!("Invalid Base64 alphabet: {alphabet:?} doesn't have exactly 64 unique characters");
+        }
+        if let Some(character) = characters.iter().find(|character| character.is_whitespace() || **character == '=') {
+            anyhow::bail!("Invalid Base64 alphabet: {alphabet:?} contains {character:?}, which is reserved for whitespace or padding");
+        }
+        Ok(CustomBase64 { characters })
+    }
+
+    /// Returns a codec with the standard base 64 alphabet.
+    fn standard() -> CustomBase64 {
+        CustomBase64 {
+            characters: CHARACTERS.iter().map(|character| *character as char).collect(),
+        }
+    }
+}
+
+/// A base 64 codec with a custom alphabet, created with `Base64::with_alphabet()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomBase64 {
+    /// The 64 characters that encode the 6-bit values, in order.
+    characters: Vec<char>,
+}
+
+impl CustomBase64 {
+    /// Encodes the UTF-8 bytes of the given text in base 64 with this codec's alphabet.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encode.
+    ///
+    /// # Returns
+    /// The encoded text.
+    pub fn encrypt(&self, plaintext: &str) -> String {
         plaintext
-            .chars()
+            .bytes()
             .chunks(3)
             .into_iter()
             .map(|triplet| {
                 let mut quadruplet = triplet
-                    .map(|character| format!("{:08b}", character as u8))
+                    .map(|byte| format!("{byte:08b}"))
                     .join("")
                     .chars()
                     .chunks(6)
@@ -20,12 +105,12 @@ impl Base64 {
                     .map(|chunk| {
                         let mut string = chunk.collect::<String>();
                         while string.len() < 6 {
-                            string = string + "0";
+                            string += "0";
                         }
-                        (*CHARACTERS.get(usize::from_str_radix(&string, 2).unwrap()).unwrap() as char).to_string()
+                        self.characters[usize::from_str_radix(&string, 2).unwrap()].to_string()
                     })
                     .collect::<String>();
-                while quadruplet.len() % 4 != 0 {
+                while quadruplet.chars().count() % 4 != 0 {
                     quadruplet += "=";
                 }
                 quadruplet
@@ -33,38 +118,71 @@ impl Base64 {
             .collect()
     }
 
-    pub fn decrypt(ciphertext: &str) -> String {
-        ciphertext
+    /// Decodes the given base 64 text with this codec's alphabet as UTF-8. Whitespace is ignored, and the trailing `=`
+    /// padding is optional.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The base 64 text to decode.
+    ///
+    /// # Returns
+    /// The decoded text.
+    ///
+    /// # Errors
+    /// If the text contains a character that isn't whitespace, padding, or in the alphabet, or if it contains padding
+    /// anywhere but the end, or if its last group of four characters has only one character, or if the decoded bytes
+    /// aren't valid UTF-8.
+    pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        String::from_utf8(self.decrypt_bytes(ciphertext)?).map_err(|error| anyhow::anyhow!("Error decrypting Base64: The decoded bytes aren't valid UTF-8: {error}"))
+    }
+
+    /// Decodes the given base 64 text with this codec's alphabet into raw bytes, for data that isn't text, such as
+    /// the salt of a bcrypt hash. Whitespace is ignored, and the trailing `=` padding is optional.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The base 64 text to decode.
+    ///
+    /// # Returns
+    /// The decoded bytes.
+    ///
+    /// # Errors
+    /// If the text contains a character that isn't whitespace, padding, or in the alphabet, or if it contains padding
+    /// anywhere but the end, or if its last group of four characters has only one character.
+    pub fn decrypt_bytes(&self, ciphertext: &str) -> anyhow::Result<Vec<u8>> {
+        if let Some((position, character)) = ciphertext
             .chars()
-            .filter(|character| !character.is_whitespace())
+            .enumerate()
+            .find(|(_, character)| !(character.is_whitespace() || *character == '=' || self.characters.contains(character)))
+        {
+            anyhow::bail!("Error decrypting Base64: Invalid character {character:?} at position {position}");
+        }
+
+        if ciphertext.trim_end_matches(|character: char| character == '=' || character.is_whitespace()).contains('=') {
+            anyhow::bail!("Error decrypting Base64: Padding found before the end of the text");
+        }
+
+        // Without padding, the length of the last group determines how many bytes it holds: 2 characters hold 1 byte
+        // and 3 characters hold 2 bytes, but 1 character can't hold a whole byte.
+        let data = ciphertext.chars().filter(|character| !character.is_whitespace() && *character != '=').collect::<Vec<_>>();
+        if data.len() % 4 == 1 {
+            anyhow::bail!("Error decrypting Base64: Invalid length {}; The last group has only one character", data.len());
+        }
+
+        Ok(data
             .chunks(4)
-            .into_iter()
-            .map(|quadruplet| {
-                quadruplet
-                    .map(|character| {
-                        if character == '=' {
-                            "2".to_owned()
-                        } else {
-                            format!("{:06b}", CHARACTERS.iter().position(|other| *other as char == character).unwrap())
-                        }
-                    })
-                    .join("")
-                    .chars()
-                    .chunks(8)
-                    .into_iter()
-                    .filter_map(|chunk| {
-                        let string = chunk.collect::<String>().trim_end_matches("2").to_owned();
-                        (string.len() == 8).then_some(u8::from_str_radix(&string, 2).unwrap() as char)
-                    })
-                    .collect::<String>()
+            .flat_map(|group| {
+                let bits = group
+                    .iter()
+                    .map(|character| format!("{:06b}", self.characters.iter().position(|other| other == character).unwrap()))
+                    .join("");
+                (0..group.len() - 1).map(move |byte| u8::from_str_radix(&bits[byte * 8..byte * 8 + 8], 2).unwrap())
             })
-            .collect()
+            .collect())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Base64;
+    use crate::{Base64, CHARACTERS};
 
     #[test]
     fn encrypt_decrypt() {
@@ -72,9 +190,97 @@ mod tests {
         let encrypted_letter = include_str!("../tests/encrypted_letter.txt").trim().replace("\r", "");
 
         let ciphertext = Base64::encrypt(&letter);
-        let plaintext = Base64::decrypt(&encrypted_letter);
+        let plaintext = Base64::decrypt(&encrypted_letter).unwrap();
 
         assert_eq!(letter, plaintext);
         assert_eq!(encrypted_letter, ciphertext);
     }
+
+    #[test]
+    fn invalid_characters() {
+        let error = Base64::decrypt("abc$def").unwrap_err();
+        assert_eq!("Error decrypting Base64: Invalid character '$' at position 3", error.to_string());
+
+        assert!(Base64::decrypt("aGVsbG8gd29ybGQ=").is_ok());
+        assert_eq!("hello world", Base64::decrypt("aGVsbG8g\nd29ybGQ=").unwrap());
+        assert!(Base64::decrypt("aGVsbG8gé29ybGQ=").is_err());
+        assert!(Base64::decrypt("=QGby92dg8GbsVGa").is_err());
+        assert_eq!("hi", Base64::decrypt("aGk=\n").unwrap());
+    }
+
+    #[test]
+    fn missing_padding() {
+        assert_eq!("Man", Base64::decrypt("TWFu").unwrap());
+        for (padded, unpadded) in [("TWE=", "TWE"), ("TQ==", "TQ"), ("aGVsbG8gd29ybGQ=", "aGVsbG8gd29ybGQ"), ("aGk=\n", "aGk\n")] {
+            assert_eq!(Base64::decrypt(padded).unwrap(), Base64::decrypt(unpadded).unwrap());
+        }
+        assert_eq!("Ma", Base64::decrypt("TWE").unwrap());
+        assert_eq!("M", Base64::decrypt("TQ").unwrap());
+
+        let letter = include_str!("../tests/letter.txt").trim().replace("\r", "");
+        let encrypted_letter = include_str!("../tests/encrypted_letter.txt").trim().replace("\r", "");
+        assert_eq!(letter, Base64::decrypt(encrypted_letter.trim_end_matches('=')).unwrap());
+
+        assert!(Base64::decrypt("TWFuT").is_err());
+    }
+
+    #[test]
+    fn utf8() {
+        assert_eq!("Grüße", Base64::decrypt("R3LDvMOfZQ==").unwrap());
+        assert_eq!("R3LDvMOfZQ==", Base64::encrypt("Grüße"));
+        assert_eq!("Köln, 東京", Base64::decrypt(&Base64::encrypt("Köln, 東京")).unwrap());
+
+        // A lone continuation byte isn't UTF-8
+        assert!(Base64::decrypt("gA==").is_err());
+    }
+
+    #[test]
+    fn wrapped() {
+        let letter = include_str!("../tests/letter.txt").trim().replace("\r", "");
+
+        for line_width in [64, 76] {
+            let ciphertext = Base64::encrypt_wrapped(&letter, line_width);
+            let lines = ciphertext.lines().collect::<Vec<_>>();
+            let (last, full) = lines.split_last().unwrap();
+            assert!(full.iter().all(|line| line.len() == line_width));
+            assert!(!last.is_empty() && last.len() <= line_width);
+            assert!(!ciphertext.ends_with('\n'));
+            assert_eq!(Base64::encrypt(&letter), ciphertext.replace('\n', ""));
+            assert_eq!(letter, Base64::decrypt(&ciphertext).unwrap());
+        }
+
+        assert_eq!("SGVs\nbG8=", Base64::encrypt_wrapped("Hello", 4));
+        assert_eq!(Base64::encrypt("Hello"), Base64::encrypt_wrapped("Hello", 0));
+    }
+
+    #[test]
+    fn custom_alphabet() -> anyhow::Result<()> {
+        let bcrypt = Base64::with_alphabet("./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")?;
+
+        // The salt of a bcrypt hash, and the same bytes in standard base 64, with each character replaced by the one at the
+        // same position in the standard alphabet
+        let salt = "N9qo8uLOickgx2ZMRZoMye";
+        let standard = salt
+            .chars()
+            .map(|character| {
+                let index = bcrypt.characters.iter().position(|other| *other == character).unwrap();
+                CHARACTERS[index] as char
+            })
+            .collect::<String>();
+        assert_eq!("P/sq+wNQkemiz4bOTbqO0g", standard);
+        assert_eq!(Base64::standard().decrypt_bytes(&standard)?, bcrypt.decrypt_bytes(salt)?);
+        assert_eq!(16, bcrypt.decrypt_bytes(salt)?.len());
+        assert!(bcrypt.decrypt(salt).is_err());
+
+        let letter = include_str!("../tests/letter.txt").trim().replace("\r", "");
+        assert_eq!(letter, bcrypt.decrypt(&bcrypt.encrypt(&letter))?);
+        assert_eq!(Base64::encrypt("Man"), Base64::with_alphabet(std::str::from_utf8(CHARACTERS)?)?.encrypt("Man"));
+        assert!(bcrypt.decrypt("N9qo+uLO").is_err());
+
+        assert!(Base64::with_alphabet("ABC").is_err());
+        assert!(Base64::with_alphabet(&"A".repeat(64)).is_err());
+        assert!(Base64::with_alphabet("=/ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789").is_err());
+
+        Ok(())
+    }
 }
@@ -1,11 +1,164 @@
+/// A Caesar cipher, which shifts each English letter forward by a fixed amount, wrapping from `Z` to `A`.
 pub struct Caeser {
+    /// The amount each letter is shifted by when encrypting, in `[0, 26)`.
     shift: u8,
 }
 
 impl Caeser {
+    /// Creates a Caesar cipher with the given shift. Shifts of 26 or more wrap around.
+    ///
+    /// # Parameters
+    /// - `shift` - The amount to shift each letter forward by when encrypting, i.e., `3` for Caesar's own cipher.
+    ///
+    /// # Returns
+    /// The created cipher.
     pub fn new(shift: u8) -> Self {
-        Self { shift }
+        Self { shift: shift % 26 }
     }
 
-    pub fn decrypt(&self, ciphertext: &str) -> String {}
+    /// Returns the number of distinct keys of a Caesar cipher, which is one for each shift, including the shift of `0`
+    /// that leaves text unchanged.
+    ///
+    /// # Returns
+    /// The number of keys, which is `26`.
+    pub const fn keyspace_size() -> u128 {
+        26
+    }
+
+    /// Encrypts the given plaintext. Casing is preserved, and characters that aren't English letters are left unchanged.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        cipher_utils::rotn(plaintext, self.shift)
+    }
+
+    /// Decrypts the given ciphertext. Casing is preserved, and characters that aren't English letters are left unchanged.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    pub fn decrypt(&self, ciphertext: &str) -> String {
+        cipher_utils::rotn(ciphertext, 26 - self.shift)
+    }
+}
+
+/// Returns the decryption of the given ciphertext under every shift.
+///
+/// # Parameters
+/// - `ciphertext` - The text to decrypt.
+///
+/// # Returns
+/// The 26 decryptions as `(shift, plaintext)` pairs, in order of shift from `0` to `25`.
+pub fn all_shifts(ciphertext: &str) -> Vec<(u8, String)> {
+    (0..26).map(|shift| (shift, Caeser::new(shift).decrypt(ciphertext))).collect()
+}
+
+/// A brute-force cracker for Caesar ciphers. There are only 26 shifts, so every one of them is tried and scored by how
+/// English-like its decryption is; See `cipher_utils::englishness()`.
+pub struct CaeserCracker;
+
+impl CaeserCracker {
+    /// The number of characters of each decryption shown in a row of `CaeserCracker::table()`.
+    const TABLE_PREVIEW_LENGTH: usize = 60;
+
+    /// Cracks the given ciphertext, returning the shift whose decryption is the most English-like.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The shift that the text was encrypted with, along with the decrypted text.
+    pub fn crack(ciphertext: &str) -> (u8, String) {
+        let (shift, plaintext, _) = Self::ranked(ciphertext).into_iter().next().unwrap();
+        (shift, plaintext)
+    }
+
+    /// Formats the decryption of the given ciphertext under every shift as a table, with the shift, the englishness
+    /// score, and the decrypted text of each, sorted from most to least English-like. Whitespace in the decryptions is
+    /// collapsed, and decryptions longer than 60 characters are cut off with `...`.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The table, with a header line followed by a line for each of the 26 shifts. There's no newline after the last
+    /// line.
+    pub fn table(ciphertext: &str) -> String {
+        let mut lines = vec!["Shift | Score | Plaintext".to_owned(), "------+-------+----------".to_owned()];
+        for (shift, plaintext, score) in Self::ranked(ciphertext) {
+            let plaintext = plaintext.split_whitespace().collect::<Vec<_>>().join(" ");
+            let preview = if plaintext.chars().count() > Self::TABLE_PREVIEW_LENGTH {
+                plaintext.chars().take(Self::TABLE_PREVIEW_LENGTH).collect::<String>() + "..."
+            } else {
+                plaintext
+            };
+            lines.push(format!("{shift:>5} | {score:.3} | {preview}"));
+        }
+        lines.join("\n")
+    }
+
+    /// Returns the decryption of the given ciphertext under every shift, with its englishness, sorted from most to
+    /// least English-like. Ties are kept in order of shift.
+    fn ranked(ciphertext: &str) -> Vec<(u8, String, f64)> {
+        let mut ranked = all_shifts(ciphertext)
+            .into_iter()
+            .map(|(shift, plaintext)| {
+                let score = cipher_utils::englishness(&plaintext);
+                (shift, plaintext, score)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|first, other| other.2.total_cmp(&first.2));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{all_shifts, Caeser, CaeserCracker};
+
+    #[test]
+    fn encrypt_decrypt() {
+        let plaintext = include_str!("../tests/letter.txt");
+        let caeser = Caeser::new(3);
+
+        assert_eq!("Yhql, ylgl, ylfl", caeser.encrypt("Veni, vidi, vici"));
+        assert_eq!(plaintext, caeser.decrypt(&caeser.encrypt(plaintext)));
+        assert_eq!(Caeser::new(29).encrypt(plaintext), caeser.encrypt(plaintext));
+        assert_eq!(plaintext, Caeser::new(0).encrypt(plaintext));
+
+        assert_eq!(26, Caeser::keyspace_size());
+
+        let shifts = all_shifts(&caeser.encrypt("Veni, vidi, vici"));
+        assert_eq!(26, shifts.len());
+        assert_eq!((3, "Veni, vidi, vici".to_owned()), shifts[3]);
+    }
+
+    #[test]
+    fn table() {
+        let plaintext = include_str!("../tests/letter.txt");
+        let ciphertext = Caeser::new(11).encrypt(plaintext);
+        assert_eq!((11, plaintext.to_owned()), CaeserCracker::crack(&ciphertext));
+
+        let table = CaeserCracker::table(&ciphertext);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(28, lines.len());
+        assert!(lines[2].starts_with("   11 | "), "Expected shift 11 to be ranked first, got {:?}", lines[2]);
+        assert!(lines[2].ends_with("..."));
+
+        let mut shifts = lines[2..]
+            .iter()
+            .map(|line| line.split('|').next().unwrap().trim().parse::<u8>().unwrap())
+            .collect::<Vec<_>>();
+        shifts.sort();
+        assert_eq!((0..26).collect::<Vec<_>>(), shifts);
+
+        let short = CaeserCracker::table(&Caeser::new(3).encrypt("Meet me\nat noon"));
+        assert!(short.contains("| Meet me at noon") && !short.ends_with('\n'));
+    }
 }
@@ -1,3 +1,4 @@
+use cipher_utils::cipher::Cipher;
 use itertools::Itertools as _;
 
 pub struct ColumnarTransposition {
@@ -17,20 +18,130 @@ impl ColumnarTransposition {
 
     pub fn encrypt(&self, plaintext: &str) -> String {
         let mut columns = vec![Vec::new(); self.key.len()];
-        for (index, character) in plaintext.char_indices() {
+        for (index, character) in plaintext.chars().enumerate() {
             columns[index % self.key.len()].push(character);
         }
 
-        columns
-            .iter()
-            .enumerate()
-            .sorted_by(|left, right| self.key.get(left.0).cmp(&self.key.get(right.0)))
-            .map(|column| column.1.iter().map(|letter| letter.to_string()).join(""))
-            .join("")
+        self.column_order().map(|column| columns[column].iter().collect::<String>()).join("")
     }
 
-    fn decrypt(&self, ciphertext: &str) -> String {
-        todo!()
+    /// Decrypts the given ciphertext. Unlike reciprocal ciphers, this is not the same as encrypting; The ciphertext is
+    /// cut back into the columns that `encrypt()` produced, which are then read row by row.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    pub fn decrypt(&self, ciphertext: &str) -> String {
+        let characters = ciphertext.chars().collect::<Vec<_>>();
+        let width = self.key.len();
+
+        // Earlier columns hold one more character when the text doesn't fill the last row
+        let mut columns = vec![&characters[0..0]; width];
+        let mut start = 0;
+        for column in self.column_order() {
+            let length = characters.len() / width + usize::from(column < characters.len() % width);
+            columns[column] = &characters[start..start + length];
+            start += length;
+        }
+
+        (0..characters.len()).map(|index| columns[index % width][index / width]).collect()
+    }
+
+    /// Recovers a key for a columnar transposition from a known plaintext and its ciphertext. The plaintext is split into
+    /// columns for each possible key length, and the order the columns are read off in is found by matching each column
+    /// against the next part of the ciphertext.
+    ///
+    /// The returned key gives each column its position in the read-off order, so it may differ from the original key
+    /// while encrypting identically; For example, the key digits `[4, 1, 9]` are recovered as `[1, 0, 2]`. If multiple
+    /// key lengths fit, the shortest is returned.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The known plaintext.
+    /// - `ciphertext` - The ciphertext that the plaintext was encrypted into.
+    ///
+    /// # Returns
+    /// The recovered key, which can be used with `ColumnarTransposition::from_key_digits()`.
+    ///
+    /// # Errors
+    /// If the plaintext and ciphertext have different lengths, or if the ciphertext isn't a columnar transposition of the
+    /// plaintext with a key of at most 256 columns.
+    ///
+    /// # Performance
+    /// This is `O(n^2)` in the worst case for a text of length `n`, but the correct key length is usually found quickly.
+    pub fn recover_key(plaintext: &str, ciphertext: &str) -> anyhow::Result<Vec<u8>> {
+        let plaintext = plaintext.chars().collect::<Vec<_>>();
+        let ciphertext = ciphertext.chars().collect::<Vec<_>>();
+        if plaintext.len() != ciphertext.len() {
+            anyhow::bail!(
+                "Error recovering columnar transposition key: Plaintext has {} characters, but ciphertext has {}.",
+                plaintext.len(),
+                ciphertext.len()
+            );
+        }
+
+        'widths: for width in 1..=plaintext.len().clamp(1, 256) {
+            let columns = (0..width)
+                .map(|column| plaintext.iter().skip(column).step_by(width).copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+
+            let mut key = vec![None; width];
+            let mut start = 0;
+            for position in 0..width {
+                let Some(column) = (0..width).find(|column| key[*column].is_none() && ciphertext.get(start..start + columns[*column].len()) == Some(&columns[*column])) else {
+                    continue 'widths;
+                };
+                key[column] = Some(position as u8);
+                start += columns[column].len();
+            }
+
+            return Ok(key.into_iter().flatten().collect());
+        }
+
+        anyhow::bail!("Error recovering columnar transposition key: The ciphertext is not a columnar transposition of the plaintext.");
+    }
+
+    /// Returns the key lengths that could have produced a ciphertext of the given length, most likely first, such as for
+    /// ordering the key lengths tried by a cracker. Messages were often padded to fill the last row of the grid, so key
+    /// lengths that divide the length come first, followed by those that leave the last row one character short or one
+    /// character over. Every other key length follows, since `encrypt()` doesn't pad, and an unpadded message fits any key
+    /// length.
+    ///
+    /// # Parameters
+    /// - `ciphertext_len` - The number of characters of the ciphertext.
+    ///
+    /// # Returns
+    /// Every key length in `[2, ciphertext_len / 2]`, so that the grid has at least two columns and two rows, ranked as
+    /// above and ascending within each rank.
+    ///
+    /// # Performance
+    /// This is `O(n log n)` for a ciphertext length of `n`.
+    pub fn key_length_candidates(ciphertext_len: usize) -> Vec<usize> {
+        (2..=ciphertext_len / 2)
+            .sorted_by_key(|key_length| match ciphertext_len % key_length {
+                0 => 0,
+                1 => 1,
+                remainder if remainder == key_length - 1 => 1,
+                _ => 2,
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the columns in the order they're read off, which is the order of their key values. Columns
+    /// with equal key values are read from left to right.
+    fn column_order(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.key.len()).sorted_by_key(|column| self.key[*column])
+    }
+}
+
+impl Cipher for ColumnarTransposition {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        Ok(ColumnarTransposition::encrypt(self, plaintext))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(ColumnarTransposition::decrypt(self, ciphertext))
     }
 }
 
@@ -47,6 +158,56 @@ mod tests {
         let columnar_transposition = ColumnarTransposition::from_key_digits(key);
 
         assert_eq!(ciphertext, columnar_transposition.encrypt(plaintext));
-        //assert_eq!(plaintext, columnar_transposition.decrypt(ciphertext));
+        assert_eq!(plaintext, columnar_transposition.decrypt(ciphertext));
+    }
+
+    #[test]
+    fn decrypt_is_not_encrypt() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let columnar_transposition = ColumnarTransposition::new("ZEBRAS");
+
+        cipher_utils::cipher::check_cipher(&columnar_transposition, plaintext)?;
+        assert_ne!(columnar_transposition.encrypt(plaintext), columnar_transposition.decrypt(plaintext));
+        assert_eq!("EVLNACDTESEAROFODEECWIREE", columnar_transposition.encrypt("WEAREDISCOVEREDFLEEATONCE"));
+        assert_eq!("WEAREDISCOVEREDFLEEATONCE", columnar_transposition.decrypt("EVLNACDTESEAROFODEECWIREE"));
+        assert_eq!("", columnar_transposition.decrypt(""));
+        Ok(())
+    }
+
+    #[test]
+    fn recover_key() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let ciphertext = include_str!("../tests/encrypted_letter.txt");
+
+        let key = ColumnarTransposition::recover_key(plaintext, ciphertext)?;
+        assert_eq!(vec![1, 0, 3, 4, 2, 6, 5, 7], key);
+        assert_eq!(ciphertext, ColumnarTransposition::from_key_digits(&key).encrypt(plaintext));
+        assert_eq!(plaintext, ColumnarTransposition::from_key_digits(&key).decrypt(ciphertext));
+
+        let ciphertext = ColumnarTransposition::new("ZEBRAS").encrypt(plaintext);
+        assert_eq!(vec![5, 2, 1, 3, 0, 4], ColumnarTransposition::recover_key(plaintext, &ciphertext)?);
+
+        assert!(ColumnarTransposition::recover_key(plaintext, &ciphertext[1..]).is_err());
+        assert!(ColumnarTransposition::recover_key("ABCDEF", "FEDCBX").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn key_length_candidates() {
+        let plaintext = include_str!("../tests/letter.txt");
+
+        // Unpadded, so the key length doesn't fit the length of the text
+        for key in ["PUZZLES", "CIPHER", "KEYWORDS", "TRANSPOSE"] {
+            let ciphertext = ColumnarTransposition::new(key).encrypt(plaintext);
+            assert!(ColumnarTransposition::key_length_candidates(ciphertext.chars().count()).contains(&key.len()));
+        }
+
+        // Divisors first, then near-divisors, then the rest
+        let ciphertext = ColumnarTransposition::new("ZEBRAS").encrypt("WEAREDISCOVEREDFLEEATONCE");
+        let candidates = ColumnarTransposition::key_length_candidates(ciphertext.len());
+        assert_eq!(vec![5, 2, 3, 4, 6, 8, 12, 7, 9, 10, 11], candidates);
+
+        assert!(ColumnarTransposition::key_length_candidates(3).is_empty());
     }
 }
@@ -1,127 +1,241 @@
 use std::io::Write;
 
+use cipher_utils::progress::ProgressTracker;
 use enigma_simulator::{EnigmaBuilder as _, EnigmaMachine, EnigmaResult};
 
 pub fn decrypt_enigma(ciphertext: &str) -> EnigmaResult<()> {
-    let plugboard = "BY EW FZ GI MQ RV UX";
-    let reflector = "B";
+    let plaintext = EnigmaCracker::new().known_plugboard("BY EW FZ GI MQ RV UX").known_reflector("B").decrypt(ciphertext)?;
+    println!("Plaintext: {plaintext}");
+
+    Ok(())
+}
+
+/// A rotor order, as the numbers of the first, second, and third rotors.
+pub type Rotors = (u8, u8, u8);
+
+/// Decrypts the given ciphertext with every order of three different rotors, keeping every other setting fixed. This is a
+/// simpler and more inspectable alternative to `EnigmaCracker` for short messages, where trying every order is fast.
+///
+/// Each decryption is scored by how English-like it is; See `cipher_utils::englishness()`.
+///
+/// # Parameters
+/// - `plugboard` - The plugboard of the machine, as space-separated letter pairs.
+/// - `reflector` - The reflector of the machine.
+/// - `positions` - The ring positions of the machine.
+/// - `settings` - The ring settings of the machine.
+/// - `ciphertext` - The text to decrypt.
+///
+/// # Returns
+/// The rotor order, plaintext, and score of every decryption, sorted from best to worst.
+///
+/// # Errors
+/// If any of the given settings are invalid.
+pub fn decrypt_all_orders(plugboard: &str, reflector: &str, positions: (u8, u8, u8), settings: (u8, u8, u8), ciphertext: &str) -> EnigmaResult<Vec<(Rotors, String, f64)>> {
+    let mut decryptions = Vec::new();
+    for rotors in candidates(None, 8)
+        .into_iter()
+        .filter(|(first, second, third)| first != second && second != third && first != third)
+    {
+        let plaintext = EnigmaMachine::new()
+            .plugboard(plugboard)
+            .reflector(reflector)
+            .rotors(rotors.0, rotors.1, rotors.2)
+            .ring_positions(positions.0, positions.1, positions.2)
+            .ring_settings(settings.0, settings.1, settings.2)?
+            .decrypt(ciphertext);
+        let score = cipher_utils::englishness(&plaintext);
+        decryptions.push((rotors, plaintext, score));
+    }
 
-    let (rotors, offsets) = best_rotors(plugboard, reflector, ciphertext)?;
-    println!("Best rotors: {}, {}, {}", rotors.0, rotors.1, rotors.2);
-    println!("Best offsets: {}, {}, {}", offsets.0, offsets.1, offsets.2);
+    decryptions.sort_by(|first, other| other.2.total_cmp(&first.2));
+    Ok(decryptions)
+}
 
-    let ring_settings = best_ring_settings(reflector, plugboard, rotors, offsets, ciphertext)?;
-    println!("Best ring settings: {}, {}, {}", ring_settings.0, ring_settings.1, ring_settings.2);
+/// A brute-force Enigma cracker. The plugboard and reflector must be known; The rotors, ring positions, and ring settings
+/// are searched, unless they're provided as known settings, in which case the search skips them entirely.
+///
+/// The search happens in two stages. First, each combination of rotors and ring positions is tried with the ring settings
+/// fixed, and the combination whose output is the most English-like is kept; See `cipher_utils::englishness()`. Then,
+/// each ring setting is tried with those rotors and positions.
+pub struct EnigmaCracker {
+    /// The plugboard of the machine, as space-separated letter pairs.
+    plugboard: String,
 
-    let plaintext = &EnigmaMachine::new()
-        .reflector(reflector)
-        .plugboard(plugboard)
-        .rotors(rotors.0, rotors.1, rotors.2)
-        .ring_positions(offsets.0, offsets.1, offsets.2)
-        .ring_settings(ring_settings.0, ring_settings.1, ring_settings.2)?
-        .decrypt(ciphertext);
+    /// The reflector of the machine.
+    reflector: String,
 
-    println!("Plaintext: {plaintext}");
+    /// The rotors of the machine, if they're known.
+    rotors: Option<(u8, u8, u8)>,
 
-    Ok(())
+    /// The ring positions of the machine, if they're known.
+    positions: Option<(u8, u8, u8)>,
+
+    /// The ring settings of the machine, if they're known.
+    ring_settings: Option<(u8, u8, u8)>,
 }
 
-#[allow(clippy::type_complexity)]
-fn best_rotors(plugboard: &str, reflector: &str, ciphertext: &str) -> EnigmaResult<((u8, u8, u8), (u8, u8, u8))> {
-    let mut plaintexts = Vec::new();
-    let total = 8 * 8 * 8 * 26 * 26 * 26;
-    let mut iteration = 0;
-
-    println!("\n");
-
-    for rotor_1 in 1..=8 {
-        for rotor_2 in 1..=8 {
-            for rotor_3 in 1..=8 {
-                for offset_1 in 1..=26 {
-                    for offset_2 in 1..=26 {
-                        for offset_3 in 1..=26 {
-                            let machine = EnigmaMachine::new()
-                                .plugboard(plugboard)
-                                .reflector(reflector)
-                                .rotors(rotor_1, rotor_2, rotor_3)
-                                .ring_positions(offset_1, offset_2, offset_3)
-                                .ring_settings(1, 1, 1)?;
-                            let plaintext = machine.decrypt(ciphertext);
-                            let distance = (index_of_coincidence(&plaintext) - 0.0667).abs();
-                            plaintexts.push((distance, ((rotor_1, rotor_2, rotor_3), (offset_1, offset_2, offset_3))));
-
-                            iteration += 1;
-                            let progress = 100f64 * (iteration as f64 / total as f64);
-                            print!("\x1B[A");
-                            println!("Finding best rotor settings... ({:.2}%)", progress);
-                            std::io::stdout().flush().unwrap();
-                        }
-                    }
-                }
-            }
+impl Default for EnigmaCracker {
+    fn default() -> Self {
+        Self {
+            plugboard: String::new(),
+            reflector: "B".to_owned(),
+            rotors: None,
+            positions: None,
+            ring_settings: None,
         }
     }
-
-    Ok(plaintexts.iter().min_by(|first, second| first.0.total_cmp(&second.0)).unwrap().1)
 }
 
-fn best_ring_settings(reflector: &str, plugboard: &str, rotors: (u8, u8, u8), ring_positions: (u8, u8, u8), ciphertext: &str) -> EnigmaResult<(u8, u8, u8)> {
-    let mut plaintexts = Vec::new();
-    let mut iteration = 1;
-    let total = 26 * 26 * 26;
-
-    println!();
-
-    for offset_1 in 1..=26 {
-        for offset_2 in 1..=26 {
-            for offset_3 in 1..=26 {
-                let machine = EnigmaMachine::new()
-                    .plugboard(plugboard)
-                    .reflector(reflector)
-                    .rotors(rotors.0, rotors.1, rotors.2)
-                    .ring_positions(ring_positions.0, ring_positions.1, ring_positions.2)
-                    .ring_settings(ring_positions.0, ring_positions.1, ring_positions.2)?;
-                let plaintext = machine.decrypt(ciphertext);
-                let distance = (index_of_coincidence(&plaintext) - 0.0667).abs();
-                plaintexts.push((distance, (offset_1, offset_2, offset_3)));
-
-                iteration += 1;
-                let progress = 100f64 * (iteration as f64 / total as f64);
+impl EnigmaCracker {
+    /// Creates a new cracker with an empty plugboard, reflector B, and nothing else known.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the plugboard of the machine to crack, as space-separated letter pairs such as `"AV BS CG"`.
+    pub fn known_plugboard(mut self, plugboard: &str) -> Self {
+        plugboard.clone_into(&mut self.plugboard);
+        self
+    }
+
+    /// Sets the reflector of the machine to crack, such as `"B"`.
+    pub fn known_reflector(mut self, reflector: &str) -> Self {
+        reflector.clone_into(&mut self.reflector);
+        self
+    }
+
+    /// Sets the rotors of the machine to crack, in the same order as `EnigmaBuilder::rotors()`, or `None` to search them.
+    pub fn known_rotors(mut self, rotors: Option<(u8, u8, u8)>) -> Self {
+        self.rotors = rotors;
+        self
+    }
+
+    /// Sets the ring positions of the machine to crack, in the same order as `EnigmaBuilder::ring_positions()`, or `None`
+    /// to search them.
+    pub fn known_positions(mut self, positions: Option<(u8, u8, u8)>) -> Self {
+        self.positions = positions;
+        self
+    }
+
+    /// Sets the ring settings of the machine to crack, in the same order as `EnigmaBuilder::ring_settings()`, or `None`
+    /// to search them.
+    pub fn known_ring_settings(mut self, ring_settings: Option<(u8, u8, u8)>) -> Self {
+        self.ring_settings = ring_settings;
+        self
+    }
+
+    /// Returns the number of machine configurations that `decrypt()` will try with the current known settings.
+    ///
+    /// # Returns
+    /// The total number of configurations across both stages of the search.
+    pub fn search_space(&self) -> usize {
+        let rotors_and_positions = if self.rotors.is_some() && self.positions.is_some() {
+            0
+        } else {
+            candidates(self.rotors, 8).len() * candidates(self.positions, 26).len()
+        };
+        let ring_settings = if self.ring_settings.is_some() { 0 } else { candidates(None, 26).len() };
+        rotors_and_positions + ring_settings
+    }
+
+    /// Cracks the given ciphertext, searching every setting that isn't known.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to crack.
+    ///
+    /// # Returns
+    /// The most likely plaintext.
+    ///
+    /// # Errors
+    /// If the known settings are invalid.
+    pub fn decrypt(&self, ciphertext: &str) -> EnigmaResult<String> {
+        let (rotors, positions) = match (self.rotors, self.positions) {
+            (Some(rotors), Some(positions)) => (rotors, positions),
+            _ => self.best_rotors(ciphertext)?,
+        };
+        println!("Best rotors: {}, {}, {}", rotors.0, rotors.1, rotors.2);
+        println!("Best offsets: {}, {}, {}", positions.0, positions.1, positions.2);
+
+        let ring_settings = match self.ring_settings {
+            Some(ring_settings) => ring_settings,
+            None => self.best_ring_settings(rotors, positions, ciphertext)?,
+        };
+        println!("Best ring settings: {}, {}, {}", ring_settings.0, ring_settings.1, ring_settings.2);
+
+        Ok(self.machine(rotors, positions, ring_settings)?.decrypt(ciphertext))
+    }
+
+    /// Builds a machine with this cracker's plugboard and reflector and the given settings.
+    fn machine(&self, rotors: (u8, u8, u8), positions: (u8, u8, u8), ring_settings: (u8, u8, u8)) -> EnigmaResult<EnigmaMachine> {
+        EnigmaMachine::new()
+            .plugboard(&self.plugboard)
+            .reflector(&self.reflector)
+            .rotors(rotors.0, rotors.1, rotors.2)
+            .ring_positions(positions.0, positions.1, positions.2)
+            .ring_settings(ring_settings.0, ring_settings.1, ring_settings.2)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn best_rotors(&self, ciphertext: &str) -> EnigmaResult<((u8, u8, u8), (u8, u8, u8))> {
+        let mut plaintexts = Vec::new();
+        let rotor_candidates = candidates(self.rotors, 8);
+        let position_candidates = candidates(self.positions, 26);
+        let ring_settings = self.ring_settings.unwrap_or((1, 1, 1));
+        let mut progress = ProgressTracker::new(rotor_candidates.len() * position_candidates.len());
+
+        println!("\n");
+
+        for rotors in &rotor_candidates {
+            for positions in &position_candidates {
+                let plaintext = self.machine(*rotors, *positions, ring_settings)?.decrypt(ciphertext);
+                plaintexts.push((cipher_utils::englishness(&plaintext), (*rotors, *positions)));
+
+                progress.advance();
                 print!("\x1B[A");
-                println!("Finding best ring settings... ({:.2}%)", progress);
+                println!("Finding best rotor settings... ({progress})");
                 std::io::stdout().flush().unwrap();
             }
         }
+
+        Ok(plaintexts.iter().max_by(|first, second| first.0.total_cmp(&second.0)).unwrap().1)
     }
 
-    Ok(plaintexts.iter().min_by(|first, second| first.0.total_cmp(&second.0)).unwrap().1)
-}
+    fn best_ring_settings(&self, rotors: (u8, u8, u8), positions: (u8, u8, u8), ciphertext: &str) -> EnigmaResult<(u8, u8, u8)> {
+        let mut plaintexts = Vec::new();
+        let mut progress = ProgressTracker::new(26 * 26 * 26);
 
-fn index_of_coincidence(text: &str) -> f64 {
-    let mut frequency = [0u32; 26];
-    let mut total_letters = 0;
+        println!();
 
-    for c in text.chars() {
-        if c.is_alphabetic() {
-            let idx = c.to_ascii_lowercase() as usize - 'a' as usize;
-            frequency[idx] += 1;
-            total_letters += 1;
+        for ring_settings in candidates(None, 26) {
+            let plaintext = self.machine(rotors, positions, ring_settings)?.decrypt(ciphertext);
+            plaintexts.push((cipher_utils::englishness(&plaintext), ring_settings));
+
+            progress.advance();
+            print!("\x1B[A");
+            println!("Finding best ring settings... ({progress})");
+            std::io::stdout().flush().unwrap();
         }
-    }
 
-    if total_letters < 2 {
-        return 0.0;
+        Ok(plaintexts.iter().max_by(|first, second| first.0.total_cmp(&second.0)).unwrap().1)
     }
+}
 
-    let mut numerator = 0u32;
-
-    for &count in &frequency {
-        numerator += count * (count - 1);
+/// Returns the settings to search for one dimension of the machine: Either only the known setting, or every triple of
+/// values in `[1, max]`.
+fn candidates(known: Option<(u8, u8, u8)>, max: u8) -> Vec<(u8, u8, u8)> {
+    if let Some(known) = known {
+        return vec![known];
     }
 
-    let denominator = total_letters * (total_letters - 1);
-
-    numerator as f64 / denominator as f64
+    let mut candidates = Vec::new();
+    for first in 1..=max {
+        for second in 1..=max {
+            for third in 1..=max {
+                candidates.push((first, second, third));
+            }
+        }
+    }
+    candidates
 }
 
 // fn best_plugboard(plugboard: &str, reflector: &str, ciphertext: &str) -> EnigmaResult<String> {
@@ -132,7 +246,7 @@ fn index_of_coincidence(text: &str) -> f64 {
 mod tests {
     use enigma_simulator::{EnigmaBuilder, EnigmaMachine, EnigmaResult};
 
-    use crate::{best_ring_settings, best_rotors};
+    use crate::{decrypt_all_orders, EnigmaCracker};
 
     #[test]
     #[ignore]
@@ -141,7 +255,7 @@ mod tests {
         let reflector = "B";
         let ciphertext = include_str!("../tests/encrypted_letter.txt");
 
-        let (rotors, offsets) = best_rotors(plugboard, reflector, ciphertext)?;
+        let (rotors, offsets) = EnigmaCracker::new().known_plugboard(plugboard).known_reflector(reflector).best_rotors(ciphertext)?;
 
         assert_eq!(rotors, (5, 8, 3));
         assert_eq!(offsets, (5, 22, 3));
@@ -156,11 +270,12 @@ mod tests {
         let reflector = "B";
         let ciphertext = include_str!("../tests/encrypted_letter.txt");
 
-        let (rotors, offsets) = best_rotors(plugboard, reflector, ciphertext)?;
+        let cracker = EnigmaCracker::new().known_plugboard(plugboard).known_reflector(reflector);
+        let (rotors, offsets) = cracker.best_rotors(ciphertext)?;
         println!("Best rotors: {}, {}, {}", rotors.0, rotors.1, rotors.2);
         println!("Best offsets: {}, {}, {}", offsets.0, offsets.1, offsets.2);
 
-        let ring_settings = best_ring_settings(reflector, plugboard, rotors, offsets, ciphertext)?;
+        let ring_settings = cracker.best_ring_settings(rotors, offsets, ciphertext)?;
         println!("Best ring settings: {}, {}, {}", ring_settings.0, ring_settings.1, ring_settings.2);
 
         let plaintext = &EnigmaMachine::new()
@@ -175,4 +290,55 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn known_settings() -> EnigmaResult<()> {
+        let cracker = EnigmaCracker::new().known_plugboard("BY EW FZ GI MQ RV UX");
+        assert_eq!(8 * 8 * 8 * 26 * 26 * 26 + 26 * 26 * 26, cracker.search_space());
+
+        // Known rotors only search the positions and ring settings
+        let cracker = cracker.known_rotors(Some((5, 8, 3)));
+        assert_eq!(26 * 26 * 26 + 26 * 26 * 26, cracker.search_space());
+
+        let cracker = cracker.known_positions(Some((5, 22, 3)));
+        assert_eq!(26 * 26 * 26, cracker.search_space());
+
+        // Everything known skips the search entirely
+        let cracker = cracker.known_ring_settings(Some((1, 1, 1)));
+        assert_eq!(0, cracker.search_space());
+
+        let ciphertext = "Attack at dawn";
+        let expected = EnigmaMachine::new()
+            .plugboard("BY EW FZ GI MQ RV UX")
+            .reflector("B")
+            .rotors(5, 8, 3)
+            .ring_positions(5, 22, 3)
+            .ring_settings(1, 1, 1)?
+            .decrypt(ciphertext);
+        assert_eq!(expected, cracker.decrypt(ciphertext)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn all_orders() -> EnigmaResult<()> {
+        let plaintext = "THEENIGMAMACHINEWASUSEDBYTHEGERMANMILITARYTOENCRYPTITSMESSAGESDURINGTHESECONDWORLDWARANDTHEBREAKINGOFITSCODESATBLETCHLEYPARKISOFTENSAIDTOHAVESHORTENEDTHEWARBYSEVERALYEARS";
+        let ciphertext = EnigmaMachine::new()
+            .plugboard("AV BS CG DL FU HZ IN KM OW RX")
+            .reflector("B")
+            .rotors(2, 4, 5)
+            .ring_positions(2, 12, 1)
+            .ring_settings(2, 21, 12)?
+            .encrypt(plaintext);
+
+        let decryptions = decrypt_all_orders("AV BS CG DL FU HZ IN KM OW RX", "B", (2, 12, 1), (2, 21, 12), &ciphertext)?;
+        assert_eq!(8 * 7 * 6, decryptions.len());
+        assert!(decryptions.windows(2).all(|pair| pair[0].2 >= pair[1].2));
+
+        let (rotors, best_plaintext, _) = &decryptions[0];
+        assert_eq!((2, 4, 5), *rotors);
+        assert_eq!(plaintext, best_plaintext);
+
+        Ok(())
+    }
 }
@@ -1,14 +1,15 @@
 /// The standard ordering of the capital English alphabet, A-Z.
 pub const ALPHABET: Alphabet = Alphabet {
-    alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZ".as_bytes(),
+    alphabet: std::borrow::Cow::Borrowed("ABCDEFGHIJKLMNOPQRSTUVWXYZ".as_bytes()),
 };
 
 /// An immutable ordering of the English alphabet. This is used by `Rotor`s and `Reflector`s, and provides helper functionality
 /// such as getting the index of a character and getting the character at an index.
 pub struct Alphabet<'letters> {
     /// The letters in the order of this alphabet, as a byte slice. Alphabets are ASCII-only, meaning each character can safely
-    /// be stored as a single byte.
-    alphabet: &'letters [u8],
+    /// be stored as a single byte. This is borrowed for the built-in rotors and reflectors, and owned for alphabets converted
+    /// from other types.
+    alphabet: std::borrow::Cow<'letters, [u8]>,
 }
 
 impl<'a> Alphabet<'a> {
@@ -36,7 +37,9 @@ impl<'a> Alphabet<'a> {
             anyhow::bail!("Invalid character found in alphabet: {alphabet}");
         }
 
-        Ok(Self { alphabet: alphabet.as_bytes() })
+        Ok(Self {
+            alphabet: std::borrow::Cow::Borrowed(alphabet.as_bytes()),
+        })
     }
 
     /// Creates a new `Alphabet` without checking for argument validity. This will not panic if the given argument is invalid;
@@ -50,7 +53,9 @@ impl<'a> Alphabet<'a> {
     /// # Returns
     /// The constructed alphabet
     pub fn new_unchecked(alphabet: &'a str) -> Self {
-        Self { alphabet: alphabet.as_bytes() }
+        Self {
+            alphabet: std::borrow::Cow::Borrowed(alphabet.as_bytes()),
+        }
     }
 
     /// Returns the zero-based index of the given letter in this alphabet, or `None` if the given charcter is not alphabetic.
@@ -117,6 +122,49 @@ impl<'a> Alphabet<'a> {
     }
 }
 
+impl TryFrom<&cipher_utils::alphabet::Alphabet> for Alphabet<'static> {
+    type Error = anyhow::Error;
+
+    /// Converts an alphabet from `cipher-utils` into an Enigma alphabet with the same ordering. Enigma alphabets are uppercase
+    /// ASCII, so letters are converted to uppercase.
+    ///
+    /// # Errors
+    /// If the given alphabet isn't exactly the 26 English letters in some order, ignoring case.
+    fn try_from(alphabet: &cipher_utils::alphabet::Alphabet) -> anyhow::Result<Self> {
+        let letters = alphabet.characters().iter().map(char::to_ascii_uppercase).collect::<Vec<_>>();
+        if let Some(letter) = letters.iter().find(|letter| !letter.is_ascii_uppercase()) {
+            anyhow::bail!("Error converting alphabet to an Enigma alphabet: {letter:?} is not an English letter");
+        }
+
+        let mut unique = letters.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() != letters.len() || letters.len() != 26 {
+            anyhow::bail!(
+                "Error converting alphabet to an Enigma alphabet: {:?} isn't 26 unique letters",
+                letters.iter().collect::<String>()
+            );
+        }
+
+        Ok(Self {
+            alphabet: std::borrow::Cow::Owned(letters.into_iter().map(|letter| letter as u8).collect()),
+        })
+    }
+}
+
+impl TryFrom<&Alphabet<'_>> for cipher_utils::alphabet::Alphabet {
+    type Error = anyhow::Error;
+
+    /// Converts an Enigma alphabet into a caseless alphabet from `cipher-utils` with the same ordering.
+    ///
+    /// # Errors
+    /// If the Enigma alphabet was created with `Alphabet::new_unchecked()` and isn't a valid caseless alphabet.
+    fn try_from(alphabet: &Alphabet<'_>) -> anyhow::Result<Self> {
+        let letters = alphabet.letters();
+        cipher_utils::alphabet::Alphabet::caseless(&letters).map_err(|error| anyhow::anyhow!("Error converting Enigma alphabet {letters:?} to a caseless alphabet: {error}"))
+    }
+}
+
 /// A wrapper around a `u8` that denotes a valid "alphabet index"; That is, a number that's always in `[0, 26)`.
 /// `AlphabetIndex` provides safety by performing bounds checks upon creation and conciseness by allowing addition
 /// and subtraction to be performed mod 26 with operator overloading.
@@ -1,12 +1,21 @@
-#[derive(Default)]
+use crate::transliteration::Transliteration;
+
+#[derive(Default, Clone)]
 pub struct MachineOptions {
     pub clear_casing: bool,
     pub clear_punctuation: bool,
 
+    /// Whether to output every letter in lowercase, regardless of the casing of the input. See `EnigmaBuilder::lowercase_output()`.
+    pub lowercase_output: bool,
+
+    /// Whether to keep the exact layout of the input, encrypting only its English letters and leaving every other
+    /// character where it was. See `EnigmaBuilder::preserve_layout()`.
+    pub preserve_layout: bool,
+
     /// Whether to print debug information during encryption/decryption. If this is set to `true`, then at each stage of encryption,
     /// the machine will print information about the current character and how it is being transformed. For example:
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let ciphertext = "HI";
     /// let machine = EnigmaMachine::new()
     /// 	.rotors(1, 2, 3)
@@ -21,7 +30,7 @@ pub struct MachineOptions {
     ///
     /// will convert "HI" to "IJ", and will print:
     ///
-    /// ```
+    /// ```text
     /// Decrypting character: 'H'
     /// 	Passing character through plugboard: 'H' -> 'H'
     /// 	Passing character through third rotor: 'H' -> 'C'
@@ -46,6 +55,10 @@ pub struct MachineOptions {
     /// 	Finalized character: 'J'
     /// ```
     pub debug: bool,
+
+    /// The table used to spell out characters such as digits as letters before encryption, and to translate them back
+    /// after decryption. See `EnigmaMachine::with_transliteration()`.
+    pub transliteration: Option<Transliteration>,
 }
 
 /// Performs a caeser shift on the given text by the given amount. This is used during the
@@ -1,13 +1,28 @@
 //! An absurdly fast and highly flexible Enigma machine simulation, encryption, and decryption library.
 
-mod alphabet;
+pub mod alphabet;
 mod enigma;
+#[cfg(feature = "morse-code")]
+mod morse;
 mod reflector;
 mod rotor;
+mod rotor_set;
 mod safe_enigma;
+mod settings_warning;
+mod stateful_enigma;
+mod transliteration;
+mod uhr;
 mod unsafe_enigma;
 
+#[cfg(feature = "morse-code")]
+pub use crate::morse::{enigma_then_morse, morse_then_enigma};
+pub use crate::reflector::Reflector;
+pub use crate::rotor::{CustomRotor, Rotor};
+pub use crate::rotor_set::RotorSet;
 pub use crate::safe_enigma::*;
+pub use crate::settings_warning::SettingsWarning;
+pub use crate::stateful_enigma::*;
+pub use crate::transliteration::Transliteration;
 pub use crate::unsafe_enigma::*;
 
 /// The result type returned from enigma functions.
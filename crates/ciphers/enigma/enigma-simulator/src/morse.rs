@@ -0,0 +1,36 @@
+use cipher_utils::cipher::{Cipher as _, CipherPipeline};
+use morse_code_cipher::MorseCode;
+
+use crate::EnigmaMachine;
+
+/// Creates the pipeline that encrypts text with the given machine and then encodes the ciphertext as Morse code.
+fn pipeline(machine: &EnigmaMachine) -> CipherPipeline {
+    CipherPipeline::new().then(machine.clone()).then(MorseCode)
+}
+
+/// Encrypts the given text with the given machine and encodes the ciphertext in Morse code, as Enigma messages were
+/// historically transmitted by radio. Letters are separated by spaces and words by `/`. This is only available with
+/// the `morse-code` feature.
+///
+/// # Parameters
+/// - `machine` - The machine to encrypt the text with.
+/// - `text` - The text to encrypt.
+///
+/// # Returns
+/// The encrypted text in Morse code.
+pub fn enigma_then_morse(machine: &EnigmaMachine, text: &str) -> String {
+    pipeline(machine).encrypt(text).unwrap()
+}
+
+/// Decodes the given Morse code and decrypts it with the given machine, undoing `enigma_then_morse()`. Since Morse code
+/// has no casing, the decrypted text is in uppercase. This is only available with the `morse-code` feature.
+///
+/// # Parameters
+/// - `machine` - The machine that encrypted the text, with the same settings it was encrypted with.
+/// - `morse` - The Morse code to decode and decrypt.
+///
+/// # Returns
+/// The decrypted text.
+pub fn morse_then_enigma(machine: &EnigmaMachine, morse: &str) -> String {
+    pipeline(machine).decrypt(morse).unwrap()
+}
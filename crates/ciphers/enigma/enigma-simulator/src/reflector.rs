@@ -1,7 +1,8 @@
 use crate::alphabet::ALPHABET;
 use strum::IntoEnumIterator;
 
-#[derive(strum_macros::EnumIter, Debug, PartialEq, Eq, Hash)]
+/// A reflector ("Umkehrwalze") in an Enigma machine.
+#[derive(strum_macros::EnumIter, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Reflector {
     A,
     B,
@@ -1,7 +1,8 @@
-use crate::alphabet::Alphabet;
+use crate::{alphabet::Alphabet, rotor_set::RotorSet};
 
 /// A rotor in an Enigma machine.
 #[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rotor {
     I,
     II,
@@ -11,9 +12,75 @@ pub enum Rotor {
     VI,
     VII,
     VIII,
+
+    /// The Greek rotor Beta, which only fits in the fourth slot of the M4 and never steps. See
+    /// `EnigmaBuilder::fourth_rotor()`.
+    Beta,
+
+    /// The Greek rotor Gamma, which only fits in the fourth slot of the M4 and never steps. See
+    /// `EnigmaBuilder::fourth_rotor()`.
+    Gamma,
+
+    /// A rotor with custom wiring and notches, created with `Rotor::custom()`.
+    Custom(CustomRotor),
+}
+
+/// The wiring and notches of a custom rotor. See `Rotor::custom()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomRotor {
+    /// The letters that `A` through `Z` are wired to, as ASCII bytes.
+    wiring: [u8; 26],
+
+    /// The notches of the rotor, of which only the first `notch_count` are used. These are stored in a fixed-size array
+    /// so that rotors stay `Copy`.
+    notches: [char; 26],
+
+    /// The number of notches on the rotor.
+    notch_count: usize,
 }
 
 impl Rotor {
+    /// Creates a rotor with the given wiring and notches, such as to simulate a rotor that isn't built in. Like rotors VI
+    /// through VIII, a custom rotor may have more than one notch, and the rotor to its left steps whenever it moves past
+    /// any of them. Custom rotors are used in a machine with `EnigmaBuilder::custom_rotors()`, and are wired the same
+    /// way in every rotor set.
+    ///
+    /// # Parameters
+    /// - `wiring` - The letters that `A` through `Z` are wired to, which must be a permutation of the alphabet.
+    /// - `notches` - The letters at which the rotor makes the rotor to its left step, i.e., `&['A', 'N']`.
+    ///
+    /// # Returns
+    /// The created rotor.
+    ///
+    /// # Errors
+    /// If the wiring isn't a permutation of the alphabet, or if a notch isn't a letter. Both are case-insensitive.
+    pub fn custom(wiring: &str, notches: &[char]) -> anyhow::Result<Self> {
+        let wiring = wiring.to_ascii_uppercase();
+        let mut sorted = wiring.bytes().collect::<Vec<_>>();
+        sorted.sort();
+        if sorted != b"ABCDEFGHIJKLMNOPQRSTUVWXYZ" {
+            anyhow::bail!("Invalid rotor wiring: {wiring} is not a permutation of the alphabet");
+        }
+
+        let mut rotor = CustomRotor {
+            wiring: wiring.as_bytes().try_into().unwrap(),
+            notches: ['A'; 26],
+            notch_count: 0,
+        };
+        for notch in notches {
+            if !notch.is_ascii_alphabetic() {
+                anyhow::bail!("Invalid rotor notch: {notch:?} is not a letter");
+            }
+            let notch = notch.to_ascii_uppercase();
+            if !rotor.notches[..rotor.notch_count].contains(&notch) {
+                rotor.notches[rotor.notch_count] = notch;
+                rotor.notch_count += 1;
+            }
+        }
+
+        Ok(Self::Custom(rotor))
+    }
+
     fn unchecked_from(value: u8) -> Self {
         match value {
             1 => Self::I,
@@ -24,6 +91,8 @@ impl Rotor {
             6 => Self::VI,
             7 => Self::VII,
             8 => Self::VIII,
+            9 => Self::Beta,
+            10 => Self::Gamma,
             _ => panic!("Rotor number out of range: {value}"),
         }
     }
@@ -42,11 +111,55 @@ impl TryFrom<u8> for Rotor {
             6 => Self::VI,
             7 => Self::VII,
             8 => Self::VIII,
+            9 => Self::Beta,
+            10 => Self::Gamma,
             _ => anyhow::bail!("Rotor number out of range: {value}"),
         })
     }
 }
 
+impl TryFrom<&str> for Rotor {
+    type Error = anyhow::Error;
+
+    /// Parses a rotor from its Roman numeral, as used in historical documentation, i.e., `"IV"`.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value.to_uppercase().as_str() {
+            "I" => Self::I,
+            "II" => Self::II,
+            "III" => Self::III,
+            "IV" => Self::IV,
+            "V" => Self::V,
+            "VI" => Self::VI,
+            "VII" => Self::VII,
+            "VIII" => Self::VIII,
+            _ => anyhow::bail!("Invalid rotor numeral: {value}"),
+        })
+    }
+}
+
+impl Rotor {
+    /// Returns the number of this rotor, i.e., `4` for rotor `IV`. This is the number accepted by `EnigmaBuilder::rotors()`.
+    /// The Greek rotors Beta and Gamma are numbered `9` and `10`.
+    ///
+    /// # Returns
+    /// The rotor's number, in `[1, 10]`, or `0` for a custom rotor.
+    pub const fn number(&self) -> u8 {
+        match self {
+            Self::I => 1,
+            Self::II => 2,
+            Self::III => 3,
+            Self::IV => 4,
+            Self::V => 5,
+            Self::VI => 6,
+            Self::VII => 7,
+            Self::VIII => 8,
+            Self::Beta => 9,
+            Self::Gamma => 10,
+            Self::Custom(_) => 0,
+        }
+    }
+}
+
 pub trait IntoRotors {
     fn try_into_rotors(self) -> anyhow::Result<(Rotor, Rotor, Rotor)>;
     fn unchecked_into_rotors(self) -> (Rotor, Rotor, Rotor);
@@ -63,36 +176,75 @@ impl IntoRotors for (u8, u8, u8) {
 }
 
 impl Rotor {
-    pub fn alphabet(&self) -> Alphabet {
-        Alphabet::new(match self {
-            Self::I => "EKMFLGDQVZNTOWYHXUSPAIBRCJ",
-            Self::II => "AJDKSIRUXBLHWTMCQGZNPYFVOE",
-            Self::III => "BDFHJLCPRTXVZNYEIWGAKMUSQO",
-            Self::IV => "ESOVPZJAYQUIRHXLNFTGKDCMWB",
-            Self::V => "VZBRGITYUPSDNHLXAWMJQOFECK",
-            Self::VI => "JPGVOUMFYQBENHZRDKASXLICTW",
-            Self::VII => "NZJHGRCXMYSWBOUFAIVLPEKQDT",
-            Self::VIII => "FKQHTLXOCBJSPDZRAMEWNIUYGV",
+    /// Returns the wiring of this rotor in the default rotor set. See `Rotor::alphabet_in()`.
+    pub fn alphabet(&self) -> Alphabet<'_> {
+        self.alphabet_in(RotorSet::default())
+    }
+
+    /// Returns the wiring of this rotor in the given rotor set, as the letters that `A` through `Z` are wired to.
+    ///
+    /// # Parameters
+    /// - `rotor_set` - The rotor set to get this rotor's wiring from.
+    ///
+    /// # Returns
+    /// The rotor's wiring.
+    ///
+    /// # Panics
+    /// If this rotor isn't in the given set; See `RotorSet::contains()`.
+    pub fn alphabet_in(&self, rotor_set: RotorSet) -> Alphabet<'_> {
+        Alphabet::new(match (rotor_set, self) {
+            (_, Self::Custom(rotor)) => std::str::from_utf8(&rotor.wiring).unwrap(),
+            (RotorSet::Railway, Self::I) => "JGDQOXUSCAMIFRVTPNEWKBLZYH",
+            (RotorSet::Railway, Self::II) => "NTZPSFBOKMWRCJDIVLAEYUXHGQ",
+            (RotorSet::Railway, Self::III) => "JVIUBHTCDYAKEQZPOSGXNRMWFL",
+            (RotorSet::Railway, _) => panic!("Rotor {} is not in the Railway Enigma's rotor set", self.number()),
+            (_, Self::I) => "EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+            (_, Self::II) => "AJDKSIRUXBLHWTMCQGZNPYFVOE",
+            (_, Self::III) => "BDFHJLCPRTXVZNYEIWGAKMUSQO",
+            (_, Self::IV) => "ESOVPZJAYQUIRHXLNFTGKDCMWB",
+            (_, Self::V) => "VZBRGITYUPSDNHLXAWMJQOFECK",
+            (_, Self::VI) => "JPGVOUMFYQBENHZRDKASXLICTW",
+            (_, Self::VII) => "NZJHGRCXMYSWBOUFAIVLPEKQDT",
+            (_, Self::VIII) => "FKQHTLXOCBJSPDZRAMEWNIUYGV",
+            (_, Self::Beta) => "LEYJVCNIXWPBQMDRTAKZGFUHOS",
+            (_, Self::Gamma) => "FSOKANUERHMBTIYCWLQPZXVGJD",
         })
         .unwrap()
     }
 
     /// Returns the notches on this rotor as a `char` slice. In Enigma machines, each rotors have notches that
-    /// determine whether the next rotor should rotate. The five basic rotors each have a single notch, and the
-    /// remaining three each have two.
+    /// determine whether the next rotor should rotate. The five basic rotors each have a single notch, rotors VI
+    /// through VIII each have two, and the Greek rotors have none, since they never step. Custom rotors have the
+    /// notches they were created with.
     ///
     /// # Returns
     /// The notches on this rotor as a `char` slice.
-    pub const fn notches(&self) -> &'static [char] {
-        match self {
-            Self::I => &['Q'],
-            Self::II => &['E'],
-            Self::III => &['V'],
-            Self::IV => &['J'],
-            Self::V => &['Z'],
-            Self::VI => &['M', 'Z'],
-            Self::VII => &['M', 'Z'],
-            Self::VIII => &['M', 'Z'],
+    pub const fn notches(&self) -> &[char] {
+        self.notches_in(RotorSet::KriegsmarineM4)
+    }
+
+    /// Returns the notches on this rotor in the given rotor set. See `Rotor::notches()`.
+    ///
+    /// # Parameters
+    /// - `rotor_set` - The rotor set to get this rotor's notches from.
+    ///
+    /// # Returns
+    /// The notches on this rotor as a `char` slice.
+    pub const fn notches_in(&self, rotor_set: RotorSet) -> &[char] {
+        match (rotor_set, self) {
+            (_, Self::Custom(rotor)) => rotor.notches.split_at(rotor.notch_count).0,
+            (RotorSet::Railway, Self::I) => &['N'],
+            (RotorSet::Railway, Self::II) => &['E'],
+            (RotorSet::Railway, _) => &['Y'],
+            (_, Self::I) => &['Q'],
+            (_, Self::II) => &['E'],
+            (_, Self::III) => &['V'],
+            (_, Self::IV) => &['J'],
+            (_, Self::V) => &['Z'],
+            (_, Self::VI) => &['M', 'Z'],
+            (_, Self::VII) => &['M', 'Z'],
+            (_, Self::VIII) => &['M', 'Z'],
+            (_, Self::Beta | Self::Gamma) => &[],
         }
     }
 }
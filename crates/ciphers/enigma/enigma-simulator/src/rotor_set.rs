@@ -0,0 +1,78 @@
+use crate::{reflector::Reflector, rotor::Rotor};
+
+/// A set of rotor wirings used by a particular Enigma model. Different models were issued different rotors, so rotor
+/// `I` of one model is wired differently from rotor `I` of another. The rotor set of a machine is chosen with
+/// `EnigmaMachine::with_rotor_set()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RotorSet {
+    /// Rotors I through V of the Enigma I, used by the German army and air force.
+    WehrmachtI,
+
+    /// Rotors I through VIII of the naval Enigma M3 and M4, and the M4's Greek rotors Beta and Gamma. Rotors I through V
+    /// are the same as the Enigma I's, and VI through VIII were only issued to the navy. This is the default, since it
+    /// contains every other rotor set that uses the same wirings.
+    #[default]
+    KriegsmarineM4,
+
+    /// Rotors I through III of the Railway Enigma (Enigma R), which also has its own reflector (`Reflector::Ukwr`) and
+    /// an entry wheel wired in keyboard order.
+    Railway,
+}
+
+impl RotorSet {
+    /// Returns the number of rotors in this set. The rotors of a set are numbered from 1 to this number, not counting
+    /// the M4's Greek rotors; See `RotorSet::contains()`.
+    ///
+    /// # Returns
+    /// The number of rotors in the set.
+    pub const fn rotor_count(&self) -> u8 {
+        match self {
+            Self::WehrmachtI => 5,
+            Self::KriegsmarineM4 => 8,
+            Self::Railway => 3,
+        }
+    }
+
+    /// Returns whether the given rotor is in this set. Custom rotors are wired the same way in every set, so they're in
+    /// all of them.
+    ///
+    /// # Parameters
+    /// - `rotor` - The rotor to check.
+    ///
+    /// # Returns
+    /// Whether the rotor is in this set.
+    pub const fn contains(&self, rotor: Rotor) -> bool {
+        match rotor {
+            Rotor::Custom(_) => true,
+            Rotor::Beta | Rotor::Gamma => matches!(self, Self::KriegsmarineM4),
+            _ => rotor.number() <= self.rotor_count(),
+        }
+    }
+
+    /// Returns the wiring of the entry wheel ("Eintrittswalze") of this set's model, or `None` if the entry wheel maps
+    /// each letter to itself. The entry wheel connects the keyboard and plugboard to the rotors; The `n`th letter of the
+    /// returned wiring is connected to the rotors' `n`th contact.
+    ///
+    /// # Returns
+    /// The entry wheel wiring, if it's not the identity.
+    pub const fn entry_wheel(&self) -> Option<&'static str> {
+        match self {
+            Self::WehrmachtI | Self::KriegsmarineM4 => None,
+            Self::Railway => Some("QWERTZUIOASDFGHJKPYXCVBNML"),
+        }
+    }
+
+    /// Returns whether the given reflector was used with this set's model.
+    ///
+    /// # Parameters
+    /// - `reflector` - The reflector to check.
+    ///
+    /// # Returns
+    /// Whether the reflector fits this set's model.
+    pub const fn fits_reflector(&self, reflector: Reflector) -> bool {
+        match self {
+            Self::WehrmachtI | Self::KriegsmarineM4 => !matches!(reflector, Reflector::Ukwr | Reflector::Ukwk),
+            Self::Railway => matches!(reflector, Reflector::Ukwr),
+        }
+    }
+}
@@ -5,16 +5,48 @@ use crate::{
     enigma::{caeser_shift, MachineOptions},
     reflector::Reflector,
     rotor::{IntoRotors as _, Rotor},
-    UncheckedEnigmaBuilder, UncheckedEnigmaMachine,
+    rotor_set::RotorSet,
+    settings_warning::SettingsWarning,
+    transliteration::Transliteration,
+    uhr, UncheckedEnigmaBuilder, UncheckedEnigmaMachine,
 };
 
 /// An enigma machine with applied settings that can encrypt or decrypt text.
+#[derive(Clone)]
 pub struct EnigmaMachine {
     rotors: (Rotor, Rotor, Rotor),
-    ring_positions: (AlphabetIndex, AlphabetIndex, AlphabetIndex),
+
+    /// The static fourth rotor to the left of the others, if the machine is a four-rotor M4. See
+    /// `EnigmaBuilder::fourth_rotor()`.
+    fourth_rotor: Option<Rotor>,
+
+    /// The ring setting and position of the fourth rotor, which stay the same for the whole message since it never
+    /// steps. See `EnigmaBuilder::fourth_rotor()`.
+    fourth_rotor_settings: (AlphabetIndex, AlphabetIndex),
+
+    pub(crate) ring_positions: (AlphabetIndex, AlphabetIndex, AlphabetIndex),
     ring_settings: (AlphabetIndex, AlphabetIndex, AlphabetIndex),
     reflector: Reflector,
+
+    /// The letter that each plugged letter is connected to on its way into the machine.
     plugboard: std::collections::HashMap<char, char>,
+
+    /// The letter that each plugged letter is connected to on its way out of the machine, which is the inverse of
+    /// `plugboard`. This is the same as `plugboard` unless the machine has an Uhr.
+    plugboard_back: std::collections::HashMap<char, char>,
+
+    /// The plugboard cables as they were given, in order. The order only matters for the Uhr.
+    plugboard_cables: Vec<(char, char)>,
+
+    /// The position of the Uhr attachment, if the machine has one. See `EnigmaBuilder::uhr()`.
+    uhr: Option<u8>,
+
+    rotor_set: RotorSet,
+
+    /// The symbols that the machine's 26 contacts are labelled with, in order, if they aren't `A-Z`. See
+    /// `EnigmaBuilder::alphabet()`.
+    alphabet: Option<Vec<char>>,
+
     options: MachineOptions,
 }
 
@@ -31,10 +63,17 @@ impl EnigmaMachine {
     pub fn new() -> impl EnigmaBuilder {
         Ok(Self {
             rotors: (1, 1, 1).try_into_rotors().unwrap(),
+            fourth_rotor: None,
+            fourth_rotor_settings: (AlphabetIndex::try_from(0u8).unwrap(), AlphabetIndex::try_from(0u8).unwrap()),
             ring_positions: (1, 1, 1).try_into_alphabet_index().unwrap(),
             ring_settings: (1, 1, 1).try_into_alphabet_index().unwrap(),
             reflector: Reflector::A,
             plugboard: std::collections::HashMap::new(),
+            plugboard_back: std::collections::HashMap::new(),
+            plugboard_cables: Vec::new(),
+            uhr: None,
+            rotor_set: RotorSet::default(),
+            alphabet: None,
             options: MachineOptions::default(),
         })
     }
@@ -57,6 +96,62 @@ impl EnigmaMachine {
         }
     }
 
+    /// Creates a new Enigma machine from a line of a historical key sheet. Key sheets list each setting as a labelled,
+    /// comma-separated field, i.e.:
+    ///
+    /// ```text
+    /// Rotors: III I II, Rings: 01 17 12, Reflector: B, Plugboard: AV BS CG DL FU HZ IN KM OW RX
+    /// ```
+    ///
+    /// Rotors are given as Roman numerals, and ring settings as numbers in `[1, 26]` or as letters. An optional
+    /// `Positions` field sets the starting rotor positions in the same format as the rings; Otherwise, the rotors
+    /// start at `A A A`. Labels are case-insensitive, and the German labels `Walzenlage`, `Ringstellung`,
+    /// `Umkehrwalze`, `Steckerverbindungen`, and `Grundstellung` are accepted as well.
+    ///
+    /// # Parameters
+    /// - `line` - The key sheet line to parse.
+    ///
+    /// # Returns
+    /// The configured Enigma machine.
+    ///
+    /// # Errors
+    /// If a field is malformed, unrecognized, or missing (other than `Positions`), or if any of the parsed settings
+    /// are invalid.
+    pub fn from_key_sheet(line: &str) -> anyhow::Result<EnigmaMachine> {
+        let mut rotors = None;
+        let mut rings = None;
+        let mut reflector = None;
+        let mut plugboard = None;
+        let mut positions = (1, 1, 1);
+
+        for field in line.split(',').map(str::trim).filter(|field| !field.is_empty()) {
+            let (label, value) = field
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Error parsing Enigma key sheet: Field \"{field}\" has no label"))?;
+            let value = value.trim();
+            match label.trim().to_lowercase().as_str() {
+                "rotors" | "walzenlage" => rotors = Some(value),
+                "rings" | "ringstellung" => rings = Some(parse_key_sheet_triple(value)?),
+                "positions" | "grundstellung" => positions = parse_key_sheet_triple(value)?,
+                "reflector" | "umkehrwalze" => reflector = Some(value),
+                "plugboard" | "steckerverbindungen" => plugboard = Some(value),
+                _ => anyhow::bail!("Error parsing Enigma key sheet: Unrecognized field \"{label}\""),
+            }
+        }
+
+        let rotors = rotors.ok_or_else(|| anyhow::anyhow!("Error parsing Enigma key sheet: No rotors provided"))?;
+        let rings = rings.ok_or_else(|| anyhow::anyhow!("Error parsing Enigma key sheet: No ring settings provided"))?;
+        let reflector = reflector.ok_or_else(|| anyhow::anyhow!("Error parsing Enigma key sheet: No reflector provided"))?;
+        let plugboard = plugboard.ok_or_else(|| anyhow::anyhow!("Error parsing Enigma key sheet: No plugboard provided"))?;
+
+        EnigmaMachine::new()
+            .rotors_roman(rotors)
+            .ring_settings(rings.0, rings.1, rings.2)
+            .ring_positions(positions.0, positions.1, positions.2)
+            .reflector(reflector)
+            .plugboard(plugboard)
+    }
+
     /// Decodes the given text using this Enigma machine.
     ///
     /// The decryption process does the following for each letter in the ciphertext:
@@ -74,27 +169,183 @@ impl EnigmaMachine {
     /// This is exactly the same as calling `machine.encode(text)`, since the enigma cipher is
     /// symmetric; The only difference is semantic meaning and intent, i.e.,
     ///
-    /// ```rust
+    /// ```rust,ignore
     ///	assert_eq!(text, machine.decrypt(machine.decrypt(text)));
     ///	assert_eq!(text, machine.encrypt(machine.encrypt(text)));
     ///	assert_eq!(text, machine.decrypt(machine.encrypt(text)));
     ///	assert_eq!(text, machine.encrypt(machine.decrypt(text)));
     /// ```
     ///
+    /// If the machine has a transliteration, then its codes are translated back into characters after decoding; See
+    /// `EnigmaMachine::with_transliteration()`.
+    ///
     /// # Parameters
     /// - `text` - The text to decode.
     ///
     /// # Returns
     /// The decoded text.
     pub fn decrypt(&self, text: &str) -> String {
+        self.decrypt_stepped(text).0
+    }
+
+    /// Decrypts the given text like `decrypt()`, and also returns the positions that the rotors are left at afterwards.
+    /// See `StatefulEnigma::feed()`.
+    pub(crate) fn decrypt_stepped(&self, text: &str) -> (String, (AlphabetIndex, AlphabetIndex, AlphabetIndex)) {
+        let (plaintext, positions) = self.process_stepped(text, None);
+        let plaintext = match &self.options.transliteration {
+            Some(transliteration) => transliteration.reverse(&plaintext),
+            None => plaintext,
+        };
+        (plaintext, positions)
+    }
+
+    /// Decrypts each of the given messages, such as a whole archive sent with the same daily key. Each message is
+    /// decrypted from this machine's configured positions, exactly as `EnigmaMachine::decrypt()` would. With the `rayon`
+    /// feature, the messages are decrypted in parallel.
+    ///
+    /// # Parameters
+    /// - `messages` - The messages to decode.
+    ///
+    /// # Returns
+    /// The decoded messages, in the same order as the given messages.
+    pub fn decrypt_many(&self, messages: &[&str]) -> Vec<String> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
+            messages.par_iter().map(|message| self.decrypt(message)).collect()
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        messages.iter().map(|message| self.decrypt(message)).collect()
+    }
+
+    /// Decrypts the given text and scores how English-like the result is, as a quick check of whether the machine's
+    /// settings are correct. Decrypting with the wrong settings gives random-looking letters, which score much lower
+    /// than real plaintext. See `cipher_utils::englishness()`.
+    ///
+    /// # Parameters
+    /// - `text` - The text to decode.
+    ///
+    /// # Returns
+    /// The decoded text, along with its englishness score in `[0, 1]`.
+    pub fn decrypt_scored(&self, text: &str) -> (String, f64) {
+        let plaintext = self.decrypt(text);
+        let score = cipher_utils::englishness(&plaintext);
+        (plaintext, score)
+    }
+
+    /// Decrypts a message that begins with an encrypted message key indicator, as in the historical indicator procedure.
+    /// The operator chose a message key of three letters, set the rotors to this machine's positions (the ground setting,
+    /// or "Grundstellung"), and encrypted the message key, typically twice in a row (the "double indicator"). They then
+    /// set the rotors to the message key and encrypted the message body.
+    ///
+    /// This decrypts the first `indicator_len` letters of the message at this machine's positions to recover the
+    /// message key, then decrypts the rest of the message with the rotors started at the message key. Whitespace
+    /// between the indicator and the body is ignored.
+    ///
+    /// # Parameters
+    /// - `full_message` - The indicator followed by the message body.
+    /// - `indicator_len` - The number of letters in the indicator, i.e., `3` for a single indicator or `6` for a double
+    ///   indicator.
+    ///
+    /// # Returns
+    /// The decrypted message body.
+    ///
+    /// # Errors
+    /// - If `indicator_len` isn't a positive multiple of 3
+    /// - If the message has fewer than `indicator_len` letters
+    /// - If the repetitions of a repeated message key decrypt to different keys, which means the indicator is garbled or
+    ///   the machine's settings are wrong
+    pub fn decrypt_with_indicator(&self, full_message: &str, indicator_len: usize) -> anyhow::Result<String> {
+        if indicator_len == 0 || !indicator_len.is_multiple_of(3) {
+            anyhow::bail!("Invalid indicator length {indicator_len}: Indicators contain the three-letter message key one or more times.");
+        }
+
+        let mut indicator = Vec::with_capacity(indicator_len);
+        let mut body_start = full_message.len();
+        for (index, character) in full_message.char_indices() {
+            if indicator.len() == indicator_len {
+                body_start = index;
+                break;
+            }
+            if self.is_letter(character) {
+                indicator.extend(character.to_uppercase());
+            }
+        }
+        if indicator.len() < indicator_len {
+            anyhow::bail!("Expected an indicator of {indicator_len} letters, but the message only has {} letters.", indicator.len());
+        }
+
+        let message_key = self.process(&indicator.iter().collect::<String>(), None).to_uppercase().chars().collect::<Vec<_>>();
+        if let Some(mismatch) = message_key.chunks(3).find(|repetition| *repetition != &message_key[..3]) {
+            anyhow::bail!(
+                "The indicator decrypted to different message keys, {} and {}; The indicator is garbled or the ground setting is wrong.",
+                message_key[..3].iter().collect::<String>(),
+                mismatch.iter().collect::<String>()
+            );
+        }
+
+        let [first, second, third] = [0, 1, 2].map(|index| self.standard_letter(message_key[index]) as u8 - b'A');
+        let machine = EnigmaMachine {
+            ring_positions: (first, second, third).try_into_alphabet_index()?,
+            ..self.clone()
+        };
+        Ok(machine.decrypt(full_message[body_start..].trim_start()))
+    }
+
+    /// Encrypts or decrypts the given text, optionally recording the path of each letter through the machine into the
+    /// given trace. See `decrypt()` and `encrypt_traced()`.
+    fn process(&self, text: &str, trace: Option<&mut Vec<CharTrace>>) -> String {
+        self.process_stepped(text, trace).0
+    }
+
+    /// Encrypts or decrypts the given text like `process()`, and also returns the positions that the rotors are left at
+    /// afterwards. Letters that expand when uppercased, such as `ß` into `SS`, step the rotors once per letter of the
+    /// expansion.
+    fn process_stepped(&self, text: &str, mut trace: Option<&mut Vec<CharTrace>>) -> (String, (AlphabetIndex, AlphabetIndex, AlphabetIndex)) {
+        // Encrypt the letters on their own, and put them back into the original text in place of its letters
+        if self.options.preserve_layout {
+            let machine = EnigmaMachine {
+                options: MachineOptions {
+                    preserve_layout: false,
+                    clear_punctuation: true,
+                    ..self.options.clone()
+                },
+                ..self.clone()
+            };
+            let letters = text.chars().filter(|character| self.is_letter(*character)).collect::<String>();
+            let (processed, positions) = machine.process_stepped(&letters, trace);
+            let mut processed = processed.chars();
+            let output = text
+                .chars()
+                .map(|character| {
+                    if !self.is_letter(character) {
+                        character
+                    } else if character.is_lowercase() {
+                        processed.next().unwrap().to_lowercase().next().unwrap()
+                    } else {
+                        processed.next().unwrap()
+                    }
+                })
+                .collect();
+            return (output, positions);
+        }
+
         let text = text.to_uppercase();
-        let rotor_a = self.rotors.0.alphabet();
-        let rotor_b = self.rotors.1.alphabet();
-        let rotor_c = self.rotors.2.alphabet();
+        let rotor_a = self.rotors.0.alphabet_in(self.rotor_set);
+        let rotor_b = self.rotors.1.alphabet_in(self.rotor_set);
+        let rotor_c = self.rotors.2.alphabet_in(self.rotor_set);
+        let (offset_d_setting, offset_d) = self.fourth_rotor_settings;
+        let rotor_d = self.fourth_rotor.as_ref().map(|rotor| {
+            let rotor_d = caeser_shift(&rotor.alphabet_in(self.rotor_set).letters(), *offset_d_setting);
+            let rotor_d_first_half = rotor_d.get((26 - *offset_d_setting as usize)..rotor_d.len()).unwrap().to_owned();
+            let rotor_d_second_half = rotor_d.get(0..(26 - *offset_d_setting as usize)).unwrap().to_owned();
+            rotor_d_first_half + &rotor_d_second_half
+        });
+        let rotor_d = rotor_d.as_deref().map(|rotor_d| Alphabet::new(rotor_d).unwrap());
+        let entry_wheel = self.rotor_set.entry_wheel().map(|wiring| Alphabet::new(wiring).unwrap());
 
-        let mut rotor_a_letter = self.ring_positions.0;
-        let mut rotor_b_letter = self.ring_positions.1;
-        let mut rotor_c_letter = self.ring_positions.2;
+        let mut rotor_letters = self.ring_positions;
 
         let rotor_a_setting = self.ring_settings.0;
         let offset_a_setting = rotor_a_setting;
@@ -122,204 +373,140 @@ impl EnigmaMachine {
         let rotor_c = rotor_c_first_half + &rotor_c_second_half;
         let rotor_c = Alphabet::new(&rotor_c).unwrap();
 
-        text.chars()
-            .map(|mut letter| {
-                if self.options.debug {
-                    println!("Decrypting character: '{}'", letter.to_string().bold().cyan());
-                }
+        let mut output = String::with_capacity(text.len());
+        for letter in text.chars() {
+            if self.options.debug {
+                println!("Decrypting character: '{}'", letter.to_string().bold().cyan());
+            }
 
-                // Non-alphabetic characters stay the same
-                if !letter.is_alphabetic() {
-                    if self.options.clear_punctuation {
-                        return String::new();
-                    } else {
-                        if self.options.debug {
-                            println!("\tCharacter is punctuation; Leaving it as-is.");
-                        }
-                        return letter.to_string();
+            // Characters that aren't in the machine's alphabet stay the same
+            if !self.is_letter(letter) {
+                if !self.options.clear_punctuation {
+                    if self.options.debug {
+                        println!("\tCharacter is punctuation; Leaving it as-is.");
                     }
+                    output.push(letter);
                 }
+                continue;
+            }
 
-                // Rotate rotor 3
-                let mut rotor_trigger = self
-                    .rotors
-                    .2
-                    .notches()
-                    .iter()
-                    .map(|notch| ALPHABET.index_of(*notch).unwrap())
-                    .collect::<Vec<_>>()
-                    .contains(&rotor_c_letter);
-                rotor_c_letter += 1;
-
-                // Rotate rotor 2
-                if rotor_trigger {
-                    rotor_trigger = self
-                        .rotors
-                        .1
-                        .notches()
-                        .iter()
-                        .map(|notch| ALPHABET.index_of(*notch).unwrap())
-                        .collect::<Vec<_>>()
-                        .contains(&rotor_b_letter);
-                    rotor_b_letter += 1;
-
-                    // Rotate rotor 1
-                    if rotor_trigger {
-                        rotor_a_letter += 1;
-                    }
-                }
-                // Double step sequence
-                else if self
-                    .rotors
-                    .1
-                    .notches()
-                    .iter()
-                    .map(|notch| ALPHABET.index_of(*notch).unwrap())
-                    .collect::<Vec<_>>()
-                    .contains(&rotor_b_letter)
-                {
-                    rotor_b_letter += 1;
-                    rotor_a_letter += 1;
-                }
+            // Rotate the rotors
+            self.step_rotors(&mut rotor_letters);
+            let (offset_a, offset_b, offset_c) = rotor_letters;
 
-                // Plugboard decryption
-                let old_letter = letter;
-                if let Some(plugboarded_letter) = self.plugboard.get(&letter) {
-                    letter = *plugboarded_letter;
-                }
-                if self.options.debug {
-                    println!(
-                        "\tPassing character through {}: '{}' -> '{}'",
-                        "plugboard".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    )
-                }
+            // Plugboard
+            let plugboard = *self.plugboard.get(&letter).unwrap_or(&letter);
 
-                let offset_a = rotor_a_letter;
-                let offset_b = rotor_b_letter;
-                let offset_c = rotor_c_letter;
-
-                // Rotor 3 Encryption
-                let pos = ALPHABET.index_of(letter).unwrap();
-                let let_ = rotor_c.letter_at(pos + offset_c);
-                let pos = ALPHABET.index_of(let_).unwrap();
-                let old_letter = letter;
-                letter = ALPHABET.letter_at(pos - offset_c);
-                if self.options.debug {
-                    println!(
-                        "\tPassing character through {}: '{}' -> '{}'",
-                        "third rotor".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Entry wheel
+            let standard = self.standard_letter(plugboard);
+            let entered = match &entry_wheel {
+                Some(entry_wheel) => ALPHABET.letter_at(entry_wheel.index_of(standard).unwrap()),
+                None => standard,
+            };
 
-                // Rotor 2 Encryption
-                let pos = ALPHABET.index_of(letter).unwrap();
-                let let_ = rotor_b.letter_at(pos + offset_b);
-                let pos = ALPHABET.index_of(let_).unwrap();
-                let old_letter = letter;
-                letter = ALPHABET.letter_at(pos - offset_b);
-                if self.options.debug {
-                    println!(
-                        "\tPassing character through {}: '{}' -> '{}'",
-                        "second rotor".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Rotor 3 Encryption
+            let pos = ALPHABET.index_of(entered).unwrap();
+            let pos = ALPHABET.index_of(rotor_c.letter_at(pos + offset_c)).unwrap();
+            let third_rotor = ALPHABET.letter_at(pos - offset_c);
 
-                // Rotor 1 Encryption
-                let pos = ALPHABET.index_of(letter).unwrap();
-                let let_ = rotor_a.letter_at(pos + offset_a);
-                let pos = ALPHABET.index_of(let_).unwrap();
-                let old_letter = letter;
-                letter = ALPHABET.letter_at(pos - offset_a);
-                if self.options.debug {
-                    println!(
-                        "\tPassing character through {}: '{}' -> '{}'",
-                        "first rotor".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Rotor 2 Encryption
+            let pos = ALPHABET.index_of(third_rotor).unwrap();
+            let pos = ALPHABET.index_of(rotor_b.letter_at(pos + offset_b)).unwrap();
+            let second_rotor = ALPHABET.letter_at(pos - offset_b);
 
-                // Reflector Encryption
-                let old_letter = letter;
-                letter = *self.reflector.alphabet().get(&letter).unwrap();
-                if self.options.debug {
-                    println!(
-                        "\tPassing character through {}: '{}' -> '{}'",
-                        "reflector".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Rotor 1 Encryption
+            let pos = ALPHABET.index_of(second_rotor).unwrap();
+            let pos = ALPHABET.index_of(rotor_a.letter_at(pos + offset_a)).unwrap();
+            let first_rotor = ALPHABET.letter_at(pos - offset_a);
 
-                // Rotor 1 Encryption
-                let pos = ALPHABET.index_of(letter).unwrap();
-                let let_ = ALPHABET.letter_at(pos + offset_a);
-                let pos = rotor_a.index_of(let_).unwrap();
-                let old_letter = letter;
-                letter = ALPHABET.letter_at(pos - offset_a);
-                if self.options.debug {
-                    println!(
-                        "\tPassing character back through {}: '{}' -> '{}'",
-                        "first rotor".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Rotor 4 Encryption; The fourth rotor never steps, so it always stays at its starting position
+            let fourth_rotor = rotor_d.as_ref().map(|rotor_d| {
+                let pos = ALPHABET.index_of(first_rotor).unwrap();
+                let pos = ALPHABET.index_of(rotor_d.letter_at(pos + offset_d)).unwrap();
+                ALPHABET.letter_at(pos - offset_d)
+            });
 
-                // Rotor 2 Encryption
-                let pos = ALPHABET.index_of(letter).unwrap();
-                let let_ = ALPHABET.letter_at(pos + offset_b);
-                let pos = rotor_b.index_of(let_).unwrap();
-                let old_letter = letter;
-                letter = ALPHABET.letter_at(pos - offset_b);
-                if self.options.debug {
-                    println!(
-                        "\tPassing character back through {}: '{}' -> '{}'",
-                        "second rotor".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Reflector Encryption
+            let reflector = *self.reflector.alphabet().get(&fourth_rotor.unwrap_or(first_rotor)).unwrap();
 
-                // Rotor 3 Encryption
-                let pos = ALPHABET.index_of(letter).unwrap();
-                let let_ = ALPHABET.letter_at(pos + offset_c);
-                let pos = rotor_c.index_of(let_).unwrap();
-                let old_letter = letter;
-                letter = ALPHABET.letter_at(pos - offset_c);
-                if self.options.debug {
-                    println!(
-                        "\tPassing character back through {}: '{}' -> '{}'",
-                        "third rotor".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                }
+            // Rotor 4 Encryption
+            let fourth_rotor_back = rotor_d.as_ref().map(|rotor_d| {
+                let pos = ALPHABET.index_of(reflector).unwrap();
+                let pos = rotor_d.index_of(ALPHABET.letter_at(pos + offset_d)).unwrap();
+                ALPHABET.letter_at(pos - offset_d)
+            });
 
-                // Plugboard Second Pass
-                let old_letter = letter;
-                if let Some(plugboarded_letter) = self.plugboard.get(&letter) {
-                    letter = *plugboarded_letter;
-                }
-                if self.options.debug {
-                    println!(
-                        "\tPassing character back through {}: '{}' -> '{}'",
-                        "plugboard".green().bold(),
-                        old_letter.to_string().bold().cyan(),
-                        letter.to_string().bold().cyan(),
-                    );
-                    println!("\tFinalized character: '{}'", letter.to_string().bold().cyan());
-                }
+            // Rotor 1 Encryption
+            let pos = ALPHABET.index_of(fourth_rotor_back.unwrap_or(reflector)).unwrap();
+            let pos = rotor_a.index_of(ALPHABET.letter_at(pos + offset_a)).unwrap();
+            let first_rotor_back = ALPHABET.letter_at(pos - offset_a);
 
-                letter.to_string()
-            })
-            .collect()
+            // Rotor 2 Encryption
+            let pos = ALPHABET.index_of(first_rotor_back).unwrap();
+            let pos = rotor_b.index_of(ALPHABET.letter_at(pos + offset_b)).unwrap();
+            let second_rotor_back = ALPHABET.letter_at(pos - offset_b);
+
+            // Rotor 3 Encryption
+            let pos = ALPHABET.index_of(second_rotor_back).unwrap();
+            let pos = rotor_c.index_of(ALPHABET.letter_at(pos + offset_c)).unwrap();
+            let third_rotor_back = ALPHABET.letter_at(pos - offset_c);
+
+            // Entry wheel Second Pass
+            let third_rotor_back = match &entry_wheel {
+                Some(entry_wheel) => entry_wheel.letter_at(ALPHABET.index_of(third_rotor_back).unwrap()),
+                None => third_rotor_back,
+            };
+            let third_rotor_back = self.alphabet_letter(third_rotor_back);
+
+            // Plugboard Second Pass
+            let output_letter = *self.plugboard_back.get(&third_rotor_back).unwrap_or(&third_rotor_back);
+
+            let char_trace = CharTrace {
+                input: letter,
+                plugboard,
+                third_rotor: self.alphabet_letter(third_rotor),
+                second_rotor: self.alphabet_letter(second_rotor),
+                first_rotor: self.alphabet_letter(first_rotor),
+                fourth_rotor: fourth_rotor.map(|letter| self.alphabet_letter(letter)),
+                reflector: self.alphabet_letter(reflector),
+                fourth_rotor_back: fourth_rotor_back.map(|letter| self.alphabet_letter(letter)),
+                first_rotor_back: self.alphabet_letter(first_rotor_back),
+                second_rotor_back: self.alphabet_letter(second_rotor_back),
+                third_rotor_back,
+                output: output_letter,
+            };
+
+            if self.options.debug {
+                char_trace.print();
+            }
+
+            if let Some(trace) = trace.as_mut() {
+                trace.push(char_trace);
+            }
+
+            output.push(if self.options.lowercase_output {
+                output_letter.to_lowercase().next().unwrap()
+            } else {
+                output_letter
+            });
+        }
+
+        (output, rotor_letters)
+    }
+
+    /// Encrypts the given text like `encrypt()`, and also returns the path each letter took through the machine. This
+    /// is the same information that the `debug` option prints, but as structured data, such as for visualizers.
+    ///
+    /// # Parameters
+    /// - `text` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text, and a trace of each encrypted letter in order. Characters that aren't letters aren't encrypted,
+    /// so they don't appear in the trace.
+    pub fn encrypt_traced(&self, text: &str) -> (String, Vec<CharTrace>) {
+        let mut trace = Vec::new();
+        let ciphertext = self.process(text, Some(&mut trace));
+        (ciphertext, trace)
     }
 
     /// Encodes the given text using this Enigma machine.
@@ -339,23 +526,464 @@ impl EnigmaMachine {
     /// This is exactly the same as calling `machine.decode(text)`, since the enigma cipher is
     /// symmetric; The only difference is semantic meaning and intent, i.e.,
     ///
-    /// ```rust
+    /// ```rust,ignore
     ///	assert_eq!(text, machine.decode(machine.decode(text)));
     ///	assert_eq!(text, machine.encode(machine.encode(text)));
     ///	assert_eq!(text, machine.decode(machine.encode(text)));
     ///	assert_eq!(text, machine.encode(machine.decode(text)));
     /// ```
     ///
+    /// If the machine has a transliteration, then characters such as digits are spelled out with their codes before
+    /// encoding; See `EnigmaMachine::with_transliteration()`.
+    ///
     /// # Parameters
     /// - `text` - The text to encode.
     ///
     /// # Returns
     /// The encoded text.
     pub fn encrypt(&self, text: &str) -> String {
-        self.decrypt(text)
+        match &self.options.transliteration {
+            Some(transliteration) => self.process(&transliteration.apply(text), None),
+            None => self.process(text, None),
+        }
+    }
+
+    /// Returns whether this machine encrypts the given character, in either case. These are the letters of the machine's
+    /// alphabet, which is `A-Z` unless it was changed with `EnigmaBuilder::alphabet()`; Any other character passes
+    /// through the machine unchanged.
+    ///
+    /// # Parameters
+    /// - `character` - The character to check.
+    ///
+    /// # Returns
+    /// Whether the character is a letter of this machine's alphabet.
+    pub(crate) fn is_letter(&self, character: char) -> bool {
+        match &self.alphabet {
+            Some(alphabet) => character.to_uppercase().all(|letter| alphabet.contains(&letter)),
+            None => character.is_ascii_alphabetic(),
+        }
+    }
+
+    /// Converts a letter of this machine's alphabet into the letter of `A-Z` at the same position, which is the contact
+    /// that the rotors are wired to it by.
+    ///
+    /// # Parameters
+    /// - `letter` - The uppercase letter to convert.
+    ///
+    /// # Returns
+    /// The letter of `A-Z` at the same position.
+    fn standard_letter(&self, letter: char) -> char {
+        match &self.alphabet {
+            Some(alphabet) => (alphabet.iter().position(|other| *other == letter).unwrap() as u8 + b'A') as char,
+            None => letter,
+        }
+    }
+
+    /// Converts a letter of `A-Z` into the letter of this machine's alphabet at the same position. This is the inverse of
+    /// `standard_letter()`.
+    ///
+    /// # Parameters
+    /// - `letter` - The letter of `A-Z` to convert.
+    ///
+    /// # Returns
+    /// The letter of this machine's alphabet at the same position.
+    fn alphabet_letter(&self, letter: char) -> char {
+        match &self.alphabet {
+            Some(alphabet) => alphabet[(letter as u8 - b'A') as usize],
+            None => letter,
+        }
+    }
+
+    /// Steps the rotors once, as happens before each letter is encrypted. The rightmost rotor always steps, and the
+    /// middle and leftmost rotors step when the rotor to their right is at a notch, including the middle rotor's
+    /// double step.
+    ///
+    /// # Parameters
+    /// - `positions` - The current positions of the rotors, which are updated in-place.
+    pub(crate) fn step_rotors(&self, positions: &mut (AlphabetIndex, AlphabetIndex, AlphabetIndex)) {
+        // Rotate rotor 3
+        let mut rotor_trigger = self
+            .rotors
+            .2
+            .notches_in(self.rotor_set)
+            .iter()
+            .map(|notch| ALPHABET.index_of(*notch).unwrap())
+            .collect::<Vec<_>>()
+            .contains(&positions.2);
+        positions.2 += 1;
+
+        // Rotate rotor 2
+        if rotor_trigger {
+            rotor_trigger = self
+                .rotors
+                .1
+                .notches_in(self.rotor_set)
+                .iter()
+                .map(|notch| ALPHABET.index_of(*notch).unwrap())
+                .collect::<Vec<_>>()
+                .contains(&positions.1);
+            positions.1 += 1;
+
+            // Rotate rotor 1
+            if rotor_trigger {
+                positions.0 += 1;
+            }
+        }
+        // Double step sequence
+        else if self
+            .rotors
+            .1
+            .notches_in(self.rotor_set)
+            .iter()
+            .map(|notch| ALPHABET.index_of(*notch).unwrap())
+            .collect::<Vec<_>>()
+            .contains(&positions.1)
+        {
+            positions.1 += 1;
+            positions.0 += 1;
+        }
+    }
+
+    /// Encrypts only the characters of the given text in the given range, producing what `encrypt_traced()` produces for
+    /// those characters. The text before the range is passed through the machine first to step the rotors to where they
+    /// are at the start of the range, which makes this useful for re-encrypting part of a text after it was edited. Like
+    /// `encrypt_traced()`, this ignores the machine's transliteration, so characters that the machine can't encrypt pass
+    /// through unchanged.
+    ///
+    /// # Parameters
+    /// - `text` - The full text.
+    /// - `range` - The range of characters (not bytes) of the given text to encrypt. This is clamped to the length of the
+    ///   text. The indices are into the text as given, before any letters are expanded by uppercasing, such as `ß` into
+    ///   `SS`, so they can differ from the indices of the same characters in the output of `encrypt_traced()`.
+    ///
+    /// # Returns
+    /// The encrypted characters in the given range.
+    pub fn encrypt_range(&self, text: &str, range: std::ops::Range<usize>) -> String {
+        let (_, positions) = self.process_stepped(&text.chars().take(range.start).collect::<String>(), None);
+
+        let machine = EnigmaMachine {
+            ring_positions: positions,
+            ..self.clone()
+        };
+        machine.process(&text.chars().skip(range.start).take(range.end.saturating_sub(range.start)).collect::<String>(), None)
+    }
+
+    /// Creates a machine with random settings, such as for testing or demonstrations. The machine has three distinct
+    /// random rotors, random ring settings and positions, reflector B, and a random plugboard of 10 pairs, so it's always
+    /// a historically plausible configuration with no warnings from `validate()`.
+    ///
+    /// # Parameters
+    /// - `rng` - The random number generator to generate the settings with.
+    ///
+    /// # Returns
+    /// The randomly configured machine.
+    pub fn random(rng: &mut impl rand::Rng) -> EnigmaMachine {
+        use rand::seq::SliceRandom as _;
+
+        let mut rotors = (1..=8).collect::<Vec<u8>>();
+        rotors.shuffle(rng);
+
+        let mut letters = ('A'..='Z').collect::<Vec<_>>();
+        letters.shuffle(rng);
+        let plugboard = letters.chunks(2).take(10).map(|pair| pair.iter().collect::<String>()).collect::<Vec<_>>().join(" ");
+
+        EnigmaMachine::new()
+            .rotors(rotors[0], rotors[1], rotors[2])
+            .reflector("B")
+            .ring_settings(rng.gen_range(1..=26), rng.gen_range(1..=26), rng.gen_range(1..=26))
+            .ring_positions(rng.gen_range(1..=26), rng.gen_range(1..=26), rng.gen_range(1..=26))
+            .plugboard(&plugboard)
+            .unwrap()
+    }
+
+    /// Returns the number of possible settings of an Enigma machine with a single reflector, which is the number of
+    /// keys a brute force attack would have to try. This counts every ordered choice of three distinct rotors from the
+    /// rotors available, every ring position and ring setting of each rotor, and every way of connecting the given
+    /// number of plugboard pairs.
+    ///
+    /// Some of these settings encrypt identically, such as the ring settings of the leftmost rotor, whose effect can
+    /// always be matched by changing its position instead, so this is an upper bound on the number of distinct
+    /// encryptions.
+    ///
+    /// # Parameters
+    /// - `rotor_count` - The number of rotors to choose from, i.e., `5` for the Army's Enigma I or `8` for the Navy's M3.
+    /// - `plugboard_pairs` - The number of plugboard cables, which was usually `10`.
+    ///
+    /// # Returns
+    /// The number of settings, which is `0` if there are fewer than 3 rotors or more than 13 plugboard pairs.
+    pub fn keyspace_size(rotor_count: u8, plugboard_pairs: u8) -> u128 {
+        if plugboard_pairs > 13 {
+            return 0;
+        }
+
+        let rotor_orders = (0..3).map(|index| (rotor_count as u128).saturating_sub(index)).product::<u128>();
+        let positions = 26u128.pow(3);
+        let ring_settings = 26u128.pow(3);
+
+        // 26! / ((26 - 2p)! * p! * 2^p), which is the ways to choose 2p letters, pair them up, and ignore the order of the
+        // pairs and of the letters in each pair
+        let factorial = |n: u128| (1..=n).product::<u128>();
+        let pairs = plugboard_pairs as u128;
+        let plugboards = factorial(26) / (factorial(26 - 2 * pairs) * factorial(pairs) * 2u128.pow(pairs as u32));
+
+        rotor_orders * positions * ring_settings * plugboards
+    }
+
+    /// Checks this machine's settings for problems that don't prevent encryption but make the configuration physically
+    /// impossible or historically implausible, such as using the same rotor twice. The builder already rejects settings
+    /// that can't be simulated at all; This is for interfaces that want to warn about unrealistic settings rather than
+    /// refuse them.
+    ///
+    /// # Returns
+    /// Every problem found with the settings, which is empty if the configuration is plausible.
+    pub fn validate(&self) -> Vec<SettingsWarning> {
+        let mut warnings = Vec::new();
+
+        let rotors = [self.rotors.0, self.rotors.1, self.rotors.2];
+        for (index, rotor) in rotors.iter().enumerate() {
+            if rotors[index + 1..].contains(rotor) && !rotors[..index].contains(rotor) {
+                warnings.push(SettingsWarning::DuplicateRotor(rotor.number()));
+            }
+        }
+
+        let pairs = self.plugboard_cables.len();
+        if !(5..=10).contains(&pairs) {
+            warnings.push(SettingsWarning::UnusualPlugboardSize(pairs));
+        }
+
+        if !self.rotor_set.fits_reflector(self.reflector) {
+            warnings.push(SettingsWarning::ReflectorFromOtherModel(self.reflector));
+        } else if matches!(self.reflector, Reflector::BThin | Reflector::CThin) && self.fourth_rotor.is_none() {
+            warnings.push(SettingsWarning::ThinReflectorWithoutGreekRotor(self.reflector));
+        }
+
+        warnings
+    }
+
+    /// Returns the pairs of letters that this machine's plugboard connects, in the same canonical form regardless of how
+    /// the plugboard was given: Each pair has its letters in alphabetical order, and the pairs are sorted. Joining the
+    /// pairs with spaces gives a plugboard string that `EnigmaBuilder::plugboard()` accepts.
+    ///
+    /// # Returns
+    /// The connected pairs of letters, which is empty if the plugboard has no cables.
+    pub fn plugboard_pairs(&self) -> Vec<(char, char)> {
+        let mut pairs = self
+            .plugboard_cables
+            .iter()
+            .map(|(first, second)| (*first.min(second), *first.max(second)))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    /// Rebuilds the plugboard's connections from its cables and the Uhr, after either has changed.
+    ///
+    /// # Errors
+    /// If the machine has an Uhr, but not exactly 10 plugboard cables.
+    fn rewire_plugboard(&mut self) -> anyhow::Result<()> {
+        let Some(position) = self.uhr else {
+            self.plugboard = self.plugboard_cables.iter().flat_map(|(first, second)| [(*first, *second), (*second, *first)]).collect();
+            self.plugboard_back = self.plugboard.clone();
+            return Ok(());
+        };
+
+        if self.plugboard_cables.len() != uhr::CABLES {
+            anyhow::bail!(
+                "The Uhr requires exactly {} plugboard pairs, but the plugboard has {}",
+                uhr::CABLES,
+                self.plugboard_cables.len()
+            );
+        }
+        self.plugboard = uhr::connections(&self.plugboard_cables, position);
+        self.plugboard_back = self.plugboard.iter().map(|(from, to)| (*to, *from)).collect();
+        Ok(())
+    }
+
+    /// Returns a copy of this machine with its reflector replaced by the given one, leaving every other setting unchanged.
+    /// This allows trying many reflectors over a fixed rotor configuration without rebuilding the machine.
+    ///
+    /// # Parameters
+    /// - `reflector` - The reflector to give the new machine.
+    ///
+    /// # Returns
+    /// The new machine.
+    pub fn with_reflector(&self, reflector: Reflector) -> EnigmaMachine {
+        EnigmaMachine { reflector, ..self.clone() }
+    }
+
+    /// Returns a copy of this machine that uses the rotor wirings of the given rotor set, such as to simulate the Railway
+    /// Enigma. The machine's rotor numbers, ring settings, and positions are kept, but refer to the rotors of the new set.
+    ///
+    /// # Parameters
+    /// - `rotor_set` - The rotor set to use.
+    ///
+    /// # Returns
+    /// The new machine.
+    ///
+    /// # Errors
+    /// If any of the machine's rotors aren't in the given set, i.e., rotor 4 in the Railway Enigma's set of 3 rotors.
+    pub fn with_rotor_set(&self, rotor_set: RotorSet) -> anyhow::Result<EnigmaMachine> {
+        for rotor in [self.rotors.0, self.rotors.1, self.rotors.2].into_iter().chain(self.fourth_rotor) {
+            if !rotor_set.contains(rotor) {
+                anyhow::bail!(
+                    "Rotor {} is not in the rotor set {rotor_set:?}, which has {} rotors.",
+                    rotor.number(),
+                    rotor_set.rotor_count()
+                );
+            }
+        }
+
+        let mut machine = self.clone();
+        machine.rotor_set = rotor_set;
+        Ok(machine)
+    }
+
+    /// Returns a copy of this machine that transliterates characters it can't encrypt, such as digits, into letter codes
+    /// before encrypting, and translates the codes back after decrypting. This allows messages containing numbers to be
+    /// round-tripped, i.e., `machine.decrypt(&machine.encrypt(text))` keeps the digits of `text`. This doesn't affect
+    /// `encrypt_traced()` or `encrypt_range()`.
+    ///
+    /// # Parameters
+    /// - `transliteration` - The transliteration table to use, such as `Transliteration::german_naval()`.
+    ///
+    /// # Returns
+    /// The new machine.
+    pub fn with_transliteration(&self, transliteration: Transliteration) -> EnigmaMachine {
+        let mut machine = self.clone();
+        machine.options.transliteration = Some(transliteration);
+        machine
+    }
+}
+
+/// Enigma is reciprocal, so a machine encrypts and decrypts identically, unless it has a transliteration, which is applied
+/// when encrypting and undone when decrypting.
+impl cipher_utils::cipher::Cipher for EnigmaMachine {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        Ok(EnigmaMachine::encrypt(self, plaintext))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(EnigmaMachine::decrypt(self, ciphertext))
+    }
+
+    fn is_reciprocal(&self) -> bool {
+        self.options.transliteration.is_none()
     }
 }
 
+/// The path of a single letter through an Enigma machine, as returned by `EnigmaMachine::encrypt_traced()`. Each field
+/// holds the letter's value after passing through the named component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CharTrace {
+    /// The letter before encryption.
+    pub input: char,
+
+    /// The letter after its first pass through the plugboard.
+    pub plugboard: char,
+
+    /// The letter after passing through the third (rightmost) rotor.
+    pub third_rotor: char,
+
+    /// The letter after passing through the second (middle) rotor.
+    pub second_rotor: char,
+
+    /// The letter after passing through the first (leftmost) rotor.
+    pub first_rotor: char,
+
+    /// The letter after passing through the static fourth rotor, if the machine has one.
+    pub fourth_rotor: Option<char>,
+
+    /// The letter after passing through the reflector.
+    pub reflector: char,
+
+    /// The letter after passing back through the static fourth rotor, if the machine has one.
+    pub fourth_rotor_back: Option<char>,
+
+    /// The letter after passing back through the first rotor.
+    pub first_rotor_back: char,
+
+    /// The letter after passing back through the second rotor.
+    pub second_rotor_back: char,
+
+    /// The letter after passing back through the third rotor.
+    pub third_rotor_back: char,
+
+    /// The encrypted letter, after its second pass through the plugboard.
+    pub output: char,
+}
+
+impl CharTrace {
+    /// Prints this trace in the format of the `debug` option; See `MachineOptions::debug`.
+    fn print(&self) {
+        let before_reflector = self.fourth_rotor.unwrap_or(self.first_rotor);
+        let after_reflector = self.fourth_rotor_back.unwrap_or(self.reflector);
+        let stages = [
+            Some(("through", "plugboard", self.input, self.plugboard)),
+            Some(("through", "third rotor", self.plugboard, self.third_rotor)),
+            Some(("through", "second rotor", self.third_rotor, self.second_rotor)),
+            Some(("through", "first rotor", self.second_rotor, self.first_rotor)),
+            self.fourth_rotor.map(|letter| ("through", "fourth rotor", self.first_rotor, letter)),
+            Some(("through", "reflector", before_reflector, self.reflector)),
+            self.fourth_rotor_back.map(|letter| ("back through", "fourth rotor", self.reflector, letter)),
+            Some(("back through", "first rotor", after_reflector, self.first_rotor_back)),
+            Some(("back through", "second rotor", self.first_rotor_back, self.second_rotor_back)),
+            Some(("back through", "third rotor", self.second_rotor_back, self.third_rotor_back)),
+            Some(("back through", "plugboard", self.third_rotor_back, self.output)),
+        ];
+
+        for (direction, component, old_letter, letter) in stages.into_iter().flatten() {
+            println!(
+                "\tPassing character {direction} {}: '{}' -> '{}'",
+                component.green().bold(),
+                old_letter.to_string().bold().cyan(),
+                letter.to_string().bold().cyan(),
+            );
+        }
+        println!("\tFinalized character: '{}'", self.output.to_string().bold().cyan());
+    }
+}
+
+/// Parses three ring settings or rotor positions from a key sheet field. Each may be written as a number in `[1, 26]` or as
+/// a letter, and letters may be written without spaces, i.e., `01 17 12`, `A Q L`, and `AQL` are all equivalent.
+///
+/// # Parameters
+/// - `value` - The value of the key sheet field.
+///
+/// # Returns
+/// The three parsed settings, each in `[1, 26]`.
+///
+/// # Errors
+/// If the value doesn't contain exactly three settings, or any setting is out of range.
+fn parse_key_sheet_triple(value: &str) -> anyhow::Result<(u8, u8, u8)> {
+    let tokens = if !value.contains(char::is_whitespace) && value.chars().all(|character| character.is_ascii_alphabetic()) {
+        value.chars().map(|letter| letter.to_string()).collect::<Vec<_>>()
+    } else {
+        value.split_whitespace().map(str::to_owned).collect()
+    };
+
+    let settings = tokens
+        .iter()
+        .map(|token| {
+            let setting = match ALPHABET.index_of(token.chars().next().unwrap_or_default()) {
+                Some(index) if token.len() == 1 && token.chars().all(|character| character.is_ascii_alphabetic()) => *index + 1,
+                _ => token
+                    .parse::<u8>()
+                    .map_err(|_| anyhow::anyhow!("Error parsing Enigma key sheet: Invalid setting \"{token}\""))?,
+            };
+            if !(1..=26).contains(&setting) {
+                anyhow::bail!("Error parsing Enigma key sheet: Setting out of range: {token}");
+            }
+            Ok(setting)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let [first, second, third] = settings[..] else {
+        anyhow::bail!("Error parsing Enigma key sheet: Expected three settings but found \"{value}\"");
+    };
+
+    Ok((first, second, third))
+}
+
 /// A trait applied to `anyhow::Result<EnigmaMachine>` that allows building an enigma machine and passing along errors if they occur.
 pub trait EnigmaBuilder {
     /// Sets the rotors for the machine.
@@ -371,9 +999,92 @@ pub trait EnigmaBuilder {
     /// # Errors
     /// If the machine builder passed to this is already an error, an error is returned immediately.
     ///
-    /// If the given numbers are not all in `[1, 26]`, an error is returned.
+    /// If the given numbers are not all in `[1, 26]`, or if any of the rotors isn't in the machine's rotor set, an error
+    /// is returned.
     fn rotors(self, first: u8, second: u8, third: u8) -> anyhow::Result<EnigmaMachine>;
 
+    /// Sets the rotors for the machine from their Roman numerals, as historical documentation refers to them. The
+    /// numerals are case-insensitive and separated by whitespace, i.e., `"III I II"` is the same as `.rotors(3, 1, 2)`.
+    ///
+    /// # Parameters
+    /// - `rotors` - The Roman numerals of the three rotors, from first to third.
+    ///
+    /// # Returns
+    /// The machine builder with the given rotors applied.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    ///
+    /// If there aren't exactly three numerals, or if a numeral isn't one of `I` through `VIII`, an error is returned.
+    ///
+    /// If any of the rotors isn't in the machine's rotor set, an error is returned.
+    fn rotors_roman(self, rotors: &str) -> anyhow::Result<EnigmaMachine>;
+
+    /// Sets the rotors for the machine from `Rotor` values rather than numbers, which allows using custom rotors created
+    /// with `Rotor::custom()` alongside the built-in ones.
+    ///
+    /// # Parameters
+    /// - `first` - The first rotor to use
+    /// - `second` - The second rotor to use
+    /// - `third` - The third rotor to use
+    ///
+    /// # Returns
+    /// The machine builder with the given rotors applied.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    ///
+    /// If any of the rotors isn't in the machine's rotor set, an error is returned.
+    fn custom_rotors(self, first: Rotor, second: Rotor, third: Rotor) -> anyhow::Result<EnigmaMachine>;
+
+    /// Adds a rotor with the given wiring and notches in the third (rightmost) slot, moving the rotors that were in the
+    /// second and third slots one slot to the left; The rotor in the first slot is removed. Calling this three times
+    /// sets all three rotors, from left to right, i.e.:
+    ///
+    /// ```rust,ignore
+    /// EnigmaMachine::new()
+    ///     .custom_rotor("EKMFLGDQVZNTOWYHXUSPAIBRCJ", &['Q'])
+    ///     .custom_rotor("AJDKSIRUXBLHWTMCQGZNPYFVOE", &['E'])
+    ///     .custom_rotor("BDFHJLCPRTXVZNYEIWGAKMUSQO", &['V'])
+    /// ```
+    ///
+    /// is the same machine as `.rotors(1, 2, 3)`. See `Rotor::custom()`.
+    ///
+    /// # Parameters
+    /// - `wiring` - The letters that `A` through `Z` are wired to, which must be a permutation of the alphabet.
+    /// - `notches` - The letters at which the rotor makes the rotor to its left step, i.e., `&['A', 'N']`.
+    ///
+    /// # Returns
+    /// The machine builder with the custom rotor added.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    ///
+    /// If the wiring isn't a permutation of the alphabet, or if a notch isn't a letter, an error is returned.
+    fn custom_rotor(self, wiring: &str, notches: &[char]) -> anyhow::Result<EnigmaMachine>;
+
+    /// Adds a static fourth rotor to the left of the other three, as in the Kriegsmarine's four-rotor M4. The fourth
+    /// rotor never steps, so it stays at the given position for the whole message, and letters pass through it between
+    /// the first rotor and the reflector in both directions. On the M4 it was one of the thin "Greek" rotors, used with
+    /// the thin reflector `BThin` or `CThin`; With both its ring setting and position at 1 (`A`), the M4 encrypts the
+    /// same as an M3 with the thick reflector `B` or `C`.
+    ///
+    /// # Parameters
+    /// - `rotor` - The rotor to use as the fourth rotor.
+    /// - `ring_setting` - The ring setting of the fourth rotor, in `[1, 26]`. See `EnigmaBuilder::ring_settings()`.
+    /// - `position` - The position of the fourth rotor, in `[1, 26]`. See `EnigmaBuilder::ring_positions()`.
+    ///
+    /// # Returns
+    /// The machine builder with the fourth rotor applied.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    ///
+    /// If the rotor isn't in the machine's rotor set, an error is returned.
+    ///
+    /// If the ring setting or position isn't in `[1, 26]`, an error is returned.
+    fn fourth_rotor(self, rotor: Rotor, ring_setting: u8, position: u8) -> anyhow::Result<EnigmaMachine>;
+
     /// Sets the plugboard for the machine. The given plugboard should be a space-separated string of letter pairs. This is automatically
     /// bidirectional, meaning the pair `AY` will map `A` to `Y` and also `Y` to `A`.
     ///
@@ -388,9 +1099,35 @@ pub trait EnigmaBuilder {
     ///
     /// If the given plugboard contains duplicate letters, an error is returned.
     ///
+    /// If the given plugboard connects a letter to itself, i.e., `AA`, an error is returned.
+    ///
     /// If the given plugboard is not formatted as a space-separated list of letter pairs, an error is returned.
     fn plugboard(self, plugboard: &str) -> anyhow::Result<EnigmaMachine>;
 
+    /// Replaces the plugboard cables with the Uhr ("clock"), an attachment that connects the letters of the 10 plugboard
+    /// pairs through a rotating disc with a dial of 40 positions. Unlike the plugboard, the Uhr's connections generally
+    /// aren't reciprocal; If `A` is connected to `B` on the way into the machine, `B` generally isn't connected to `A`.
+    /// Only the positions that are multiples of 4 are reciprocal, and at `00` the Uhr is the same as the plain plugboard.
+    /// Each position gives a different set of connections, so the dial is an additional part of the key.
+    ///
+    /// The order of the pairs matters: The first letter of each pair is on the cable's "a" plug, and the second on its "b"
+    /// plug, and the cables are numbered in the order they're given, i.e., the plugboard `"AB CD"` puts `A` on plug 1a
+    /// and `D` on plug 2b. The whole machine stays reciprocal, as the letters pass through the Uhr in opposite
+    /// directions on the way in and out.
+    ///
+    /// # Parameters
+    /// - `position` - The position of the dial, in `[0, 40)`.
+    ///
+    /// # Returns
+    /// The machine builder with the Uhr applied.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    ///
+    /// If the position isn't in `[0, 40)`, or the plugboard doesn't have exactly 10 pairs, an error is returned. The
+    /// plugboard must be set before the Uhr.
+    fn uhr(self, position: u8) -> anyhow::Result<EnigmaMachine>;
+
     // Sets the reflector of the machine.
     ///
     /// # Parameters
@@ -405,7 +1142,10 @@ pub trait EnigmaBuilder {
     /// If the given reflector string does not represent an existing reflector.
     fn reflector(self, reflector: &str) -> anyhow::Result<EnigmaMachine>;
 
-    // Sets the ring settings of the machine.
+    /// Sets the ring settings ("Ringstellung") of the machine. The ring setting of a rotor rotates its internal wiring
+    /// relative to the lettered ring around it; A setting of 1 (`A` or `01`) leaves the wiring unrotated, and each
+    /// increase rotates the wiring forward by one letter. Unlike the ring positions, the ring settings don't change
+    /// where a rotor's notch is, so they don't affect when the rotors step.
     ///
     /// # Parameters
     /// - `first` - The first ring setting, in `[1, 26]`.
@@ -421,7 +1161,9 @@ pub trait EnigmaBuilder {
     /// If the given numbers are not all in `[1, 26]`, an error is returned.
     fn ring_settings(self, first: u8, second: u8, third: u8) -> anyhow::Result<EnigmaMachine>;
 
-    /// Sets the "ring positions" or "rotor positions" of the machine.
+    /// Sets the "ring positions" or "rotor positions" ("Grundstellung") of the machine; That is, the letters showing in
+    /// the machine's windows before the first letter is encrypted, where 1 is `A`. The rotors step before each letter is
+    /// encrypted, so the first letter is encrypted one step past these positions.
     ///
     /// # Parameters
     /// - `first` - The offset of the first rotor, in `[1, 26]`.
@@ -437,6 +1179,27 @@ pub trait EnigmaBuilder {
     /// If the given numbers are not all in `[1, 26]`, an error is returned.
     fn ring_positions(self, first: u8, second: u8, third: u8) -> anyhow::Result<EnigmaMachine>;
 
+    /// Relabels the machine's 26 contacts with the given symbols, such as the letters of a non-Latin script, for
+    /// simulating experimental machines. The rotors, reflector, and entry wheel are wired by position, so the `n`th
+    /// symbol of the alphabet takes the place of the `n`th letter of `A-Z` everywhere in the machine, and the machine
+    /// encrypts exactly as the standard one does up to this relabelling. The plugboard and the input are given in the
+    /// new symbols, and symbols are matched case-insensitively. The traces of `EnigmaMachine::encrypt_traced()` show
+    /// each letter as the symbol at its position.
+    ///
+    /// # Parameters
+    /// - `alphabet` - The 26 symbols to label the contacts with, in order.
+    ///
+    /// # Returns
+    /// The machine builder with the given alphabet applied.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    ///
+    /// If the given alphabet doesn't contain exactly 26 unique symbols, or contains whitespace, an error is returned.
+    ///
+    /// If the machine's plugboard connects a letter that isn't in the given alphabet, an error is returned.
+    fn alphabet(self, alphabet: &str) -> anyhow::Result<EnigmaMachine>;
+
     /// Disables case preservation for this machine. This means that the output will be entirely
     /// uppercase instead of preserving the original message's casing.
     ///
@@ -447,6 +1210,30 @@ pub trait EnigmaBuilder {
     /// If the machine builder passed to this is already an error, an error is returned immediately.
     fn clear_casing(self) -> anyhow::Result<EnigmaMachine>;
 
+    /// Makes this machine output every letter in lowercase, regardless of the casing of the input. Enigma output is
+    /// uppercase by convention, but lowercase can be easier to read. This only changes the casing of the output; The
+    /// encryption itself is unchanged, and the machine still accepts input of any case.
+    ///
+    /// # Returns
+    /// The machine builder with lowercase output enabled.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    fn lowercase_output(self) -> anyhow::Result<EnigmaMachine>;
+
+    /// Makes this machine keep the exact layout of its input, such as the spacing, line breaks, punctuation, and casing of
+    /// a formatted document. Only the letters of the machine's alphabet are encrypted, as one continuous message, and each
+    /// is put back where it was, in its original case; Every other character, including letters outside the alphabet, is
+    /// left where it was.
+    /// This takes precedence over `EnigmaBuilder::clear_casing()` and the removal of punctuation.
+    ///
+    /// # Returns
+    /// The machine builder with layout preservation enabled.
+    ///
+    /// # Errors
+    /// If the machine builder passed to this is already an error, an error is returned immediately.
+    fn preserve_layout(self) -> anyhow::Result<EnigmaMachine>;
+
     /// Enables debugging for this enigma machine. This means that during each step of encryption,
     /// the machine will print information to stdout about what's happening in the encryption and
     /// what each letter becomes as it goes through each stage of encryption.
@@ -458,10 +1245,65 @@ impl EnigmaBuilder for anyhow::Result<EnigmaMachine> {
         let rotors = (first, second, third)
             .try_into_rotors()
             .map_err(|error| anyhow::anyhow!("Error while setting ring positions when creating Enigma machine: {error}"))?;
-        self.map(|mut machine| {
-            machine.rotors = rotors;
-            machine
-        })
+        self.custom_rotors(rotors.0, rotors.1, rotors.2)
+    }
+
+    fn rotors_roman(self, rotors: &str) -> anyhow::Result<EnigmaMachine> {
+        let parsed = rotors
+            .split_whitespace()
+            .map(Rotor::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|error| anyhow::anyhow!("Error while setting rotors when creating Enigma machine: {error}"))?;
+        let [first, second, third] = parsed[..] else {
+            anyhow::bail!("Error while setting rotors when creating Enigma machine: Expected three rotors but found \"{rotors}\"");
+        };
+        self.custom_rotors(first, second, third)
+    }
+
+    fn custom_rotors(self, first: Rotor, second: Rotor, third: Rotor) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
+            if let Some(rotor) = [first, second, third].into_iter().find(|rotor| !machine.rotor_set.contains(*rotor)) {
+                anyhow::bail!(
+                    "Error while setting rotors when creating Enigma machine: Rotor {} is not in the rotor set {:?}",
+                    rotor.number(),
+                    machine.rotor_set
+                );
+            }
+            machine.rotors = (first, second, third);
+            Ok(machine)
+        } else {
+            self
+        }
+    }
+
+    fn custom_rotor(self, wiring: &str, notches: &[char]) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
+            let rotor = Rotor::custom(wiring, notches).map_err(|error| anyhow::anyhow!("Error while adding custom rotor when creating Enigma machine: {error}"))?;
+            machine.rotors = (machine.rotors.1, machine.rotors.2, rotor);
+            Ok(machine)
+        } else {
+            self
+        }
+    }
+
+    fn fourth_rotor(self, rotor: Rotor, ring_setting: u8, position: u8) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
+            if !machine.rotor_set.contains(rotor) {
+                anyhow::bail!(
+                    "Error while setting fourth rotor when creating Enigma machine: Rotor {} is not in the rotor set {:?}",
+                    rotor.number(),
+                    machine.rotor_set
+                );
+            }
+            machine.fourth_rotor = Some(rotor);
+            let index = |number: u8| {
+                AlphabetIndex::try_from(number.wrapping_sub(1)).map_err(|error| anyhow::anyhow!("Error while setting fourth rotor when creating Enigma machine: {error}"))
+            };
+            machine.fourth_rotor_settings = (index(ring_setting)?, index(position)?);
+            Ok(machine)
+        } else {
+            self
+        }
     }
 
     fn reflector(self, reflector: &str) -> anyhow::Result<EnigmaMachine> {
@@ -496,25 +1338,64 @@ impl EnigmaBuilder for anyhow::Result<EnigmaMachine> {
         }
     }
 
-    fn plugboard(self, plugboard: &str) -> anyhow::Result<EnigmaMachine> {
+    fn alphabet(self, alphabet: &str) -> anyhow::Result<EnigmaMachine> {
         if let Ok(mut machine) = self {
-            let mut chars = plugboard.chars().collect::<Vec<char>>();
-            chars.dedup();
-            if chars.len() != plugboard.len() {
-                anyhow::bail!("Plugboard contains duplicate characters: {plugboard}");
+            let letters = alphabet.chars().flat_map(char::to_uppercase).collect::<Vec<_>>();
+            if letters.len() != 26 || letters.iter().any(|letter| letters.iter().filter(|other| *other == letter).count() > 1) {
+                anyhow::bail!("Enigma alphabet must contain exactly 26 unique symbols: {alphabet}");
+            }
+            if letters.iter().any(|letter| letter.is_whitespace()) {
+                anyhow::bail!("Enigma alphabet cannot contain whitespace: {alphabet:?}");
+            }
+            if let Some(letter) = machine.plugboard.keys().find(|letter| !letters.contains(letter)) {
+                anyhow::bail!("Plugboard letter {letter:?} is not in the Enigma alphabet {alphabet}");
             }
 
+            machine.alphabet = Some(letters);
+            Ok(machine)
+        } else {
+            self
+        }
+    }
+
+    fn plugboard(self, plugboard: &str) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
             let mappings = plugboard.split_whitespace();
             let mut plugboard = std::collections::HashMap::new();
+            let mut cables = Vec::new();
             for pair in mappings {
-                let mut chars = pair.chars();
-                let first = chars.next().unwrap();
-                let second = chars.next().unwrap();
+                let [first, second] = pair.chars().collect::<Vec<_>>()[..] else {
+                    anyhow::bail!("Plugboard pair must be exactly two letters: {pair}");
+                };
+                if !machine.is_letter(first) || !machine.is_letter(second) {
+                    anyhow::bail!("Plugboard pair contains a non-letter: {pair}");
+                }
+                if first == second {
+                    anyhow::bail!("Plugboard cannot connect a letter to itself: {pair}");
+                }
+                if plugboard.contains_key(&first) || plugboard.contains_key(&second) {
+                    anyhow::bail!("Plugboard contains duplicate characters: {pair}");
+                }
                 plugboard.insert(first, second);
                 plugboard.insert(second, first);
+                cables.push((first, second));
             }
 
-            machine.plugboard = plugboard;
+            machine.plugboard_cables = cables;
+            machine.rewire_plugboard()?;
+            Ok(machine)
+        } else {
+            self
+        }
+    }
+
+    fn uhr(self, position: u8) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
+            if position >= uhr::POSITIONS {
+                anyhow::bail!("Uhr position must be in [0, {}): {position}", uhr::POSITIONS);
+            }
+            machine.uhr = Some(position);
+            machine.rewire_plugboard()?;
             Ok(machine)
         } else {
             self
@@ -530,6 +1411,24 @@ impl EnigmaBuilder for anyhow::Result<EnigmaMachine> {
         }
     }
 
+    fn lowercase_output(self) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
+            machine.options.lowercase_output = true;
+            Ok(machine)
+        } else {
+            self
+        }
+    }
+
+    fn preserve_layout(self) -> anyhow::Result<EnigmaMachine> {
+        if let Ok(mut machine) = self {
+            machine.options.preserve_layout = true;
+            Ok(machine)
+        } else {
+            self
+        }
+    }
+
     fn debug(self) -> anyhow::Result<EnigmaMachine> {
         if let Ok(mut machine) = self {
             machine.options.debug = true;
@@ -539,3 +1438,79 @@ impl EnigmaBuilder for anyhow::Result<EnigmaMachine> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+
+    use crate::{alphabet::AlphabetIndex, EnigmaBuilder as _, EnigmaMachine, Rotor};
+
+    #[test]
+    fn custom_rotor_notches() -> anyhow::Result<()> {
+        let rotor = Rotor::custom("BDFHJLCPRTXVZNYEIWGAKMUSQO", &['A', 'n', 'A'])?;
+        assert_eq!(&['A', 'N'], rotor.notches());
+
+        let machine = EnigmaMachine::new().custom_rotors(Rotor::I, Rotor::III, rotor).reflector("B").ring_positions(1, 1, 1)?;
+        let mut positions = machine.ring_positions;
+        for _ in 0..52 {
+            let (before, middle) = (positions.2, positions.1);
+            machine.step_rotors(&mut positions);
+            let notched = before == AlphabetIndex::try_from(0)? || before == AlphabetIndex::try_from(13)?;
+            assert_eq!(notched, positions.1 != middle, "Expected the middle rotor to step only at A and N, stepped at {}", *before);
+        }
+        assert_eq!(AlphabetIndex::try_from(4)?, positions.1);
+
+        // A custom rotor with the wiring and notch of a built-in rotor is the same rotor
+        let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG".repeat(20);
+        let builtin = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").ring_positions(1, 4, 21)?;
+        let custom = EnigmaMachine::new()
+            .custom_rotors(Rotor::I, Rotor::II, Rotor::custom("BDFHJLCPRTXVZNYEIWGAKMUSQO", &['V'])?)
+            .reflector("B")
+            .ring_positions(1, 4, 21)?;
+        assert_eq!(builtin.encrypt(&text), custom.encrypt(&text));
+
+        assert!(Rotor::custom("ABC", &['A']).is_err());
+        assert!(Rotor::custom("AACDEFGHIJKLMNOPQRSTUVWXYZ", &['A']).is_err());
+        assert!(Rotor::custom("BDFHJLCPRTXVZNYEIWGAKMUSQO", &['1']).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn uhr_is_not_reciprocal() -> anyhow::Result<()> {
+        let plugboard = "AV BS CG DL FU HZ IN KM OW RX";
+        let plugged = plugboard.chars().filter(char::is_ascii_alphabetic).collect::<std::collections::HashSet<_>>();
+
+        let mut reciprocal_positions = Vec::new();
+        for position in 0..40 {
+            let machine = EnigmaMachine::new().plugboard(plugboard).uhr(position)?;
+            assert_eq!(plugged, machine.plugboard.keys().copied().collect());
+            assert_eq!(plugged, machine.plugboard.values().copied().collect());
+            for (from, to) in &machine.plugboard {
+                assert_eq!(from, &machine.plugboard_back[to]);
+            }
+            if machine.plugboard == machine.plugboard_back {
+                reciprocal_positions.push(position);
+            }
+        }
+        assert_eq!((0..40).step_by(4).collect::<Vec<_>>(), reciprocal_positions);
+
+        // At 00, the Uhr is the same as the plain plugboard
+        let plain = EnigmaMachine::new().plugboard(plugboard)?;
+        assert_eq!(plain.plugboard, EnigmaMachine::new().plugboard(plugboard).uhr(0)?.plugboard);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plugboard_is_involution() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let machine = EnigmaMachine::random(&mut rng);
+            for letter in 'A'..='Z' {
+                let plugged = *machine.plugboard.get(&letter).unwrap_or(&letter);
+                assert_eq!(letter, *machine.plugboard.get(&plugged).unwrap_or(&plugged));
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::reflector::Reflector;
+
+/// A problem with an Enigma machine's settings that doesn't prevent it from encrypting, but that makes the configuration
+/// physically impossible or historically implausible. These are returned by `EnigmaMachine::validate()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingsWarning {
+    /// The rotor with the given number is used in more than one slot, which is impossible on a real machine since each
+    /// rotor is a single physical wheel.
+    DuplicateRotor(u8),
+
+    /// The plugboard has the given number of pairs, outside of the 5 to 10 pairs that were used in practice.
+    UnusualPlugboardSize(usize),
+
+    /// A thin reflector is used without a fourth Greek rotor. Thin reflectors were made for the four-rotor M4, and only
+    /// fit alongside a Greek rotor.
+    ThinReflectorWithoutGreekRotor(Reflector),
+
+    /// The reflector belongs to a different Enigma model than the machine's rotor set, such as the Railway Enigma's
+    /// reflector with the Enigma I's rotors. See `RotorSet::fits_reflector()`.
+    ReflectorFromOtherModel(Reflector),
+}
+
+impl std::fmt::Display for SettingsWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateRotor(rotor) => write!(f, "Rotor {rotor} is used more than once"),
+            Self::UnusualPlugboardSize(pairs) => write!(f, "The plugboard has {pairs} pairs, but 5 to 10 pairs were used in practice"),
+            Self::ThinReflectorWithoutGreekRotor(reflector) => write!(f, "The thin reflector {reflector:?} is used without a Greek rotor"),
+            Self::ReflectorFromOtherModel(reflector) => write!(f, "The reflector {reflector:?} is from a different Enigma model than the rotors"),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{alphabet::AlphabetIndex, EnigmaMachine};
+
+/// An Enigma machine that remembers its rotor positions between calls, allowing text to be encrypted or decrypted as it
+/// arrives, such as in a chat or stream.
+///
+/// `EnigmaMachine::decrypt()` always starts at the machine's configured ring positions; `StatefulEnigma::feed()` instead
+/// continues from wherever the previous call left off, so feeding a text in several pieces produces the same output as
+/// decrypting it all at once.
+pub struct StatefulEnigma {
+    /// The machine whose settings are used for encryption.
+    machine: EnigmaMachine,
+
+    /// The current positions of the rotors, which advance as letters are fed through the machine.
+    positions: (AlphabetIndex, AlphabetIndex, AlphabetIndex),
+}
+
+impl StatefulEnigma {
+    /// Creates a new stateful machine, with its rotors starting at the given machine's ring positions.
+    ///
+    /// # Parameters
+    /// - `machine` - The machine whose settings to use.
+    ///
+    /// # Returns
+    /// The created stateful machine.
+    pub fn new(machine: EnigmaMachine) -> Self {
+        let positions = machine.ring_positions;
+        Self { machine, positions }
+    }
+
+    /// Encrypts or decrypts the given text, continuing from the rotor positions that the last call left off at.
+    ///
+    /// # Parameters
+    /// - `text` - The next piece of text to pass through the machine.
+    ///
+    /// # Returns
+    /// The encrypted or decrypted text.
+    pub fn feed(&mut self, text: &str) -> String {
+        let mut machine = self.machine.clone();
+        machine.ring_positions = self.positions;
+        let (output, positions) = machine.decrypt_stepped(text);
+        self.positions = positions;
+        output
+    }
+
+    /// Moves the rotors back to the machine's configured ring positions, as if nothing had been fed through it.
+    pub fn reset(&mut self) {
+        self.positions = self.machine.ring_positions;
+    }
+}
+
+impl From<EnigmaMachine> for StatefulEnigma {
+    fn from(machine: EnigmaMachine) -> Self {
+        Self::new(machine)
+    }
+}
@@ -0,0 +1,103 @@
+/// A table for spelling out characters that an Enigma machine can't encrypt, such as digits, as letters. Enigma machines
+/// only have the 26 letters, so operators wrote numbers and punctuation as agreed-upon letter codes before encrypting,
+/// and the recipient translated them back after decrypting.
+///
+/// Reversing a transliteration replaces every occurrence of a code in the decrypted text, so plain words that happen to
+/// contain a code will also be translated; Choose codes that don't appear in the message text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transliteration {
+    /// The code for each transliterated character, sorted from longest to shortest code so that longer codes take
+    /// precedence when reversing.
+    codes: Vec<(char, String)>,
+}
+
+impl Transliteration {
+    /// Creates a new transliteration from a map between characters and the letters to spell them as.
+    ///
+    /// # Parameters
+    /// - `map` - The code for each character. Codes are case-insensitive.
+    ///
+    /// # Returns
+    /// The created transliteration.
+    ///
+    /// # Errors
+    /// If any code is empty or contains a character that's not an English letter.
+    pub fn new(map: std::collections::HashMap<char, String>) -> anyhow::Result<Self> {
+        let mut codes = Vec::new();
+        for (character, code) in map {
+            if code.is_empty() || !code.chars().all(|letter| letter.is_ascii_alphabetic()) {
+                anyhow::bail!("Invalid transliteration code for {character:?}: {code:?}; Codes must be made of letters.");
+            }
+            codes.push((character, code.to_ascii_uppercase()));
+        }
+        codes.sort_by(|first, other| other.1.len().cmp(&first.1.len()).then(first.0.cmp(&other.0)));
+        Ok(Self { codes })
+    }
+
+    /// Returns the convention used by the German navy, which spelled out each digit as its German name, using `SEQS` for
+    /// six and `ZWO` for two to avoid confusion with `SIEBEN` and `DREI`, and used `X` for a full stop.
+    ///
+    /// # Returns
+    /// The German naval transliteration.
+    pub fn german_naval() -> Self {
+        Self::new(std::collections::HashMap::from(
+            [
+                ('0', "NULL"),
+                ('1', "EINS"),
+                ('2', "ZWO"),
+                ('3', "DREI"),
+                ('4', "VIER"),
+                ('5', "FUNF"),
+                ('6', "SEQS"),
+                ('7', "SIEBEN"),
+                ('8', "ACHT"),
+                ('9', "NEUN"),
+                ('.', "X"),
+            ]
+            .map(|(character, code)| (character, code.to_owned())),
+        ))
+        .unwrap()
+    }
+
+    /// Replaces each transliterated character in the given text with its code.
+    ///
+    /// # Parameters
+    /// - `text` - The text to transliterate.
+    ///
+    /// # Returns
+    /// The transliterated text.
+    pub fn apply(&self, text: &str) -> String {
+        let mut transliterated = String::with_capacity(text.len());
+        for character in text.chars() {
+            match self.codes.iter().find(|(transliterated_character, _)| *transliterated_character == character) {
+                Some((_, code)) => transliterated.push_str(code),
+                None => transliterated.push(character),
+            }
+        }
+        transliterated
+    }
+
+    /// Replaces each code in the given text with the character it represents, undoing `Transliteration::apply()`.
+    ///
+    /// # Parameters
+    /// - `text` - The text to reverse the transliteration of.
+    ///
+    /// # Returns
+    /// The text with codes replaced by their characters.
+    pub fn reverse(&self, text: &str) -> String {
+        let mut reversed = String::with_capacity(text.len());
+        let mut rest = text;
+        'text: while let Some(character) = rest.chars().next() {
+            for (transliterated_character, code) in &self.codes {
+                if let Some(remaining) = rest.strip_prefix(code.as_str()) {
+                    reversed.push(*transliterated_character);
+                    rest = remaining;
+                    continue 'text;
+                }
+            }
+            reversed.push(character);
+            rest = &rest[character.len_utf8()..];
+        }
+        reversed
+    }
+}
@@ -0,0 +1,87 @@
+/// The wiring of the Uhr's rotating disc, from each of its 40 contacts on the side of the a-plugs to the contact on the
+/// side of the b-plugs that it's wired to. Each contact `k` is wired to a contact that's `k + 2` mod 4, so a thick pin on
+/// one side always meets a thin pin on the other, at every position.
+const WIRING: [u8; 40] = [
+    6, 31, 4, 29, 18, 39, 16, 25, 30, 23, 28, 1, 38, 11, 36, 37, 26, 27, 24, 21, 14, 3, 12, 17, 2, 7, 0, 33, 10, 35, 8, 5, 22, 19, 20, 13, 34, 15, 32, 9,
+];
+
+/// The group of four contacts on the side of the b-plugs that each cable's b-plug is on. The a-plug of cable `i` is on
+/// group `i` of the other side, and the b-plugs are placed so that at position `00` the disc connects each a-plug to the
+/// b-plug of the same cable, so the Uhr at `00` is the same as the plain plugboard.
+const B_PLUG_GROUPS: [usize; 10] = [1, 4, 7, 9, 6, 3, 0, 2, 5, 8];
+
+/// The number of positions of the Uhr's dial, which are numbered from `00` to `39`.
+pub(crate) const POSITIONS: u8 = 40;
+
+/// The number of plugboard cables that the Uhr replaces.
+pub(crate) const CABLES: usize = 10;
+
+/// Returns the connections that the Uhr makes between the letters of the given plugboard cables at the given position.
+///
+/// Each plug has a thick pin, which is connected to its letter's keyboard and lamp, and a thin pin, which is connected to
+/// the letter's contact on the entry wheel, just as with a plain plugboard cable. The plugs of cable `i` sit on the
+/// contacts `4 * g` (thick pin) and `4 * g + 2` (thin pin) of their side of the disc, where `g` is `i` for the a-plug
+/// and `B_PLUG_GROUPS[i]` for the b-plug. Turning the dial rotates the disc by one contact per position, which changes
+/// which b-plug each a-plug is connected to. The connections are reciprocal at the positions that are multiples of 4,
+/// where each a-plug's pins both meet the same b-plug, and aren't otherwise; At `00` they're the same as the plain
+/// plugboard.
+///
+/// # Parameters
+/// - `cables` - The 10 plugboard cables, in order, as `(a-plug, b-plug)` letter pairs.
+/// - `position` - The position of the dial, in `[0, 40)`.
+///
+/// # Returns
+/// The letter that each plugged letter is connected to on its way into the machine. Letters that aren't plugged aren't
+/// in the map, and stay the same. The letters are connected back the opposite way on their way out of the machine.
+pub(crate) fn connections(cables: &[(char, char)], position: u8) -> std::collections::HashMap<char, char> {
+    let position = position as usize;
+    let b_plug_at = |contact: usize| {
+        let group = contact / 4;
+        cables[B_PLUG_GROUPS.iter().position(|b_plug_group| *b_plug_group == group).unwrap()].1
+    };
+    let across = |contact: usize| (WIRING[(contact + position) % 40] as usize + 40 - position) % 40;
+
+    let mut connections = std::collections::HashMap::new();
+    for (index, (a_plug, _)) in cables.iter().enumerate() {
+        // The a-plug's keyboard goes to a b-plug's entry wheel contact, and a b-plug's keyboard comes to its entry wheel
+        // contact
+        connections.insert(*a_plug, b_plug_at(across(4 * index)));
+        connections.insert(b_plug_at(across(4 * index + 2)), *a_plug);
+    }
+    connections
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::uhr::connections;
+
+    #[test]
+    fn connections_at_position() {
+        let cables = [
+            ('A', 'V'),
+            ('B', 'S'),
+            ('C', 'G'),
+            ('D', 'L'),
+            ('F', 'U'),
+            ('H', 'Z'),
+            ('I', 'N'),
+            ('K', 'M'),
+            ('O', 'W'),
+            ('R', 'X'),
+        ];
+
+        // At 00, each a-plug is connected to the b-plug of its own cable, like a plain cable
+        for (a_plug, b_plug) in cables {
+            assert_eq!(b_plug, connections(&cables, 0)[&a_plug]);
+            assert_eq!(a_plug, connections(&cables, 0)[&b_plug]);
+        }
+
+        // At 01, 2a's thick pin on contact 04 meets the disc's contact 05, which is wired to 39, and so reaches contact 38
+        // on the other side, the thin pin of 4b on group 9. 2a's thin pin on contact 06 meets the disc's contact 07,
+        // which is wired to 25, and so reaches contact 24, the thick pin of 5b on group 6.
+        let position_1 = connections(&cables, 1);
+        assert_eq!('L', position_1[&'B']);
+        assert_eq!('B', position_1[&'U']);
+        assert_ne!('B', position_1[&'L']);
+    }
+}
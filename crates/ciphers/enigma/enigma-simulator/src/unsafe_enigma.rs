@@ -19,7 +19,7 @@ impl UncheckedEnigmaMachine {
     /// This is exactly the same as calling `machine.encrypt_unchecked(text)`, since the enigma cipher is
     /// symmetric; The only difference is semantic meaning and intent, i.e.,
     ///
-    /// ```rust
+    /// ```rust,ignore
     ///	assert_eq!(text, machine.decrypt_unchecked(machine.decrypt_unchecked(text)));
     ///	assert_eq!(text, machine.encrypt_unchecked(machine.encrypt_unchecked(text)));
     ///	assert_eq!(text, machine.decrypt_unchecked(machine.encrypt_unchecked(text)));
@@ -184,7 +184,7 @@ impl UncheckedEnigmaMachine {
     /// This is exactly the same as calling `machine.decrypt_unchecked(text)`, since the enigma cipher is
     /// symmetric; The only difference is semantic meaning and intent, i.e.,
     ///
-    /// ```rust
+    /// ```rust,ignore
     ///	assert_eq!(text, machine.decrypt_unchecked(machine.decrypt_unchecked(text)));
     ///	assert_eq!(text, machine.encrypt_unchecked(machine.encrypt_unchecked(text)));
     ///	assert_eq!(text, machine.decrypt_unchecked(machine.encrypt_unchecked(text)));
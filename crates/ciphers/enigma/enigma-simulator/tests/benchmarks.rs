@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use enigma_simulator::{EnigmaBuilder, EnigmaMachine, UncheckedEnigmaBuilder};
+use enigma_simulator::{EnigmaMachine, UncheckedEnigmaBuilder};
 use rand::seq::SliceRandom;
 use rand::Rng;
 
@@ -42,18 +42,9 @@ fn random_enigmas_benchmark() -> anyhow::Result<()> {
         // optimization doesn't make the second run through faster.
 
         // Safe API
-        let plugboard = random_plugboard(10);
-        let (rotor_a, rotor_b, rotor_c) = (random_in(1..=8), random_in(1..=8), random_in(1..=8));
-        let (ring_a, ring_b, ring_c) = (random_in(1..=26), random_in(1..=26), random_in(1..=26));
-        let (position_a, position_b, position_c) = (random_in(1..=26), random_in(1..=26), random_in(1..=26));
         let cipher = random_string(100);
         let start = std::time::Instant::now();
-        let machine = EnigmaMachine::new()
-            .reflector("B")
-            .plugboard(&plugboard)
-            .rotors(rotor_a, rotor_b, rotor_c)
-            .ring_settings(ring_a, ring_b, ring_c)
-            .ring_positions(position_a, position_b, position_c)?;
+        let machine = EnigmaMachine::random(&mut rand::thread_rng());
         machine.decrypt(&cipher);
         let elapsed = start.elapsed().as_nanos();
         checked_times.push(elapsed);
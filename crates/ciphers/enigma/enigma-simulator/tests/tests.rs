@@ -1,4 +1,6 @@
-use enigma_simulator::{EnigmaBuilder as _, EnigmaMachine, EnigmaResult, UncheckedEnigmaBuilder};
+use enigma_simulator::{
+    CharTrace, EnigmaBuilder as _, EnigmaMachine, EnigmaResult, Reflector, Rotor, RotorSet, SettingsWarning, StatefulEnigma, Transliteration, UncheckedEnigmaBuilder,
+};
 
 #[test]
 fn encrypt_and_decrypt() -> EnigmaResult<()> {
@@ -45,3 +47,849 @@ fn debug_information() -> EnigmaResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn encrypt_traced() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .plugboard("BY EW FZ GI QM RV UX")?;
+
+    // The example from the documentation of `MachineOptions::debug`
+    let (ciphertext, trace) = machine.encrypt_traced("H, I");
+    assert_eq!("I, J", ciphertext);
+    assert_eq!(machine.encrypt("H, I"), ciphertext);
+    assert_eq!(
+        vec![
+            CharTrace {
+                input: 'H',
+                plugboard: 'H',
+                third_rotor: 'C',
+                second_rotor: 'M',
+                first_rotor: 'V',
+                fourth_rotor: None,
+                reflector: 'W',
+                fourth_rotor_back: None,
+                first_rotor_back: 'C',
+                second_rotor_back: 'E',
+                third_rotor_back: 'G',
+                output: 'I',
+            },
+            CharTrace {
+                input: 'I',
+                plugboard: 'G',
+                third_rotor: 'B',
+                second_rotor: 'X',
+                first_rotor: 'X',
+                fourth_rotor: None,
+                reflector: 'J',
+                fourth_rotor_back: None,
+                first_rotor_back: 'F',
+                second_rotor_back: 'K',
+                third_rotor_back: 'J',
+                output: 'J',
+            },
+        ],
+        trace
+    );
+
+    Ok(())
+}
+
+#[test]
+fn transliteration() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .plugboard("BY EW FZ GI QM RV UX")?
+        .with_transliteration(Transliteration::german_naval());
+
+    let ciphertext = machine.encrypt("ATTACK AT 0600");
+    assert!(ciphertext.chars().all(|character| character.is_ascii_uppercase() || character == ' '));
+    assert_eq!(machine.encrypt("ATTACK AT NULLSEQSNULLNULL"), ciphertext);
+    assert_eq!("ATTACK AT 0600", machine.decrypt(&ciphertext));
+
+    let transliteration = Transliteration::new(std::collections::HashMap::from([('1', "q".to_owned()), ('!', "QQ".to_owned())]))?;
+    assert_eq!("Q QQ", transliteration.apply("1 !"));
+    assert_eq!("1 !1", transliteration.reverse("Q QQQ"));
+    assert!(Transliteration::new(std::collections::HashMap::from([('1', "ONE1".to_owned())])).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn validate() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .plugboard("BY EW FZ GI QM RV UX")?;
+    assert!(machine.validate().is_empty());
+
+    let machine = EnigmaMachine::new()
+        .rotors(4, 2, 4)
+        .reflector("BThin")
+        .ring_settings(1, 1, 1)
+        .ring_positions(1, 1, 1)
+        .plugboard("AB CD")?;
+    assert_eq!(
+        vec![
+            SettingsWarning::DuplicateRotor(4),
+            SettingsWarning::UnusualPlugboardSize(2),
+            SettingsWarning::ThinReflectorWithoutGreekRotor(Reflector::BThin),
+        ],
+        machine.validate()
+    );
+
+    // Warnings don't stop the machine from working
+    assert_eq!("HELLO", machine.decrypt(&machine.encrypt("HELLO")));
+
+    let machine = EnigmaMachine::new().rotors(5, 5, 5).reflector("UKWR").plugboard("AB CD EF GH IJ KL MN OP QR ST UV")?;
+    assert_eq!(
+        vec![
+            SettingsWarning::DuplicateRotor(5),
+            SettingsWarning::UnusualPlugboardSize(11),
+            SettingsWarning::ReflectorFromOtherModel(Reflector::Ukwr),
+        ],
+        machine.validate()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lowercase_output() -> EnigmaResult<()> {
+    let builder = || {
+        EnigmaMachine::new()
+            .rotors(1, 2, 3)
+            .reflector("B")
+            .ring_settings(10, 12, 14)
+            .ring_positions(5, 22, 3)
+            .plugboard("BY EW FZ GI QM RV UX")
+    };
+    let machine = builder()?;
+    let lowercase = builder().lowercase_output()?;
+
+    let plaintext = "Attack at dawn, HOLD the BRIDGE!";
+    let ciphertext = lowercase.encrypt(plaintext);
+    assert!(!ciphertext.chars().any(|character| character.is_uppercase()));
+    assert_eq!(machine.encrypt(plaintext).to_lowercase(), ciphertext);
+    assert_eq!(plaintext.to_lowercase(), lowercase.decrypt(&ciphertext));
+    assert_eq!(plaintext.to_uppercase(), machine.decrypt(&ciphertext));
+
+    Ok(())
+}
+
+#[test]
+fn random_machines() {
+    use rand::SeedableRng as _;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(1939);
+    let plaintext = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG";
+    for _ in 0..100 {
+        let machine = EnigmaMachine::random(&mut rng);
+        assert!(machine.validate().is_empty());
+
+        let ciphertext = machine.encrypt(plaintext);
+        assert_eq!(plaintext, machine.encrypt(&ciphertext));
+        assert!(plaintext.chars().zip(ciphertext.chars()).all(|(plain, cipher)| plain == ' ' || plain != cipher));
+    }
+}
+
+#[test]
+fn safe_matches_unchecked() -> EnigmaResult<()> {
+    use rand::{seq::SliceRandom as _, Rng as _, SeedableRng as _};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(1945);
+    for _ in 0..2000 {
+        let mut rotors = (1..=8).collect::<Vec<u8>>();
+        rotors.shuffle(&mut rng);
+        let reflector = *["A", "B", "C"].choose(&mut rng).unwrap();
+        let ring_settings = (rng.gen_range(1..=26), rng.gen_range(1..=26), rng.gen_range(1..=26));
+        let ring_positions = (rng.gen_range(1..=26), rng.gen_range(1..=26), rng.gen_range(1..=26));
+
+        let mut letters = ('A'..='Z').collect::<Vec<_>>();
+        letters.shuffle(&mut rng);
+        let pairs = rng.gen_range(0..=13);
+        let plugboard = letters.chunks(2).take(pairs).map(|pair| pair.iter().collect::<String>()).collect::<Vec<_>>().join(" ");
+
+        let message = (0..rng.gen_range(1..=200))
+            .map(|_| if rng.gen_ratio(1, 6) { ' ' } else { rng.gen_range('A'..='Z') })
+            .collect::<String>();
+
+        let machine = EnigmaMachine::new()
+            .rotors(rotors[0], rotors[1], rotors[2])
+            .reflector(reflector)
+            .ring_settings(ring_settings.0, ring_settings.1, ring_settings.2)
+            .ring_positions(ring_positions.0, ring_positions.1, ring_positions.2)
+            .plugboard(&plugboard)?;
+
+        let unchecked_machine = EnigmaMachine::unchecked()
+            .rotors(rotors[0], rotors[1], rotors[2])
+            .reflector(reflector)
+            .ring_settings(ring_settings.0, ring_settings.1, ring_settings.2)
+            .ring_positions(ring_positions.0, ring_positions.1, ring_positions.2)
+            .plugboard(&plugboard)
+            .build();
+
+        assert_eq!(
+            machine.decrypt(&message),
+            unsafe { unchecked_machine.decrypt_unchecked(&message) },
+            "Safe and unchecked machines differ with rotors {:?}, reflector {reflector}, ring settings {ring_settings:?}, ring positions {ring_positions:?}, plugboard {plugboard:?}",
+            &rotors[..3]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rotor_sets() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("UKWR")
+        .ring_settings(1, 1, 1)
+        .ring_positions(1, 1, 1)
+        .plugboard("")?;
+    let railway = machine.with_rotor_set(RotorSet::Railway)?;
+    let wehrmacht = machine.with_rotor_set(RotorSet::WehrmachtI)?;
+
+    let plaintext = "AAAAAAAAAAAAAAAAAAAAAAAAA";
+    assert_eq!(machine.encrypt(plaintext), wehrmacht.encrypt(plaintext));
+    assert_ne!(machine.encrypt(plaintext), railway.encrypt(plaintext));
+    assert_eq!(plaintext, railway.decrypt(&railway.encrypt(plaintext)));
+    assert!(plaintext.chars().zip(railway.encrypt(plaintext).chars()).all(|(plain, cipher)| plain != cipher));
+
+    // Each letter of the Railway Enigma's entry wheel is connected to the next contact of the rotors
+    let (_, trace) = railway.encrypt_traced("Q");
+    let (_, identity_trace) = machine.encrypt_traced("A");
+    assert_ne!(trace[0].third_rotor, identity_trace[0].third_rotor);
+
+    // The UKW-R reflector fits the Railway Enigma only
+    assert!(railway.validate().iter().all(|warning| !matches!(warning, SettingsWarning::ReflectorFromOtherModel(_))));
+    assert!(wehrmacht.validate().contains(&SettingsWarning::ReflectorFromOtherModel(Reflector::Ukwr)));
+
+    let naval = EnigmaMachine::new().rotors(6, 7, 8)?;
+    assert!(naval.with_rotor_set(RotorSet::WehrmachtI).is_err());
+    assert!(naval.with_rotor_set(RotorSet::Railway).is_err());
+    assert!(naval.with_rotor_set(RotorSet::KriegsmarineM4).is_ok());
+
+    // Rotors that aren't in the machine's rotor set can't be swapped in afterwards
+    let railway = || {
+        EnigmaMachine::new()
+            .rotors(1, 2, 3)
+            .reflector("B")
+            .and_then(|machine| machine.with_rotor_set(RotorSet::Railway))
+    };
+    assert!(railway().rotors(4, 5, 6).is_err());
+    assert!(railway().rotors_roman("I II IV").is_err());
+    assert!(railway().custom_rotors(Rotor::I, Rotor::V, Rotor::II).is_err());
+    assert!(railway().rotors(3, 1, 2).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn decrypt_many() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .plugboard("BY EW FZ GI QM RV UX")?;
+
+    let messages = ["KDZVKMNTYQJPHFXI", "TOPSECRETMESSAGE", "Attack at dawn!", "", "WETTERVORHERSAGEBISKAYA"].repeat(10);
+
+    let expected = messages.iter().map(|message| machine.decrypt(message)).collect::<Vec<_>>();
+    assert_eq!(expected, machine.decrypt_many(&messages));
+    assert!(machine.decrypt_many(&[]).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn decrypt_scored() -> EnigmaResult<()> {
+    let machine = || {
+        EnigmaMachine::new()
+            .rotors(1, 2, 3)
+            .reflector("B")
+            .ring_settings(10, 12, 14)
+            .plugboard("BY EW FZ GI QM RV UX")
+    };
+    let correct = machine().ring_positions(5, 22, 3)?;
+    let wrong = machine().ring_positions(6, 1, 17)?;
+
+    let plaintext = "THE MEETING WITH THE GENERAL HAS BEEN MOVED TO THURSDAY MORNING AT THE OLD FARMHOUSE NEAR THE RIVER";
+    let ciphertext = correct.encrypt(plaintext);
+
+    let (decrypted, score) = correct.decrypt_scored(&ciphertext);
+    assert_eq!(plaintext, decrypted);
+    assert_eq!(correct.decrypt(&ciphertext), decrypted);
+    assert!(score > 0.7, "Expected the correct settings to score highly, got {score}");
+
+    let (decrypted, wrong_score) = wrong.decrypt_scored(&ciphertext);
+    assert_ne!(plaintext, decrypted);
+    assert!(wrong_score < 0.4, "Expected the wrong settings to score poorly, got {wrong_score}");
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_size() {
+    // 60 rotor orders, 26^3 positions and ring settings each, and 150,738,274,937,250 plugboards with 10 pairs
+    assert_eq!(2_793_925_870_508_516_103_360_000, EnigmaMachine::keyspace_size(5, 10));
+    assert_eq!(60 * 26u128.pow(6), EnigmaMachine::keyspace_size(5, 0));
+    assert_eq!(EnigmaMachine::keyspace_size(5, 10) / 60 * 336, EnigmaMachine::keyspace_size(8, 10));
+    assert!(EnigmaMachine::keyspace_size(8, 13) > 0);
+    assert_eq!(0, EnigmaMachine::keyspace_size(2, 10));
+    assert_eq!(0, EnigmaMachine::keyspace_size(5, 14));
+}
+
+#[test]
+fn uhr() -> EnigmaResult<()> {
+    let machine = || {
+        EnigmaMachine::new()
+            .rotors(1, 2, 3)
+            .reflector("B")
+            .ring_settings(10, 12, 14)
+            .ring_positions(5, 22, 3)
+            .plugboard("AV BS CG DL FU HZ IN KM OW RX")
+    };
+    let plaintext = "THEUHRCHANGESTHEPLUGBOARDEVERYPOSITION";
+
+    let plain = machine()?.encrypt(plaintext);
+    let ciphertexts = (0..40)
+        .map(|position| machine().uhr(position).map(|machine| machine.encrypt(plaintext)))
+        .collect::<EnigmaResult<Vec<_>>>()?;
+    for (position, ciphertext) in ciphertexts.iter().enumerate().skip(1) {
+        assert_ne!(plain, *ciphertext, "Expected the Uhr at position {position} to change the ciphertext");
+        assert_eq!(plaintext, machine().uhr(position as u8)?.decrypt(ciphertext));
+    }
+    assert_ne!(ciphertexts[0], ciphertexts[1]);
+
+    // At 00, the Uhr connects the letters of each pair like the plain plugboard, so it only changes the ciphertext at the
+    // other positions
+    assert_eq!(plain, ciphertexts[0]);
+
+    // The order of the pairs decides which plug each letter is on
+    assert_ne!(ciphertexts[7], machine().plugboard("VA BS CG DL FU HZ IN KM OW RX").uhr(7)?.encrypt(plaintext));
+
+    assert!(machine().uhr(40).is_err());
+    assert!(machine().plugboard("AV BS CG").uhr(0).is_err());
+    assert!(machine().uhr(0).plugboard("AV BS CG").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn decrypt_with_indicator() -> EnigmaResult<()> {
+    let ground_setting = EnigmaMachine::new()
+        .rotors(2, 4, 5)
+        .reflector("B")
+        .ring_settings(2, 21, 12)
+        .ring_positions(1, 2, 3)
+        .plugboard("AV BS CG DL FU HZ IN KM OW RX")?;
+    let message_key = EnigmaMachine::new()
+        .rotors(2, 4, 5)
+        .reflector("B")
+        .ring_settings(2, 21, 12)
+        .ring_positions(16, 4, 23)
+        .plugboard("AV BS CG DL FU HZ IN KM OW RX")?;
+
+    let plaintext = "ANGRIFFUMZWEIUHRNACHTS";
+    let body = message_key.encrypt(plaintext);
+
+    let double_indicator = ground_setting.encrypt("PDWPDW");
+    let message = format!("{} {}", double_indicator, body);
+    assert_eq!(plaintext, ground_setting.decrypt_with_indicator(&message, 6)?);
+
+    let single_indicator = ground_setting.encrypt("PDW");
+    assert_eq!(plaintext, ground_setting.decrypt_with_indicator(&format!("{single_indicator}{body}"), 3)?);
+
+    let garbled = ground_setting.encrypt("PDWPDX");
+    assert!(ground_setting.decrypt_with_indicator(&format!("{garbled} {body}"), 6).is_err());
+    assert!(ground_setting.decrypt_with_indicator(&message, 4).is_err());
+    assert!(ground_setting.decrypt_with_indicator("ABC", 6).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn key_sheet() -> EnigmaResult<()> {
+    // The Operation Barbarossa message of 7 July 1941, with its message key "BLA" as the starting positions.
+    let machine = EnigmaMachine::from_key_sheet("Rotors: II IV V, Rings: 02 21 12, Reflector: B, Plugboard: AV BS CG DL FU HZ IN KM OW RX, Positions: B L A")?;
+    assert_eq!("AUFKLXABTEILUNGXVONXKURTINOWAX", machine.decrypt("EDPUDNRGYSZRCXNUYTPOMRMBOFKTBZ"));
+
+    let machine = EnigmaMachine::from_key_sheet("Rotors: III I II, Rings: 01 17 12, Reflector: B, Plugboard: AV BS CG DL FU HZ IN KM OW RX")?;
+    let expected = EnigmaMachine::new()
+        .rotors(3, 1, 2)
+        .ring_settings(1, 17, 12)
+        .ring_positions(1, 1, 1)
+        .reflector("B")
+        .plugboard("AV BS CG DL FU HZ IN KM OW RX")?;
+    assert_eq!(expected.encrypt("TOPSECRETMESSAGE"), machine.encrypt("TOPSECRETMESSAGE"));
+
+    assert!(EnigmaMachine::from_key_sheet("Rotors: III I IX, Rings: 01 17 12, Reflector: B, Plugboard: AV BS").is_err());
+    assert!(EnigmaMachine::from_key_sheet("Rotors: III I II, Rings: 01 17 27, Reflector: B, Plugboard: AV BS").is_err());
+    assert!(EnigmaMachine::from_key_sheet("Rotors: III I II, Reflector: B, Plugboard: AV BS").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn rotors_roman() -> EnigmaResult<()> {
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+    for (numerals, (first, second, third)) in [("I II III", (1, 2, 3)), ("iii i ii", (3, 1, 2)), ("VIII  VI\tIV", (8, 6, 4))] {
+        let roman = EnigmaMachine::new().rotors_roman(numerals).reflector("B").ring_positions(1, 4, 21)?;
+        let numbered = EnigmaMachine::new().rotors(first, second, third).reflector("B").ring_positions(1, 4, 21)?;
+        assert_eq!(numbered.encrypt(text), roman.encrypt(text));
+    }
+
+    assert!(EnigmaMachine::new().rotors_roman("I II").is_err());
+    assert!(EnigmaMachine::new().rotors_roman("I II III IV").is_err());
+    assert!(EnigmaMachine::new().rotors_roman("I II IX").is_err());
+    assert!(EnigmaMachine::new().rotors_roman("1 2 3").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn invalid_plugboards() {
+    let machine = |plugboard: &str| EnigmaMachine::new().rotors(1, 2, 3).reflector("B").plugboard(plugboard);
+
+    assert!(machine("BY EW FZ").is_ok());
+    assert!(machine("BY AA FZ").is_err());
+    assert!(machine("BY ABC FZ").is_err());
+    assert!(machine("BY A FZ").is_err());
+    assert!(machine("BY B1 FZ").is_err());
+    assert!(machine("BY YF").is_err());
+}
+
+#[test]
+fn alphabet_conversion() -> EnigmaResult<()> {
+    let alphabet = cipher_utils::alphabet::Alphabet::caseless("qwertyuiopasdfghjklzxcvbnm")?;
+    let enigma_alphabet = enigma_simulator::alphabet::Alphabet::try_from(&alphabet)?;
+    assert_eq!("QWERTYUIOPASDFGHJKLZXCVBNM", enigma_alphabet.letters());
+
+    let converted_back = cipher_utils::alphabet::Alphabet::try_from(&enigma_alphabet)?;
+    assert_eq!(alphabet, converted_back);
+
+    // Alphabets that aren't A-Z in some order can't be converted without losing letters
+    let accented = cipher_utils::alphabet::Alphabet::cased("ABCDEFGHIJKLMNOPQRSTUVWXYÉ")?;
+    assert!(enigma_simulator::alphabet::Alphabet::try_from(&accented).is_err());
+    assert!(enigma_simulator::alphabet::Alphabet::try_from(&cipher_utils::alphabet::Alphabet::without_j()?).is_err());
+    let mixed_case = cipher_utils::alphabet::Alphabet::cased("ABCDEFGHIJKLMNOPQRSTUVWXYa")?;
+    assert!(enigma_simulator::alphabet::Alphabet::try_from(&mixed_case).is_err());
+
+    let unchecked = enigma_simulator::alphabet::Alphabet::new_unchecked("ABC");
+    assert!(cipher_utils::alphabet::Alphabet::try_from(&unchecked).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn with_reflector() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").ring_settings(10, 12, 14).ring_positions(5, 22, 3)?;
+    let plaintext = "ATTACKATDAWN";
+    let ciphertext = machine.encrypt(plaintext);
+
+    let swapped = machine.with_reflector(Reflector::C);
+    assert_ne!(ciphertext, swapped.encrypt(plaintext));
+    assert_eq!(plaintext, swapped.decrypt(&swapped.encrypt(plaintext)));
+
+    let expected = EnigmaMachine::new().rotors(1, 2, 3).reflector("C").ring_settings(10, 12, 14).ring_positions(5, 22, 3)?;
+    assert_eq!(expected.encrypt(plaintext), swapped.encrypt(plaintext));
+    assert_eq!(ciphertext, swapped.with_reflector(Reflector::B).encrypt(plaintext));
+
+    Ok(())
+}
+
+#[test]
+fn encrypt_range() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new()
+        .rotors(2, 4, 5)
+        .reflector("B")
+        .ring_settings(2, 21, 12)
+        .plugboard("AV BS CG DL FU HZ IN KM OW RX")?;
+    let text = "Attack at dawn, hold the bridge until relieved.";
+    let ciphertext = machine.encrypt(text);
+
+    assert_eq!(ciphertext.chars().skip(5).take(5).collect::<String>(), machine.encrypt_range(text, 5..10));
+    assert_eq!(ciphertext, machine.encrypt_range(text, 0..text.len()));
+    assert_eq!(ciphertext.chars().skip(30).collect::<String>(), machine.encrypt_range(text, 30..1000));
+    assert_eq!("", machine.encrypt_range(text, 10..10));
+
+    // Transliteration only applies to `encrypt()`, so the range matches the untransliterated encryption
+    let machine = machine.with_transliteration(Transliteration::german_naval());
+    let text = "Attack at 0600, hold the bridge until 1800.";
+    let (ciphertext, _) = machine.encrypt_traced(text);
+    assert_ne!(machine.encrypt(text), ciphertext);
+    assert_eq!(ciphertext.chars().skip(12).take(20).collect::<String>(), machine.encrypt_range(text, 12..32));
+    assert_eq!(ciphertext, machine.encrypt_range(text, 0..text.len()));
+
+    // The range is in characters of the given text, and letters before it that expand when uppercased step the rotors
+    // once per letter of the expansion
+    let text = "straße xyz";
+    let (ciphertext, _) = machine.encrypt_traced(text);
+    assert_eq!(ciphertext.chars().skip(8).collect::<String>(), machine.encrypt_range(text, 7..10));
+    assert_eq!(ciphertext, machine.encrypt_range(text, 0..10));
+
+    Ok(())
+}
+
+#[test]
+fn stateful_enigma() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").ring_settings(1, 1, 1).ring_positions(1, 1, 1)?;
+    let expected = machine.decrypt("HELLO");
+
+    let mut stateful = StatefulEnigma::new(machine);
+    let output = stateful.feed("HEL") + &stateful.feed("LO");
+    assert_eq!(expected, output);
+
+    // Punctuation doesn't advance the rotors
+    stateful.reset();
+    assert_eq!(expected[..2].to_owned() + ", " + &expected[2..], stateful.feed("HE, ") + &stateful.feed("LLO"));
+
+    // Stepping across the double step of the middle rotor
+    let machine = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").ring_positions(1, 4, 21)?;
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+    let expected = machine.decrypt(text);
+    let mut stateful = StatefulEnigma::from(machine.clone());
+    assert_eq!(expected, text.chars().map(|letter| stateful.feed(&letter.to_string())).collect::<String>());
+
+    // Letters that expand when uppercased step the rotors once per letter of the expansion
+    let mut stateful = StatefulEnigma::new(machine.clone());
+    assert_eq!(machine.decrypt("straße xyz"), stateful.feed("straße") + &stateful.feed(" xyz"));
+
+    Ok(())
+}
+
+/// Ring settings ("Ringstellung") rotate a rotor's wiring relative to its lettered ring, while ring positions
+/// ("Grundstellung") are the letters showing in the machine's windows when a message starts. Both are 1-based here, i.e.,
+/// a ring setting of 1 is "A" or "01". The source of each vector is noted above it; Vectors marked as regression values
+/// were produced by this crate, and only guard against changes in its output.
+#[test]
+fn ring_settings() -> EnigmaResult<()> {
+    let machine = |ring_settings: (u8, u8, u8)| {
+        EnigmaMachine::new()
+            .rotors(1, 2, 3)
+            .reflector("B")
+            .ring_settings(ring_settings.0, ring_settings.1, ring_settings.2)
+            .ring_positions(1, 1, 1)
+    };
+
+    // Rotors I II III with rings AAA and BBB, starting at AAA. Source: the "Rotor offset" section of Wikipedia's
+    // "Enigma rotor details" article.
+    assert_eq!("BDZGO", machine((1, 1, 1))?.encrypt("AAAAA"));
+    assert_eq!("EWTYX", machine((2, 2, 2))?.encrypt("AAAAA"));
+
+    // Only the leftmost ring moved, which only matters once the leftmost rotor's wiring is reached. Regression values.
+    assert_eq!("ZOMWLSETTFSGUGKNIBWTSTRZD", machine((2, 1, 1))?.encrypt("AAAAAAAAAAAAAAAAAAAAAAAAA"));
+    assert_eq!("VNSOUFIXEO", machine((2, 1, 1))?.encrypt("HELLOWORLD"));
+
+    // The start of the first part of the Operation Barbarossa message from 1941, with rings 02 21 12 and
+    // positions BLA, the decrypted message key. Source: Dirk Rijmenants, "Enigma Message Procedures", Cipher Machines
+    // and Cryptology.
+    let machine = EnigmaMachine::new()
+        .rotors(2, 4, 5)
+        .reflector("B")
+        .ring_settings(2, 21, 12)
+        .ring_positions(2, 12, 1)
+        .plugboard("AV BS CG DL FU HZ IN KM OW RX")?;
+    assert_eq!("AUFKLXABTEILUNGXVONXKURTINOWAX", machine.decrypt("EDPUDNRGYSZRCXNUYTPOMRMBOFKTBZ"));
+
+    // Unrelated rings and positions, with a double step of the middle rotor. Regression values.
+    let machine = EnigmaMachine::new()
+        .rotors(3, 1, 2)
+        .reflector("B")
+        .ring_settings(5, 17, 3)
+        .ring_positions(17, 5, 21)
+        .plugboard("AB CD EF")?;
+    assert_eq!("QAYVGGZQYDACHCDONVTDRSHIEEJPPMBHSTD", machine.encrypt("THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG"));
+
+    Ok(())
+}
+
+#[test]
+fn preserve_layout() -> EnigmaResult<()> {
+    let plaintext = "Dear Sir,\n\n  The attack begins at dawn; hold the bridge!\n(Burn this — no copies.)\n";
+    let machine = || {
+        EnigmaMachine::new()
+            .rotors(1, 2, 3)
+            .reflector("B")
+            .ring_settings(10, 12, 14)
+            .ring_positions(5, 22, 3)
+            .plugboard("BY EW FZ GI QM RV UX")
+    };
+
+    let ciphertext = machine().preserve_layout()?.encrypt(plaintext);
+    assert_eq!(plaintext.chars().count(), ciphertext.chars().count());
+    for (plain, cipher) in plaintext.chars().zip(ciphertext.chars()) {
+        if plain.is_ascii_alphabetic() {
+            assert_ne!(plain, cipher);
+            assert_eq!(plain.is_ascii_lowercase(), cipher.is_ascii_lowercase());
+        } else {
+            assert_eq!(plain, cipher);
+        }
+    }
+
+    // The letters are encrypted as one message, just as without layout preservation
+    let letters = |text: &str| text.chars().filter(char::is_ascii_alphabetic).collect::<String>().to_uppercase();
+    assert_eq!(machine()?.encrypt(&letters(plaintext)), letters(&ciphertext));
+    assert_eq!(plaintext, machine().preserve_layout()?.decrypt(&ciphertext));
+
+    Ok(())
+}
+
+#[test]
+fn fourth_rotor() -> EnigmaResult<()> {
+    let plaintext = "The fourth rotor sits to the left of the other three, and never moves while the message is typed.";
+
+    // With the Greek rotors at position A, the M4's thin reflectors act like the M3's ordinary ones
+    for (greek, thin, thick) in [("LEYJVCNIXWPBQMDRTAKZGFUHOS", "BThin", "B"), ("FSOKANUERHMBTIYCWLQPZXVGJD", "CThin", "C")] {
+        let m3 = EnigmaMachine::new()
+            .rotors(2, 4, 8)
+            .reflector(thick)
+            .ring_settings(3, 14, 25)
+            .ring_positions(7, 19, 1)
+            .plugboard("AT BL DF GJ HM NW OP QY RZ VX")?;
+        let m4 = EnigmaMachine::new()
+            .rotors(2, 4, 8)
+            .fourth_rotor(Rotor::custom(greek, &[])?, 1, 1)
+            .reflector(thin)
+            .ring_settings(3, 14, 25)
+            .ring_positions(7, 19, 1)
+            .plugboard("AT BL DF GJ HM NW OP QY RZ VX")?;
+        assert_eq!(m3.encrypt(plaintext), m4.encrypt(plaintext));
+        assert_eq!(plaintext.to_uppercase(), m4.decrypt(&m4.encrypt(plaintext)));
+        assert!(m4.validate().is_empty());
+        assert!(m4
+            .encrypt_traced("A")
+            .1
+            .iter()
+            .all(|trace| trace.fourth_rotor.is_some() && trace.fourth_rotor_back.is_some()));
+    }
+
+    // A fourth rotor changes the encryption otherwise
+    let m3 = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").ring_positions(1, 1, 1)?;
+    let m4 = EnigmaMachine::new().rotors(1, 2, 3).fourth_rotor(Rotor::IV, 1, 1).reflector("B").ring_positions(1, 1, 1)?;
+    assert_ne!(m3.encrypt(plaintext), m4.encrypt(plaintext));
+    assert!(m4.with_rotor_set(RotorSet::Railway).is_err());
+
+    // The Greek rotor's position changes the encryption, and only its position relative to its ring setting matters
+    let m4 = |ring_setting, position| {
+        EnigmaMachine::new()
+            .rotors(2, 4, 8)
+            .fourth_rotor(Rotor::Beta, ring_setting, position)
+            .reflector("BThin")
+            .ring_settings(3, 14, 25)
+            .ring_positions(7, 19, 1)
+            .plugboard("AT BL DF GJ HM NW OP QY RZ VX")
+    };
+    let ciphertext = m4(1, 13)?.encrypt(plaintext);
+    assert_ne!(m4(1, 1)?.encrypt(plaintext), ciphertext);
+    assert_ne!(m4(1, 14)?.encrypt(plaintext), ciphertext);
+    assert_eq!(plaintext.to_uppercase(), m4(1, 13)?.decrypt(&ciphertext));
+    assert_eq!(ciphertext, m4(6, 18)?.encrypt(plaintext));
+    assert_eq!(m4(1, 1)?.encrypt(plaintext), m4(20, 20)?.encrypt(plaintext));
+
+    // The Greek rotor's letter at a position that isn't `A`
+    let trace = m4(1, 2)?.encrypt_traced("A").1[0];
+    let wiring = Rotor::Beta.alphabet().letters().chars().collect::<Vec<_>>();
+    let shifted = |letter: char, shift: i32| (((letter as i32 - 'A' as i32 + shift).rem_euclid(26)) as u8 + b'A') as char;
+    let index = (trace.first_rotor as u8 - b'A' + 1) as usize % 26;
+    assert_eq!(Some(shifted(wiring[index], -1)), trace.fourth_rotor);
+
+    assert!(m4(0, 1).is_err());
+    assert!(m4(1, 27).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn custom_rotor() -> EnigmaResult<()> {
+    let text = "Rotors with custom wirings can be used for teaching.";
+
+    // Three custom rotors with the wirings and notches of rotors I, II, and III are the same as the built-in rotors
+    let builtin = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").ring_settings(4, 9, 17).ring_positions(1, 4, 21)?;
+    let custom = EnigmaMachine::new()
+        .custom_rotor("EKMFLGDQVZNTOWYHXUSPAIBRCJ", &['Q'])
+        .custom_rotor("ajdksiruxblhwtmcqgznpyfvoe", &['e'])
+        .custom_rotor("BDFHJLCPRTXVZNYEIWGAKMUSQO", &['V'])
+        .reflector("B")
+        .ring_settings(4, 9, 17)
+        .ring_positions(1, 4, 21)?;
+    assert_eq!(builtin.encrypt(text), custom.encrypt(text));
+
+    // A single custom rotor replaces the rightmost rotor, and moves the others to the left
+    let builtin = EnigmaMachine::new().rotors(2, 3, 5).reflector("B").ring_positions(3, 7, 11)?;
+    let custom = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .custom_rotor("VZBRGITYUPSDNHLXAWMJQOFECK", &['Z'])
+        .reflector("B")
+        .ring_positions(3, 7, 11)?;
+    assert_eq!(builtin.encrypt(text), custom.encrypt(text));
+
+    // Wirings that aren't a permutation of the alphabet
+    assert!(EnigmaMachine::new().custom_rotor("ABC", &['A']).is_err());
+    assert!(EnigmaMachine::new().custom_rotor("AACDEFGHIJKLMNOPQRSTUVWXYZ", &['A']).is_err());
+    assert!(EnigmaMachine::new().custom_rotor("ABCDEFGHIJKLMNOPQRSTUVWXY1", &['A']).is_err());
+
+    // Notches that aren't letters
+    assert!(EnigmaMachine::new().custom_rotor("EKMFLGDQVZNTOWYHXUSPAIBRCJ", &['1']).is_err());
+    assert!(EnigmaMachine::new().custom_rotor("EKMFLGDQVZNTOWYHXUSPAIBRCJ", &['Q', 'Ä']).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn greek_rotors() -> EnigmaResult<()> {
+    assert_eq!(Rotor::Beta, Rotor::try_from(9)?);
+    assert_eq!(Rotor::Gamma, Rotor::try_from(10)?);
+    assert!(Rotor::try_from(11).is_err());
+    assert_eq!(10, Rotor::Gamma.number());
+    assert!(Rotor::Beta.notches().is_empty());
+    assert!(Rotor::Gamma.notches().is_empty());
+    assert_eq!("LEYJVCNIXWPBQMDRTAKZGFUHOS", Rotor::Beta.alphabet().letters());
+    assert_eq!("FSOKANUERHMBTIYCWLQPZXVGJD", Rotor::Gamma.alphabet().letters());
+
+    assert!(RotorSet::KriegsmarineM4.contains(Rotor::Beta));
+    assert!(!RotorSet::WehrmachtI.contains(Rotor::Gamma));
+    assert!(EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .fourth_rotor(Rotor::Beta, 1, 1)
+        .reflector("BThin")
+        .ring_positions(1, 1, 1)?
+        .with_rotor_set(RotorSet::WehrmachtI)
+        .is_err());
+
+    // The built-in Greek rotors are wired the same as the equivalent custom ones
+    let plaintext = "Beta and Gamma were the two Greek rotors of the M4.";
+    for (rotor, wiring) in [(Rotor::Beta, "LEYJVCNIXWPBQMDRTAKZGFUHOS"), (Rotor::Gamma, "FSOKANUERHMBTIYCWLQPZXVGJD")] {
+        let builtin = EnigmaMachine::new()
+            .rotors(5, 1, 7)
+            .fourth_rotor(rotor, 4, 11)
+            .reflector("CThin")
+            .ring_positions(13, 2, 24)?;
+        let custom = EnigmaMachine::new()
+            .rotors(5, 1, 7)
+            .fourth_rotor(Rotor::custom(wiring, &[])?, 4, 11)
+            .reflector("CThin")
+            .ring_positions(13, 2, 24)?;
+        assert_eq!(custom.encrypt(plaintext), builtin.encrypt(plaintext));
+    }
+
+    // Greek rotors can also be used in the three ordinary slots
+    let machine = EnigmaMachine::new().rotors(9, 1, 2).reflector("B").ring_positions(1, 5, 5)?;
+    assert_eq!(plaintext.to_uppercase(), machine.decrypt(&machine.encrypt(plaintext)));
+
+    Ok(())
+}
+
+#[test]
+fn plugboard_pairs() -> EnigmaResult<()> {
+    let machine = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").plugboard("YA QB ER")?;
+    assert_eq!(vec![('A', 'Y'), ('B', 'Q'), ('E', 'R')], machine.plugboard_pairs());
+
+    // Reading the plugboard back and setting it again gives the same machine
+    let plugboard = machine
+        .plugboard_pairs()
+        .iter()
+        .map(|(first, second)| format!("{first}{second}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!("AY BQ ER", plugboard);
+    let rebuilt = EnigmaMachine::new().rotors(1, 2, 3).reflector("B").plugboard(&plugboard)?;
+    assert_eq!(machine.plugboard_pairs(), rebuilt.plugboard_pairs());
+    assert_eq!(machine.encrypt("HELLOWORLD"), rebuilt.encrypt("HELLOWORLD"));
+
+    assert!(EnigmaMachine::new().rotors(1, 2, 3).reflector("B").plugboard("")?.plugboard_pairs().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn custom_alphabet() -> EnigmaResult<()> {
+    let cyrillic = "АБВГДЕЖЗИЙКЛМНОПРСТУФХЦЧШЩ";
+    let relabel = |text: &str, from: &str, to: &str| {
+        text.chars()
+            .map(|character| {
+                from.chars()
+                    .position(|letter| letter == character)
+                    .map_or(character, |index| to.chars().nth(index).unwrap())
+            })
+            .collect::<String>()
+    };
+    let standard = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .plugboard("BY EW FZ GI QM RV UX")?;
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .alphabet(cyrillic)
+        .plugboard(&relabel("BY EW FZ GI QM RV UX", "ABCDEFGHIJKLMNOPQRSTUVWXYZ", cyrillic))?;
+
+    // Reciprocity holds, and no letter is encrypted into itself
+    let plaintext = "ПРИВЕТ МИР, ВСТРЕЧАЕМСЯ В ДЕВЯТЬ";
+    let ciphertext = machine.encrypt(plaintext);
+    assert_eq!(plaintext, machine.decrypt(&ciphertext));
+    assert!(plaintext
+        .chars()
+        .zip(ciphertext.chars())
+        .all(|(letter, encrypted)| letter != encrypted || !cyrillic.contains(letter)));
+
+    // The machine encrypts exactly like the standard machine, up to relabelling its letters
+    assert_eq!(
+        relabel(
+            &standard.encrypt(&relabel(plaintext, cyrillic, "ABCDEFGHIJKLMNOPQRSTUVWXYZ")),
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            cyrillic
+        ),
+        ciphertext
+    );
+    assert_eq!(ciphertext, machine.encrypt(&plaintext.to_lowercase()));
+
+    assert!(EnigmaMachine::new().alphabet("ABC").is_err());
+    assert!(EnigmaMachine::new().alphabet("АБВГДЕЖЗИЙКЛМНОПРСТУФХЦЧША").is_err());
+    assert!(EnigmaMachine::new().plugboard("AB").alphabet(cyrillic).is_err());
+    assert!(EnigmaMachine::new().alphabet(cyrillic).plugboard("AB").is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "morse-code")]
+fn enigma_then_morse() -> EnigmaResult<()> {
+    use cipher_utils::cipher::check_cipher;
+
+    let machine = EnigmaMachine::new()
+        .rotors(1, 2, 3)
+        .reflector("B")
+        .ring_settings(10, 12, 14)
+        .ring_positions(5, 22, 3)
+        .plugboard("BY EW FZ GI QM RV UX")?;
+
+    let morse = enigma_simulator::enigma_then_morse(&machine, "TOPSECRETMESSAGE");
+    assert_eq!("-.- -.. --.. ...- -.- -- -. - -.-- --.- .--- .--. .... ..-. -..- ..", morse);
+    assert_eq!("TOPSECRETMESSAGE", enigma_simulator::morse_then_enigma(&machine, &morse));
+
+    let plaintext = "Attack at dawn, hold the bridge.";
+    let morse = enigma_simulator::enigma_then_morse(&machine, plaintext);
+    assert!(morse.chars().all(|character| ".-/ ".contains(character)));
+    assert_eq!(plaintext.to_uppercase(), enigma_simulator::morse_then_enigma(&machine, &morse));
+
+    check_cipher(&machine, "TOPSECRETMESSAGE")?;
+    check_cipher(&machine.with_transliteration(Transliteration::german_naval()), "MEET AT 0600")?;
+
+    Ok(())
+}
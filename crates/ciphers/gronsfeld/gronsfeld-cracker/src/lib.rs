@@ -1,6 +1,6 @@
-use std::io::Write;
+use std::{cmp::Reverse, collections::BinaryHeap, io::Write};
 
-use cipher_utils::{score::PossiblePlaintext, Analyze};
+use cipher_utils::progress::ProgressTracker;
 use colored::Colorize;
 use gronsfeld::{Gronsfeld, GronsfeldBuilder};
 use itertools::Itertools;
@@ -8,11 +8,14 @@ use itertools::Itertools;
 #[derive(Default)]
 pub struct GronsfeldCracker {
     alphabet: Option<String>,
-    key: Option<u128>,
     key_digits: Option<Vec<u128>>,
 }
 
 impl GronsfeldCracker {
+    /// The englishness above which a decryption is considered good quality, and longer keys aren't tried. See
+    /// `cipher_utils::englishness()`.
+    const PLAINTEXT_ENGLISHNESS: f64 = 0.85;
+
     pub fn new() -> Self {
         Self::default()
     }
@@ -29,11 +32,10 @@ impl GronsfeldCracker {
                     "Decrypting".bold().green(),
                     "Gronsfeld cipher".bold().cyan()
                 );
-                let total = key_digits.iter().permutations(key_digits.len()).unique().count();
-                let mut iteration = 0;
+                let mut progress = ProgressTracker::new(key_digits.iter().permutations(key_digits.len()).unique().count());
 
                 for permutation in key_digits.iter().permutations(key_digits.len()).unique() {
-                    iteration += 1;
+                    progress.advance();
 
                     let key = permutation.iter().map(|digit| digit.to_string()).collect::<String>();
                     let gronsfeld = Gronsfeld::new().alphabet(alphabet).key_str(&key).build().unwrap();
@@ -45,24 +47,24 @@ impl GronsfeldCracker {
                     println!(
                         "{} key permutations... {}",
                         "Brute forcing".bold().green(),
-                        if iteration == total {
-                            format!("{:.2}%", (100. * iteration as f64 / total as f64)).bold().green()
+                        if progress.is_complete() {
+                            progress.to_string().bold().green()
                         } else {
-                            format!("{:.2}%", (100. * iteration as f64 / total as f64)).bold().yellow()
+                            progress.to_string().bold().yellow()
                         }
                     );
                     std::io::stdout().flush()?;
                 }
 
                 println!("{} best plaintext...", "Finding".bold().green());
-                let best = plaintexts
+                let (_, key, plaintext) = plaintexts
                     .into_iter()
-                    .sorted_by(|first, other| PossiblePlaintext::new(&first.1).cmp(&PossiblePlaintext::new(&other.1)))
-                    .next_back()
+                    .map(|(key, plaintext)| (cipher_utils::englishness(&plaintext), key, plaintext))
+                    .max_by(|first, other| first.0.total_cmp(&other.0))
                     .unwrap();
 
-                println!("{} permutation: {}\n", "Best key".green().bold(), best.0.cyan().bold());
-                return Ok(best.1);
+                println!("{} permutation: {}\n", "Best key".green().bold(), key.cyan().bold());
+                return Ok(plaintext);
             }
 
             // No key digits known
@@ -75,11 +77,10 @@ impl GronsfeldCracker {
             loop {
                 let mut plaintexts: Vec<(String, String)> = Vec::new();
                 println!("\t{} all keys with {} digits...\n", "Checking".bold().green(), key_digits.to_string().bold().cyan());
-                let total = (0..10).permutations(key_digits as usize).unique().count();
-                let mut iteration = 0;
+                let mut progress = ProgressTracker::new((0..10).permutations(key_digits as usize).unique().count());
 
                 for permutation in (0..10).permutations(key_digits as usize).unique() {
-                    iteration += 1;
+                    progress.advance();
 
                     let key = permutation.iter().map(|digit| digit.to_string()).collect::<String>();
                     let gronsfeld = Gronsfeld::new().alphabet(alphabet).key_str(&key).build().unwrap();
@@ -91,22 +92,23 @@ impl GronsfeldCracker {
                     println!(
                         "\t\t{} key permutations... {}",
                         "Brute forcing".bold().green(),
-                        if iteration == total {
-                            format!("{:.2}%", (100. * iteration as f64 / total as f64)).bold().green()
+                        if progress.is_complete() {
+                            progress.to_string().bold().green()
                         } else {
-                            format!("{:.2}%", (100. * iteration as f64 / total as f64)).bold().yellow()
+                            progress.to_string().bold().yellow()
                         }
                     );
                 }
 
                 println!("\t\t{} potential plaintexts...", "Scoring and sorting".bold().green(),);
-                let (_best_key, best_plaintext) = plaintexts
+                let (score, best_plaintext) = plaintexts
                     .iter()
-                    .max_by(|first, other| PossiblePlaintext::new(&first.1).cmp(&PossiblePlaintext::new(&other.1)))
+                    .map(|(_, plaintext)| (cipher_utils::englishness(plaintext), plaintext))
+                    .max_by(|first, other| first.0.total_cmp(&other.0))
                     .unwrap();
 
                 println!("\t\t{} best plaintext...", "Quality checking".bold().green(),);
-                if PossiblePlaintext::new(&best_plaintext).score() > 0.85 {
+                if score > Self::PLAINTEXT_ENGLISHNESS {
                     println!("\t\t{} plaintext found!", "Good quality".bold().green());
                     return Ok(best_plaintext.to_owned());
                 }
@@ -119,6 +121,89 @@ impl GronsfeldCracker {
         unimplemented!()
     }
 
+    /// Decrypts the given ciphertext and returns the `n` most English-like candidates, for when the single plaintext
+    /// returned by `decrypt()` is wrong. If the key digits are known, every arrangement of them is tried; Otherwise,
+    /// every key of increasing length, including keys with repeated digits, is tried until one of them gives a good
+    /// quality plaintext, like `decrypt()` does.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    /// - `n` - The number of candidates to return.
+    ///
+    /// # Returns
+    /// The `n` best `(key, plaintext, score)` candidates, in order from best to worst, where the score is the
+    /// plaintext's `cipher_utils::englishness()`.
+    ///
+    /// # Errors
+    /// - If the alphabet isn't known
+    /// - If `n` is 0 or greater than the number of keys tried
+    ///
+    /// # Performance
+    /// Only the `n` best keys are kept while searching, and their plaintexts are decrypted again at the end.
+    pub fn decrypt_best_n(&self, ciphertext: &str, n: usize) -> anyhow::Result<Vec<(String, String, f64)>> {
+        let Some(alphabet) = &self.alphabet else {
+            anyhow::bail!("Finding the best Gronsfeld decryptions requires a known alphabet");
+        };
+
+        let (best, tried) = if let Some(key_digits) = &self.key_digits {
+            let keys = key_digits.iter().permutations(key_digits.len()).unique().map(|permutation| permutation.iter().join(""));
+            Self::best_keys(alphabet, ciphertext, keys, n)?
+        } else {
+            let mut key_digits = 1;
+            loop {
+                let keys = itertools::repeat_n(0..10, key_digits).multi_cartesian_product().map(|digits| digits.iter().join(""));
+                let (best, tried) = Self::best_keys(alphabet, ciphertext, keys, n)?;
+                if key_digits == 10 || best.iter().any(|Reverse(candidate)| candidate.score > Self::PLAINTEXT_ENGLISHNESS) {
+                    break (best, tried);
+                }
+                key_digits += 1;
+            }
+        };
+
+        if n == 0 || n > tried {
+            anyhow::bail!("Error getting the best {n} Gronsfeld decryptions: Expected between 1 and {tried} candidates");
+        }
+
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ScoredKey { score, key })| {
+                let plaintext = Gronsfeld::new().alphabet(alphabet).key_from_digit_str(&key).build()?.decrypt(ciphertext)?;
+                Ok((key, plaintext, score))
+            })
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext with each of the given keys, keeping the `n` keys that give the most English-like
+    /// plaintexts.
+    ///
+    /// # Parameters
+    /// - `alphabet` - The alphabet of the cipher.
+    /// - `ciphertext` - The text to decrypt.
+    /// - `keys` - The keys to try, as strings of digits.
+    /// - `n` - The number of keys to keep.
+    ///
+    /// # Returns
+    /// A min-heap of the `n` best keys, so its top is the worst of them, and the number of keys tried.
+    ///
+    /// # Errors
+    /// If the alphabet is invalid.
+    fn best_keys(alphabet: &str, ciphertext: &str, keys: impl Iterator<Item = String>, n: usize) -> anyhow::Result<(BinaryHeap<Reverse<ScoredKey>>, usize)> {
+        let mut best = BinaryHeap::with_capacity(n + 1);
+        let mut tried = 0;
+        for key in keys {
+            let plaintext = Gronsfeld::new().alphabet(alphabet).key_from_digit_str(&key).build()?.decrypt(ciphertext)?;
+            best.push(Reverse(ScoredKey {
+                score: cipher_utils::englishness(&plaintext),
+                key,
+            }));
+            if best.len() > n {
+                best.pop();
+            }
+            tried += 1;
+        }
+        Ok((best, tried))
+    }
+
     pub fn with_known_alphabet(mut self, alphabet: &str) -> Self {
         self.alphabet = Some(alphabet.to_owned());
         self
@@ -130,8 +215,36 @@ impl GronsfeldCracker {
     }
 }
 
+/// A key tried by `GronsfeldCracker::decrypt_best_n()` and the englishness of its plaintext, ordered by score.
+struct ScoredKey {
+    score: f64,
+    key: String,
+}
+
+impl PartialEq for ScoredKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for ScoredKey {}
+
+impl PartialOrd for ScoredKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score.total_cmp(&other.score)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use gronsfeld::{Gronsfeld, GronsfeldBuilder as _};
+
     use crate::GronsfeldCracker;
 
     #[test]
@@ -147,4 +260,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn decrypt_best_n() -> anyhow::Result<()> {
+        let ciphertext = include_str!("../tests/encrypted_letter.txt");
+        let plaintext = include_str!("../tests/letter.txt");
+
+        let gronsfeld = GronsfeldCracker::new()
+            .with_known_alphabet("AYCDWZIHGJKLQNOPMVSTXREUBF")
+            .with_known_key_digits(&[1, 2, 3, 3, 4, 4, 8]);
+
+        let best = gronsfeld.decrypt_best_n(ciphertext, 3)?;
+        assert_eq!(3, best.len());
+        assert!(best.iter().any(|(_, candidate, _)| candidate == plaintext));
+        assert!(best.windows(2).all(|pair| pair[0].2 >= pair[1].2));
+
+        let (key, _, _) = best.iter().find(|(_, candidate, _)| candidate == plaintext).unwrap();
+        assert_eq!(7, key.len());
+
+        assert!(gronsfeld.decrypt_best_n(ciphertext, 0).is_err());
+        assert!(GronsfeldCracker::new().decrypt_best_n(ciphertext, 3).is_err());
+
+        // Unknown keys can repeat digits and start with a zero
+        let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let ciphertext = Gronsfeld::new().alphabet(alphabet).key_from_digit_str("090").build()?.encrypt(plaintext)?;
+        let best = GronsfeldCracker::new().with_known_alphabet(alphabet).decrypt_best_n(&ciphertext, 2)?;
+        assert_eq!(2, best.len());
+        assert_eq!("090", best[0].0);
+        assert_eq!(plaintext, best[0].1);
+
+        Ok(())
+    }
 }
@@ -1,8 +1,12 @@
-use cipher_utils::alphabet::Alphabet;
+use cipher_utils::{alphabet::Alphabet, unknown_char_policy::UnknownCharPolicy};
 
 pub struct Gronsfeld {
     alphabet: Alphabet,
-    key: u128,
+
+    /// The digits of the key, in order.
+    key: Vec<u8>,
+
+    unknown_char_policy: UnknownCharPolicy,
 }
 
 impl Gronsfeld {
@@ -12,52 +16,47 @@ impl Gronsfeld {
     }
 
     pub fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
-        let key = self.key.to_string().repeat(plaintext.len() / self.key.to_string().len());
+        let key = &self.key;
 
         let mut index = 0;
         plaintext
             .chars()
-            .map(|letter| {
-                if !letter.is_alphabetic() {
-                    return Ok(letter);
-                }
+            .filter_map(|letter| {
+                let Some(alphabet_index) = self.alphabet.index_of(letter) else {
+                    return self.unknown_char_policy.apply(letter).transpose();
+                };
 
-                let key_digit = key.chars().nth(index).unwrap();
+                let key_digit = key[index % key.len()] as u32;
 
-                let alphabet_index = self.alphabet.index_of(letter).ok_or_else(|| anyhow::anyhow!("Character not in alphabet: {letter}"))?;
-                let mut ciphertext_letter = *self.alphabet.letter_at(alphabet_index + key_digit.to_digit(10).unwrap());
+                let mut ciphertext_letter = *self.alphabet.letter_at(alphabet_index + key_digit);
                 if letter.is_lowercase() {
                     ciphertext_letter = ciphertext_letter.to_ascii_lowercase();
                 }
                 index += 1;
-                Ok(ciphertext_letter)
+                Some(Ok(ciphertext_letter))
             })
             .collect::<anyhow::Result<String>>()
     }
 
     pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
-        let key = self.key.to_string().repeat(ciphertext.len() / self.key.to_string().len());
+        let key = &self.key;
 
         let mut index = 0;
         ciphertext
             .chars()
-            .map(|ciphertext_letter| {
-                if !ciphertext_letter.is_alphabetic() {
-                    return Ok(ciphertext_letter);
-                }
+            .filter_map(|ciphertext_letter| {
+                let Some(alphabet_index) = self.alphabet.index_of(ciphertext_letter) else {
+                    return self.unknown_char_policy.apply(ciphertext_letter).transpose();
+                };
 
-                let key_digit = key.chars().nth(index).unwrap();
+                let key_digit = key[index % key.len()] as u32;
 
-                let alphabet_index = self
-                    .alphabet
-                    .index_of(ciphertext_letter)
-                    .ok_or_else(|| anyhow::anyhow!("Character not in alphabet: {ciphertext_letter}"))?;
                 index += 1;
-                let mut plaintext_character = *self.alphabet.letter_at(alphabet_index - key_digit.to_digit(10).unwrap());
+                let mut plaintext_character = *self.alphabet.letter_at(alphabet_index - key_digit);
                 if ciphertext_letter.is_lowercase() {
                     plaintext_character = plaintext_character.to_ascii_lowercase();
                 }
-                Ok(plaintext_character)
+                Some(Ok(plaintext_character))
             })
             .collect::<anyhow::Result<String>>()
     }
@@ -66,20 +65,61 @@ impl Gronsfeld {
 #[derive(Default, Debug)]
 struct IncompleteGronsfeld {
     alphabet: Option<Alphabet>,
-    key: Option<u128>,
+    key: Option<Vec<u8>>,
+    unknown_char_policy: UnknownCharPolicy,
 }
 
 pub trait GronsfeldBuilder {
     fn alphabet(self, alphabet: &str) -> Self;
     fn key(self, key: u128) -> Self;
     fn key_str(self, key: &str) -> Self;
+
+    /// Sets the key from a string of digits that may be separated, such as `"9 5 3"`, `"9,5,3"`, or `"9-5-3"`, as keys
+    /// are often written in puzzles. Every digit in the string is a digit of the key, so keys can be longer than the
+    /// 39 digits that fit in `key()`, and leading zeros are kept.
+    ///
+    /// # Parameters
+    /// - `key` - The digits of the key, optionally separated by whitespace or punctuation.
+    ///
+    /// # Returns
+    /// The builder with the key set.
+    ///
+    /// # Errors
+    /// If the string has no digits, or contains a character that's not a digit, whitespace, or punctuation.
+    fn key_from_digit_str(self, key: &str) -> Self;
+
+    /// Sets the key from a keyword, where each letter becomes the digit of its position in the English alphabet
+    /// modulo 10, counting from `A = 0`. For example, `"KEY"` becomes the key `044`. Characters that aren't English
+    /// letters, such as spaces, are skipped.
+    ///
+    /// # Parameters
+    /// - `word` - The keyword to derive the key from.
+    ///
+    /// # Returns
+    /// The builder with the key set.
+    ///
+    /// # Errors
+    /// If the keyword has no English letters.
+    fn key_from_word(self, word: &str) -> Self;
+
+    /// Sets what the cipher does with characters that aren't in its alphabet. By default, they're
+    /// passed through to the output unchanged.
+    fn unknown_char_policy(self, policy: UnknownCharPolicy) -> Self;
+
     fn build(self) -> anyhow::Result<Gronsfeld>;
 }
 
 impl GronsfeldBuilder for anyhow::Result<IncompleteGronsfeld> {
     fn alphabet(self, alphabet: &str) -> Self {
         if let Ok(mut gronsfeld) = self {
-            gronsfeld.alphabet = Some(Alphabet::caseless(alphabet)?);
+            let alphabet = Alphabet::caseless(alphabet)?;
+            if !alphabet.is_standard_permutation() {
+                anyhow::bail!(
+                    "Invalid alphabet: {:?} is not a permutation of the English alphabet",
+                    alphabet.characters().iter().collect::<String>()
+                );
+            }
+            gronsfeld.alphabet = Some(alphabet);
             Ok(gronsfeld)
         } else {
             self
@@ -88,7 +128,7 @@ impl GronsfeldBuilder for anyhow::Result<IncompleteGronsfeld> {
 
     fn key(self, key: u128) -> Self {
         if let Ok(mut gronsfeld) = self {
-            gronsfeld.key = Some(key);
+            gronsfeld.key = Some(digits_of(key));
             Ok(gronsfeld)
         } else {
             self
@@ -97,7 +137,52 @@ impl GronsfeldBuilder for anyhow::Result<IncompleteGronsfeld> {
 
     fn key_str(self, key: &str) -> Self {
         if let Ok(mut gronsfeld) = self {
-            gronsfeld.key = Some(key.parse()?);
+            gronsfeld.key = Some(digits_of(key.parse()?));
+            Ok(gronsfeld)
+        } else {
+            self
+        }
+    }
+
+    fn key_from_digit_str(self, key: &str) -> Self {
+        if let Ok(mut gronsfeld) = self {
+            if let Some(character) = key
+                .chars()
+                .find(|character| !(character.is_ascii_digit() || character.is_whitespace() || character.is_ascii_punctuation()))
+            {
+                anyhow::bail!("Invalid Gronsfeld key {key:?}: {character:?} is not a digit or separator");
+            }
+            let digits = key.chars().filter_map(|character| character.to_digit(10)).map(|digit| digit as u8).collect::<Vec<_>>();
+            if digits.is_empty() {
+                anyhow::bail!("Invalid Gronsfeld key {key:?}: The key has no digits");
+            }
+            gronsfeld.key = Some(digits);
+            Ok(gronsfeld)
+        } else {
+            self
+        }
+    }
+
+    fn key_from_word(self, word: &str) -> Self {
+        if let Ok(mut gronsfeld) = self {
+            let digits = word
+                .chars()
+                .filter(|character| character.is_ascii_alphabetic())
+                .map(|letter| (letter.to_ascii_uppercase() as u8 - b'A') % 10)
+                .collect::<Vec<_>>();
+            if digits.is_empty() {
+                anyhow::bail!("Invalid Gronsfeld keyword {word:?}: The keyword has no letters");
+            }
+            gronsfeld.key = Some(digits);
+            Ok(gronsfeld)
+        } else {
+            self
+        }
+    }
+
+    fn unknown_char_policy(self, policy: UnknownCharPolicy) -> Self {
+        if let Ok(mut gronsfeld) = self {
+            gronsfeld.unknown_char_policy = policy;
             Ok(gronsfeld)
         } else {
             self
@@ -113,15 +198,32 @@ impl GronsfeldBuilder for anyhow::Result<IncompleteGronsfeld> {
                 anyhow::bail!("Error constructing Gronsfeld cipher: No key set");
             };
 
-            Ok(Gronsfeld { alphabet, key })
+            Ok(Gronsfeld {
+                alphabet,
+                key,
+                unknown_char_policy: gronsfeld.unknown_char_policy,
+            })
         } else {
             Err(self.unwrap_err())
         }
     }
 }
 
+/// Returns the decimal digits of the given number, from most to least significant.
+///
+/// # Parameters
+/// - `number` - The number to get the digits of.
+///
+/// # Returns
+/// The digits of the number.
+fn digits_of(number: u128) -> Vec<u8> {
+    number.to_string().bytes().map(|digit| digit - b'0').collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use cipher_utils::unknown_char_policy::UnknownCharPolicy;
+
     use crate::{Gronsfeld, GronsfeldBuilder as _};
 
     #[test]
@@ -138,4 +240,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn unknown_char_policies() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+        let builder = || Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(31415);
+
+        let passthrough = builder().build()?;
+        assert_eq!("Pfiu rh bx 10:45, cd jbxf 3!", passthrough.encrypt(plaintext)?);
+        assert_eq!(plaintext, passthrough.decrypt(&passthrough.encrypt(plaintext)?)?);
+
+        let strip = builder().unknown_char_policy(UnknownCharPolicy::Strip).build()?;
+        assert_eq!("Pfiurhbxcdjbxf", strip.encrypt(plaintext)?);
+        assert_eq!("Meetmeatbygate", strip.decrypt(&strip.encrypt(plaintext)?)?);
+
+        let error = builder().unknown_char_policy(UnknownCharPolicy::Error).build()?;
+        assert!(error.encrypt(plaintext).is_err());
+        assert!(error.decrypt("Pfiu rh bx 10:45").is_err());
+        assert_eq!("Pfiurhbx", error.encrypt("Meetmeat")?);
+
+        Ok(())
+    }
+    #[test]
+    fn key_from_digit_str() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+        let expected = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(953).build()?.encrypt(plaintext)?;
+
+        for key in ["953", "9 5 3", "9, 5, 3", "9,5,3", "9-5-3", " 9 - 5 - 3 "] {
+            let gronsfeld = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_digit_str(key).build()?;
+            assert_eq!(expected, gronsfeld.encrypt(plaintext)?);
+            assert_eq!(plaintext, gronsfeld.decrypt(&expected)?);
+        }
+
+        // Keys longer than a u128, and keys with leading zeros
+        let long_key = "3141-5926-5358-9793-2384-6264-3383-2795-0288-4197";
+        let gronsfeld = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_digit_str(long_key).build()?;
+        assert_eq!(plaintext, gronsfeld.decrypt(&gronsfeld.encrypt(plaintext)?)?);
+        let leading_zero = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_digit_str("0 1").build()?;
+        assert_eq!("Accg", leading_zero.encrypt("Abcf")?);
+
+        assert!(Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_digit_str("9-5-X").build().is_err());
+        assert!(Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_digit_str(" - ").build().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn key_from_word() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+
+        // K = 10, E = 4, Y = 24, and so on, each mod 10
+        let expected = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_digit_str("0 4 4 2 4 7 3 1 8 3").build()?;
+        let gronsfeld = Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_word("Key word-Lid").build()?;
+        assert_eq!(expected.encrypt(plaintext)?, gronsfeld.encrypt(plaintext)?);
+        assert_eq!(plaintext, gronsfeld.decrypt(&gronsfeld.encrypt(plaintext)?)?);
+        assert_eq!(
+            "Accg",
+            Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_word("ab").build()?.encrypt("Abcf")?
+        );
+
+        assert!(Gronsfeld::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key_from_word("123 !").build().is_err());
+
+        Ok(())
+    }
 }
@@ -3,23 +3,96 @@ use std::fmt::Write as _;
 pub struct HexCipher;
 
 impl HexCipher {
+    /// Decrypts whitespace-separated hexadecimal codes. Codes may have a `0x` or `0X` prefix and use digits of either
+    /// case, and codes that run together without separators, such as `"4869"`, are read two digits at a time.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The hexadecimal codes to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If any of the codes has a character that isn't a hexadecimal digit, or if codes that run together have an odd
+    /// number of digits.
     pub fn decrypt(ciphertext: &str) -> anyhow::Result<String> {
-        ciphertext
-            .split_whitespace()
-            .map(|octal| Ok(u8::from_str_radix(octal, 8).map(|code| code as char)?))
-            .collect()
+        let mut plaintext = String::new();
+        for code in ciphertext.split_whitespace() {
+            Self::decrypt_code(code, &mut plaintext)?;
+        }
+        Ok(plaintext)
+    }
+
+    /// Decrypts hexadecimal codes that are separated by the given delimiter, such as `","` or `"\n"`. Whitespace around each code
+    /// is ignored, as are empty codes, such as from a trailing delimiter. Codes are read like in `HexCipher::decrypt()`.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The delimited hexadecimal codes to decrypt.
+    /// - `delimiter` - The delimiter between codes.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If any of the codes has a character that isn't a hexadecimal digit, or if codes that run together have an odd
+    /// number of digits.
+    pub fn decrypt_with_delimiter(ciphertext: &str, delimiter: &str) -> anyhow::Result<String> {
+        let mut plaintext = String::new();
+        for code in ciphertext.split(delimiter).map(|code| code.trim()).filter(|code| !code.is_empty()) {
+            Self::decrypt_code(code, &mut plaintext)?;
+        }
+        Ok(plaintext)
+    }
+
+    /// Decrypts a single hexadecimal code, which may be several codes run together, and appends the decrypted
+    /// characters to the given text.
+    ///
+    /// # Parameters
+    /// - `code` - The code to decrypt, optionally prefixed with `0x` or `0X`.
+    /// - `plaintext` - The text to append the decrypted characters to.
+    ///
+    /// # Errors
+    /// If the code has a character that isn't a hexadecimal digit, or if it's longer than two digits and has an odd number
+    /// of them.
+    fn decrypt_code(code: &str, plaintext: &mut String) -> anyhow::Result<()> {
+        let digits = code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")).unwrap_or(code);
+        if let Some(character) = digits.chars().find(|character| !character.is_ascii_hexdigit()) {
+            anyhow::bail!("Error decrypting hex: {code:?} has the non-hexadecimal character {character:?}");
+        }
+        if digits.is_empty() {
+            anyhow::bail!("Error decrypting hex: {code:?} has no digits");
+        }
+        if digits.len() > 2 && !digits.len().is_multiple_of(2) {
+            anyhow::bail!("Error decrypting hex: {code:?} has an odd number of digits, so it can't be split into bytes");
+        }
+
+        for pair in digits.as_bytes().chunks(2) {
+            plaintext.push(u8::from_str_radix(std::str::from_utf8(pair)?, 16)? as char);
+        }
+        Ok(())
     }
 
     pub fn encrypt(plaintext: &str) -> String {
-        plaintext
-            .chars()
-            .fold(String::new(), |mut accumulator, current| {
-                write!(accumulator, " {:03x}", current as u8).unwrap();
-                accumulator
-            })
-            .get(1..)
-            .unwrap()
-            .to_owned()
+        Self::encrypt_with_delimiter(plaintext, " ")
+    }
+
+    /// Encrypts the given text into hexadecimal codes separated by the given delimiter, such as `","` or `"\n"`.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    /// - `delimiter` - The delimiter to put between codes.
+    ///
+    /// # Returns
+    /// The delimited hexadecimal codes.
+    pub fn encrypt_with_delimiter(plaintext: &str, delimiter: &str) -> String {
+        let mut ciphertext = String::new();
+        for (index, character) in plaintext.chars().enumerate() {
+            if index != 0 {
+                ciphertext.push_str(delimiter);
+            }
+            write!(ciphertext, "{:02x}", character as u8).unwrap();
+        }
+        ciphertext
     }
 }
 
@@ -37,4 +110,53 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn delimiters() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let newline_separated = HexCipher::encrypt_with_delimiter(plaintext, "\n");
+        assert_eq!(plaintext.chars().count(), newline_separated.lines().count());
+        assert_eq!(plaintext, HexCipher::decrypt_with_delimiter(&newline_separated, "\n")?);
+        assert_eq!(HexCipher::encrypt(plaintext), HexCipher::encrypt_with_delimiter(plaintext, " "));
+
+        // Codes are written as two lowercase digits
+        assert_eq!("4a,0a,7e", HexCipher::encrypt_with_delimiter("J\n~", ","));
+
+        // Delimited codes are read like whitespace-separated ones: Prefixes, either case, and codes run together
+        for ciphertext in ["48,69,21", "0x48,0X69,0x21", "4869,21", "0x4869, 21,", "48,6921"] {
+            assert_eq!("Hi!", HexCipher::decrypt_with_delimiter(ciphertext, ",")?, "Failed to decrypt {ciphertext:?}");
+        }
+        assert_eq!("JJ", HexCipher::decrypt_with_delimiter("4A;4a", ";")?);
+        assert_eq!("\n", HexCipher::decrypt_with_delimiter("a", ";")?);
+
+        // A delimiter made of hex digits still splits the codes
+        assert_eq!("Hi", HexCipher::decrypt_with_delimiter("48ff69", "ff")?);
+
+        assert!(HexCipher::decrypt_with_delimiter("41;xyz", ";").is_err());
+        assert!(HexCipher::decrypt_with_delimiter("486,21", ",").is_err());
+        assert!(HexCipher::decrypt_with_delimiter("0x,41", ",").is_err());
+        assert!(HexCipher::decrypt_with_delimiter("48 69", ",").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn prefixes_and_grouping() -> anyhow::Result<()> {
+        for ciphertext in ["48 69 21", "0x48 0x69 0x21", "0X48 0x69 21", "486921", "0x486921", "48 6921"] {
+            assert_eq!("Hi!", HexCipher::decrypt(ciphertext)?, "Failed to decrypt {ciphertext:?}");
+        }
+
+        let plaintext = "\u{ab}\u{cd}\u{ef}";
+        for ciphertext in ["ab cd ef", "AB CD EF", "aB Cd eF", "0xAB 0XcD 0xef", "ABcdEF"] {
+            assert_eq!(plaintext, HexCipher::decrypt(ciphertext)?, "Failed to decrypt {ciphertext:?}");
+        }
+        assert_eq!(plaintext, HexCipher::decrypt_with_delimiter("0xAB,0xcd,0xEf", ",")?);
+
+        assert!(HexCipher::decrypt("48692").is_err());
+        assert!(HexCipher::decrypt("48 6g").is_err());
+        assert!(HexCipher::decrypt("0x").is_err());
+        assert!(HexCipher::decrypt("+48").is_err());
+
+        Ok(())
+    }
 }
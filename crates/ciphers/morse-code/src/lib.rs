@@ -1,3 +1,5 @@
+use cipher_utils::cipher::Cipher;
+
 static DECRYPTION_MAP: phf::Map<&str, char> = phf::phf_map! {
     ".-" => 'A',
     "-..." => 'B',
@@ -133,6 +135,16 @@ impl MorseCode {
     }
 }
 
+impl Cipher for MorseCode {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        Ok(MorseCode::encrypt(plaintext))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(MorseCode::decrypt(ciphertext))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::MorseCode;
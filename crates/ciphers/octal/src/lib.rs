@@ -6,20 +6,52 @@ impl OctalCipher {
     pub fn decrypt(ciphertext: &str) -> anyhow::Result<String> {
         ciphertext
             .split_whitespace()
-            .map(|octal| Ok(u8::from_str_radix(octal, 8).map(|code| code as char)?))
+            .map(|code| Ok(u8::from_str_radix(code, 8).map(|code| code as char)?))
+            .collect()
+    }
+
+    /// Decrypts octal codes that are separated by the given delimiter, such as `","` or `"\n"`. Whitespace around each code
+    /// is ignored, as are empty codes, such as from a trailing delimiter.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The delimited octal codes to decrypt.
+    /// - `delimiter` - The delimiter between codes.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If any of the codes isn't a valid octal number in `[0, 255]`.
+    pub fn decrypt_with_delimiter(ciphertext: &str, delimiter: &str) -> anyhow::Result<String> {
+        ciphertext
+            .split(delimiter)
+            .map(|code| code.trim())
+            .filter(|code| !code.is_empty())
+            .map(|code| Ok(u8::from_str_radix(code, 8).map(|code| code as char)?))
             .collect()
     }
 
     pub fn encrypt(plaintext: &str) -> String {
-        plaintext
-            .chars()
-            .fold(String::new(), |mut accumulator, current| {
-                write!(accumulator, " {:03o}", current as u8).unwrap();
-                accumulator
-            })
-            .get(1..)
-            .unwrap()
-            .to_owned()
+        Self::encrypt_with_delimiter(plaintext, " ")
+    }
+
+    /// Encrypts the given text into octal codes separated by the given delimiter, such as `","` or `"\n"`.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    /// - `delimiter` - The delimiter to put between codes.
+    ///
+    /// # Returns
+    /// The delimited octal codes.
+    pub fn encrypt_with_delimiter(plaintext: &str, delimiter: &str) -> String {
+        let mut ciphertext = String::new();
+        for (index, character) in plaintext.chars().enumerate() {
+            if index != 0 {
+                ciphertext.push_str(delimiter);
+            }
+            write!(ciphertext, "{:03o}", character as u8).unwrap();
+        }
+        ciphertext
     }
 }
 
@@ -37,4 +69,34 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn delimiters() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let newline_separated = OctalCipher::encrypt_with_delimiter(plaintext, "\n");
+        assert_eq!(plaintext.chars().count(), newline_separated.lines().count());
+        assert_eq!(plaintext, OctalCipher::decrypt_with_delimiter(&newline_separated, "\n")?);
+        assert_eq!(OctalCipher::encrypt(plaintext), OctalCipher::encrypt_with_delimiter(plaintext, " "));
+
+        // Codes are always three digits wide, so small codes are padded with zeros
+        assert_eq!("110,151,041", OctalCipher::encrypt_with_delimiter("Hi!", ","));
+        assert_eq!("012,011,000", OctalCipher::encrypt_with_delimiter("\n\t\0", ","));
+        assert!(OctalCipher::encrypt_with_delimiter(plaintext, ",").split(',').all(|code| code.len() == 3));
+
+        // Decrypting doesn't need the padding, and ignores whitespace and empty codes
+        assert_eq!("\n\t\0", OctalCipher::decrypt_with_delimiter("12,11,0", ",")?);
+        assert_eq!("\n\t", OctalCipher::decrypt_with_delimiter("0012, 011,", ",")?);
+
+        // The largest three-digit code that fits in a byte is 377
+        assert_eq!("\u{ff}", OctalCipher::decrypt_with_delimiter("377", ",")?);
+        assert!(OctalCipher::decrypt_with_delimiter("400", ",").is_err());
+
+        // Unlike hex, codes can't run together, since each code is read as a single number
+        assert!(OctalCipher::decrypt_with_delimiter("110151", ",").is_err());
+
+        assert!(OctalCipher::decrypt_with_delimiter("101;xyz", ";").is_err());
+        assert!(OctalCipher::decrypt_with_delimiter("101;108", ";").is_err());
+
+        Ok(())
+    }
 }
@@ -20,7 +20,7 @@ impl OneTimePad {
     pub fn encrypt(plaintext: &str) -> (String, OneTimePadDecryptor) {
         let key = plaintext.chars().map(|_| rand::thread_rng().gen_range(65u8..=90u8) as char).collect::<String>();
         let vigenere = Vigenere::new().key(&key).alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").build().unwrap();
-        let ciphertext = vigenere.encrypt(plaintext);
+        let ciphertext = vigenere.encrypt(plaintext).unwrap();
         let decryptor = OneTimePadDecryptor { key };
         (ciphertext, decryptor)
     }
@@ -46,6 +46,6 @@ impl OneTimePadDecryptor {
     /// The decrypted message.
     pub fn decrypt(self, ciphertext: &str) -> String {
         let vigenere = Vigenere::new().key(self.key).alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").build().unwrap();
-        vigenere.decrypt(ciphertext)
+        vigenere.decrypt(ciphertext).unwrap()
     }
 }
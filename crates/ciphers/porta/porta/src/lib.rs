@@ -39,7 +39,11 @@ impl PortaBuilder for anyhow::Result<IncompletePorta> {
 
     fn alphabet<T: AsRef<str>>(self, alphabet: T) -> impl PortaBuilder {
         if let Ok(mut porta) = self {
-            porta.alphabet = Some(Alphabet::caseless(alphabet.as_ref())?);
+            let alphabet = Alphabet::caseless(alphabet.as_ref())?;
+            if !alphabet.is_standard_permutation() {
+                anyhow::bail!("Invalid alphabet: {:?} is not a permutation of the English alphabet", alphabet.characters().iter().collect::<String>());
+            }
+            porta.alphabet = Some(alphabet);
             Ok(porta)
         } else {
             self
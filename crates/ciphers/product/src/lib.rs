@@ -0,0 +1,113 @@
+use cipher_utils::cipher::{Cipher, CipherPipeline};
+use columnar_transposition::ColumnarTransposition;
+use vigenere_lib::{Vigenere, VigenereBuilder as _};
+
+/// A product cipher, which encrypts text with a Vigenere cipher and then with a keyed columnar transposition. The
+/// Vigenere hides the letter frequencies that would give away the transposition, and the transposition scrambles the
+/// periodic patterns that would give away the Vigenere, so the combination is much stronger than either alone, in the
+/// same way as the ADFGVX cipher's substitution and transposition stages.
+///
+/// Decrypting undoes the transposition and then the Vigenere. Both stages keep every character, so spaces and
+/// punctuation are transposed along with the letters.
+pub struct ProductCipher {
+    /// The Vigenere cipher followed by the columnar transposition.
+    pipeline: CipherPipeline,
+}
+
+impl ProductCipher {
+    /// Creates a product cipher from a key for each of its stages.
+    ///
+    /// # Parameters
+    /// - `vigenere_key` - The key of the Vigenere stage, which uses the standard English alphabet.
+    /// - `transposition_key` - The key of the columnar transposition stage, whose characters give the order the columns
+    ///   are read off in; See `ColumnarTransposition::new()`.
+    ///
+    /// # Returns
+    /// The created cipher.
+    ///
+    /// # Errors
+    /// If the Vigenere key is empty or has characters that aren't English letters, or if the transposition key is empty.
+    pub fn new(vigenere_key: &str, transposition_key: &str) -> anyhow::Result<Self> {
+        if let Some(character) = vigenere_key.chars().find(|character| !character.is_ascii_alphabetic()) {
+            anyhow::bail!("Error constructing product cipher: Vigenere key {vigenere_key:?} has the non-letter {character:?}");
+        }
+        if transposition_key.is_empty() {
+            anyhow::bail!("Error constructing product cipher: Transposition key is empty");
+        }
+
+        let vigenere = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(vigenere_key.to_ascii_uppercase()).build()?;
+        Ok(Self {
+            pipeline: CipherPipeline::new().then(vigenere).then(ColumnarTransposition::new(transposition_key)),
+        })
+    }
+
+    /// Encrypts the given plaintext with the Vigenere cipher, and then with the columnar transposition.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    ///
+    /// # Errors
+    /// If either stage fails to encrypt the text.
+    pub fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        self.pipeline.encrypt(plaintext)
+    }
+
+    /// Decrypts the given ciphertext by undoing the columnar transposition, and then the Vigenere cipher.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If either stage fails to decrypt the text.
+    pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        self.pipeline.decrypt(ciphertext)
+    }
+}
+
+impl Cipher for ProductCipher {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        ProductCipher::encrypt(self, plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        ProductCipher::decrypt(self, ciphertext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use columnar_transposition::ColumnarTransposition;
+    use vigenere_lib::{Vigenere, VigenereBuilder as _};
+
+    use crate::ProductCipher;
+
+    #[test]
+    fn encrypt_decrypt() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let cipher = ProductCipher::new("lemon", "ZEBRAS")?;
+
+        let substituted = Vigenere::new()
+            .alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+            .key("LEMON")
+            .build()?
+            .encrypt("WEAREDISCOVEREDFLEEATONCE")?;
+        let expected = ColumnarTransposition::new("ZEBRAS").encrypt(&substituted);
+        assert_eq!(expected, cipher.encrypt("WEAREDISCOVEREDFLEEATONCE")?);
+        assert_eq!("WEAREDISCOVEREDFLEEATONCE", cipher.decrypt(&expected)?);
+
+        cipher_utils::cipher::check_cipher(&cipher, plaintext)?;
+        assert_ne!(plaintext, cipher.encrypt(plaintext)?);
+
+        assert!(ProductCipher::new("", "ZEBRAS").is_err());
+        assert!(ProductCipher::new("LEM0N", "ZEBRAS").is_err());
+        assert!(ProductCipher::new("LEMON", "").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+/// The rail fence cipher, which writes the text in a zigzag down and up across a number of rows ("rails"), and then reads
+/// it off one rail at a time.
+pub struct RailFence {
+    rails: usize,
+}
+
+impl RailFence {
+    /// Creates a new rail fence cipher with the given number of rails.
+    ///
+    /// # Parameters
+    /// - `rails` - The number of rails to write the text across.
+    ///
+    /// # Returns
+    /// The created cipher.
+    ///
+    /// # Errors
+    /// If there are fewer than two rails.
+    pub fn new(rails: usize) -> anyhow::Result<Self> {
+        if rails < 2 {
+            anyhow::bail!("Error creating rail fence cipher: Expected at least 2 rails, but found {rails}");
+        }
+        Ok(Self { rails })
+    }
+
+    /// Encrypts the given text.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        let mut rails = vec![String::new(); self.rails];
+        for (index, character) in plaintext.chars().enumerate() {
+            rails[self.rail_of(index)].push(character);
+        }
+        rails.concat()
+    }
+
+    /// Decrypts the given text. The ciphertext is cut back into the rails that `encrypt()` produced, which are then read
+    /// in the zigzag order.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    pub fn decrypt(&self, ciphertext: &str) -> String {
+        let characters = ciphertext.chars().collect::<Vec<_>>();
+        let mut lengths = vec![0; self.rails];
+        for index in 0..characters.len() {
+            lengths[self.rail_of(index)] += 1;
+        }
+
+        let mut starts = Vec::with_capacity(self.rails);
+        let mut start = 0;
+        for length in lengths {
+            starts.push(start);
+            start += length;
+        }
+
+        (0..characters.len())
+            .map(|index| {
+                let rail = self.rail_of(index);
+                starts[rail] += 1;
+                characters[starts[rail] - 1]
+            })
+            .collect()
+    }
+
+    /// Returns the numbers of rails that could have produced a ciphertext of the given length, most likely first, such as
+    /// for ordering the numbers of rails tried by a cracker. Messages were often padded to fill the last zigzag, so
+    /// numbers of rails whose zigzag of `2 * (rails - 1)` characters divides the length come first. Every other number of
+    /// rails follows, since an unpadded message fits any number of rails.
+    ///
+    /// # Parameters
+    /// - `ciphertext_len` - The number of characters of the ciphertext.
+    ///
+    /// # Returns
+    /// Every number of rails in `[2, ciphertext_len)`, ranked as above and ascending within each rank. With as many
+    /// rails as characters, the ciphertext is the same as the plaintext.
+    ///
+    /// # Performance
+    /// This is `O(n)` for a ciphertext length of `n`.
+    pub fn rail_candidates(ciphertext_len: usize) -> Vec<usize> {
+        let (fitting, other) = (2..ciphertext_len).partition::<Vec<_>, _>(|rails| ciphertext_len.is_multiple_of(2 * (rails - 1)));
+        fitting.into_iter().chain(other).collect()
+    }
+
+    /// Returns the rail that the character at the given index of the plaintext is written on.
+    fn rail_of(&self, index: usize) -> usize {
+        let cycle = 2 * (self.rails - 1);
+        let position = index % cycle;
+        position.min(cycle - position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RailFence;
+
+    #[test]
+    fn encrypt_decrypt() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+
+        assert_eq!("WECRLTEERDSOEEFEAOCAIVDEN", RailFence::new(3)?.encrypt("WEAREDISCOVEREDFLEEATONCE"));
+        assert_eq!("WEAREDISCOVEREDFLEEATONCE", RailFence::new(3)?.decrypt("WECRLTEERDSOEEFEAOCAIVDEN"));
+
+        for rails in 2..10 {
+            let rail_fence = RailFence::new(rails)?;
+            assert_eq!(plaintext, rail_fence.decrypt(&rail_fence.encrypt(plaintext)));
+        }
+
+        assert!(RailFence::new(1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rail_candidates() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        for rails in [3, 5, 8] {
+            let ciphertext = RailFence::new(rails)?.encrypt(plaintext);
+            assert!(RailFence::rail_candidates(ciphertext.chars().count()).contains(&rails));
+        }
+
+        // 24 characters fill the zigzags of 2, 3, 4, 5, 7, and 13 rails
+        assert_eq!(
+            vec![2, 3, 4, 5, 7, 13, 6, 8, 9, 10, 11, 12, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23],
+            RailFence::rail_candidates(24)
+        );
+        assert!(RailFence::rail_candidates(2).is_empty());
+
+        Ok(())
+    }
+}
@@ -1,75 +1,108 @@
-use cipher_utils::alphabet::Alphabet;
-use vigenere_lib::{Vigenere, VigenereBuilder};
+use cipher_utils::{
+    alphabet::{Alphabet, AlphabetIndex},
+    unknown_char_policy::UnknownCharPolicy,
+};
 
 pub struct RunningKey {
     alphabet: Alphabet,
     key: String,
+    unknown_char_policy: UnknownCharPolicy,
 }
 
 impl RunningKey {
+    /// Encrypts the given plaintext. Each letter of the plaintext is shifted by the next letter of the key; Characters
+    /// of the key that aren't in the alphabet, such as the spaces and punctuation of a book passage, are skipped.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    ///
+    /// # Errors
+    /// If the key has fewer letters than the plaintext, or if the unknown character policy rejects a character.
     pub fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
-        if self.key.len() < plaintext.len() {
-            anyhow::bail!("Error encrypting running-key cipher: Plaintext is shorter than key. If this is intentional, consider using a Vigenere cipher.");
+        if self.key_letters().count() < self.letter_count(plaintext) {
+            anyhow::bail!("Error encrypting running-key cipher: Key is shorter than plaintext. If this is intentional, consider using a Vigenere cipher.");
         }
-        let key_bytes = self.key.as_bytes();
-        let mut index = 0;
+        let mut key_letters = self.key_letters();
         plaintext
             .chars()
-            .map(|plain_char| {
-                if !plain_char.is_alphabetic() {
-                    return Ok(plain_char);
-                }
-                let key_char = key_bytes[index] as char;
-                let plaintext_index = self.alphabet.index_of(plain_char).unwrap();
-                let key_index = self.alphabet.index_of(key_char).unwrap();
+            .filter_map(|plain_char| {
+                let Some(plaintext_index) = self.alphabet.index_of(plain_char) else {
+                    return self.unknown_char_policy.apply(plain_char).transpose();
+                };
+                let key_index = key_letters.next().unwrap();
                 let result = self.alphabet.letter_at(plaintext_index + key_index - 1);
-                index += 1;
-                Ok(if plain_char.is_uppercase() {
+                Some(Ok(if plain_char.is_uppercase() {
                     result.to_ascii_uppercase()
                 } else {
                     result.to_ascii_lowercase()
-                })
+                }))
             })
             .collect()
     }
 
+    /// Decrypts the given ciphertext. Characters of the key that aren't in the alphabet are skipped, as in
+    /// `RunningKey::encrypt()`.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the key has fewer letters than the ciphertext, or if the unknown character policy rejects a character.
     pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
-        if self.key.len() < ciphertext.len() {
-            anyhow::bail!("Error decrypting running-key cipher: Ciphertext is shorter than key. If this is intentional, consider using a Vigenere cipher.");
+        if self.key_letters().count() < self.letter_count(ciphertext) {
+            anyhow::bail!("Error decrypting running-key cipher: Key is shorter than ciphertext. If this is intentional, consider using a Vigenere cipher.");
         }
-        let key_bytes = self.key.as_bytes();
-        let mut index = 0;
+        let mut key_letters = self.key_letters();
         ciphertext
             .chars()
-            .map(|cipher_char| {
-                if !cipher_char.is_alphabetic() {
-                    return Ok(cipher_char);
-                }
-                let key_char = key_bytes[index] as char;
-                let ciphertext_index = self.alphabet.index_of(cipher_char).unwrap();
-                let key_index = self.alphabet.index_of(key_char).unwrap();
+            .filter_map(|cipher_char| {
+                let Some(ciphertext_index) = self.alphabet.index_of(cipher_char) else {
+                    return self.unknown_char_policy.apply(cipher_char).transpose();
+                };
+                let key_index = key_letters.next().unwrap();
                 let result = self.alphabet.letter_at(ciphertext_index - key_index + 1);
-                index += 1;
-                Ok(if cipher_char.is_uppercase() {
+                Some(Ok(if cipher_char.is_uppercase() {
                     result.to_ascii_uppercase()
                 } else {
                     result.to_ascii_lowercase()
-                })
+                }))
             })
             .collect()
     }
+
+    /// Returns the indices of the letters of the key in the alphabet, skipping characters that aren't in the alphabet.
+    fn key_letters(&self) -> impl Iterator<Item = AlphabetIndex> + '_ {
+        self.key.chars().filter_map(|character| self.alphabet.index_of(character))
+    }
+
+    /// Returns the number of characters of the given text that are in the alphabet.
+    fn letter_count(&self, text: &str) -> usize {
+        text.chars().filter(|character| self.alphabet.index_of(*character).is_some()).count()
+    }
 }
 
 pub trait RunningKeyBuilder {
     fn alphabet<T: AsRef<str>>(self, alphabet: T) -> impl RunningKeyBuilder;
     fn key<T: AsRef<str>>(self, key: T) -> impl RunningKeyBuilder;
-    fn build(self) -> anyhow::Result<Vigenere>;
+
+    /// Sets what the cipher does with characters that aren't in its alphabet. By default, they're
+    /// passed through to the output unchanged.
+    fn unknown_char_policy(self, policy: UnknownCharPolicy) -> impl RunningKeyBuilder;
+
+    fn build(self) -> anyhow::Result<RunningKey>;
 }
 
 #[derive(Debug, Default)]
 struct IncompleteRunningKey {
     key: Option<String>,
     alphabet: Option<Alphabet>,
+    unknown_char_policy: UnknownCharPolicy,
 }
 
 impl RunningKeyBuilder for anyhow::Result<IncompleteRunningKey> {
@@ -84,14 +117,30 @@ impl RunningKeyBuilder for anyhow::Result<IncompleteRunningKey> {
 
     fn alphabet<T: AsRef<str>>(self, alphabet: T) -> impl RunningKeyBuilder {
         if let Ok(mut running_key) = self {
-            running_key.alphabet = Some(Alphabet::caseless(alphabet.as_ref())?);
+            let alphabet = Alphabet::caseless(alphabet.as_ref())?;
+            if !alphabet.is_standard_permutation() {
+                anyhow::bail!(
+                    "Invalid alphabet: {:?} is not a permutation of the English alphabet",
+                    alphabet.characters().iter().collect::<String>()
+                );
+            }
+            running_key.alphabet = Some(alphabet);
+            Ok(running_key)
+        } else {
+            self
+        }
+    }
+
+    fn unknown_char_policy(self, policy: UnknownCharPolicy) -> impl RunningKeyBuilder {
+        if let Ok(mut running_key) = self {
+            running_key.unknown_char_policy = policy;
             Ok(running_key)
         } else {
             self
         }
     }
 
-    fn build(self) -> anyhow::Result<Vigenere> {
+    fn build(self) -> anyhow::Result<RunningKey> {
         if let Ok(running_key) = self {
             let Some(key) = running_key.key else {
                 anyhow::bail!("Error building RunningKey: No key provided.");
@@ -101,7 +150,11 @@ impl RunningKeyBuilder for anyhow::Result<IncompleteRunningKey> {
                 anyhow::bail!("Error building RunningKey: No alphabet provided.");
             };
 
-            Ok(Vigenere::new().alphabet(&alphabet.characters().iter().collect::<String>()).key(key).build().unwrap())
+            Ok(RunningKey {
+                alphabet,
+                key,
+                unknown_char_policy: running_key.unknown_char_policy,
+            })
         } else {
             Err(self.unwrap_err())
         }
@@ -114,3 +167,54 @@ impl RunningKey {
         Ok(IncompleteRunningKey::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cipher_utils::unknown_char_policy::UnknownCharPolicy;
+
+    use crate::{RunningKey, RunningKeyBuilder as _};
+
+    #[test]
+    fn unknown_char_policies() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+        let key = "ITWASTHEBESTOFTIMESITWASTHEWORSTOFTIMES";
+        let builder = || RunningKey::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(key);
+
+        let passthrough = builder().build()?;
+        assert_eq!("Uxat ex hx 10:45, cc ythj 3!", passthrough.encrypt(plaintext)?);
+        assert_eq!(plaintext, passthrough.decrypt(&passthrough.encrypt(plaintext)?)?);
+
+        let strip = builder().unknown_char_policy(UnknownCharPolicy::Strip).build()?;
+        assert_eq!("Uxatexhxccythj", strip.encrypt(plaintext)?);
+        assert_eq!("Meetmeatbygate", strip.decrypt(&strip.encrypt(plaintext)?)?);
+
+        let error = builder().unknown_char_policy(UnknownCharPolicy::Error).build()?;
+        assert!(error.encrypt(plaintext).is_err());
+        assert!(error.decrypt("Uxat ex hx 10:45").is_err());
+        assert_eq!("Uxatexhx", error.encrypt("Meetmeat")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn key_with_punctuation() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+        let book = "It was the best of times, it was the worst of times...";
+        let running_key = RunningKey::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(book).build()?;
+        let letters_only = RunningKey::new()
+            .alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+            .key("ITWASTHEBESTOFTIMESITWASTHEWORSTOFTIMES")
+            .build()?;
+
+        assert_eq!("Uxat ex hx 10:45, cc ythj 3!", running_key.encrypt(plaintext)?);
+        assert_eq!(letters_only.encrypt(plaintext)?, running_key.encrypt(plaintext)?);
+        assert_eq!(plaintext, running_key.decrypt(&running_key.encrypt(plaintext)?)?);
+
+        let short = RunningKey::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("It was, the best!").build()?;
+        assert!(short.encrypt(plaintext).is_err());
+        assert!(short.decrypt("Uxat ex hx 10:45, cc ythj 3!").is_err());
+        assert_eq!("Uxatexhx", short.encrypt("Meetmeat")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use cipher_utils::frequency;
+use rand::seq::SliceRandom as _;
+use substitution_cipher::{Substitution, SubstitutionBuilder as _};
+
+/// A cracker for monoalphabetic substitution ciphers. The key is found by hill climbing: Starting from a random key,
+/// pairs of letters are swapped whenever the swap makes the decryption more English-like, until no swap improves it.
+/// This is repeated from several random keys, and the best result is kept.
+#[derive(Debug, Default, Clone)]
+pub struct SubstitutionCracker {
+    /// Letter mappings known in advance, as `(plaintext, ciphertext)` pairs, which are held fixed while hill climbing.
+    known_mapping: Vec<(char, char)>,
+}
+
+/// The result of cracking a substitution cipher with `SubstitutionCracker::crack()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrackedSubstitution {
+    /// The recovered key, as the letters that `A` through `Z` are encrypted into. This can be passed to
+    /// `Substitution::new().key()`.
+    pub key: String,
+
+    /// The ciphertext decrypted with the recovered key.
+    pub plaintext: String,
+
+    /// The number of candidate keys that were scored while hill climbing, which measures how much work cracking took.
+    pub evaluations: usize,
+}
+
+impl SubstitutionCracker {
+    /// The number of random keys that hill climbing is started from.
+    const RESTARTS: usize = 4;
+
+    /// The average quadgram log-probability above which a Caesar shift or Atbash decryption is accepted without hill
+    /// climbing. English text averages around `-4.6`, while text under the wrong key averages around `-9`.
+    const SIMPLE_KEY_THRESHOLD: f64 = -6.;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locks the given letter mappings in the key, such as letters known from a crib. Only the remaining letters are
+    /// permuted while hill climbing, which makes cracking faster and more reliable. Mappings are case-insensitive.
+    ///
+    /// # Parameters
+    /// - `mapping` - The known `(plaintext, ciphertext)` letter pairs.
+    ///
+    /// # Returns
+    /// This cracker with the known mappings added.
+    pub fn with_known_mapping(mut self, mapping: &[(char, char)]) -> Self {
+        self.known_mapping
+            .extend(mapping.iter().map(|(plain, cipher)| (plain.to_ascii_uppercase(), cipher.to_ascii_uppercase())));
+        self
+    }
+
+    /// Cracks the given ciphertext and returns the most English-like decryption found.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    ///
+    /// # Errors
+    /// If the known mappings are invalid; See `SubstitutionCracker::crack()`.
+    pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(self.crack(ciphertext, &mut rand::thread_rng())?.plaintext)
+    }
+
+    /// Cracks the given ciphertext, returning the recovered key along with the decryption.
+    ///
+    /// Every Caesar shift of the alphabet (including ROT13) and its Atbash mirror are tried first, and if one of them
+    /// decrypts the text into something clearly English-like, it's returned right away without hill climbing.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    /// - `rng` - The random number generator to generate the starting keys with.
+    ///
+    /// # Returns
+    /// The recovered key, the decrypted text, and the number of keys scored.
+    ///
+    /// # Errors
+    /// If a known mapping contains a character that's not an English letter, or if the known mappings contradict each
+    /// other, such as by mapping one plaintext letter to two different ciphertext letters.
+    ///
+    /// # Performance
+    /// Each pass of hill climbing scores `O(m^2)` keys for `m` unknown letters, and scoring a key is `O(n)` for a
+    /// ciphertext of length `n`.
+    pub fn crack(&self, ciphertext: &str, rng: &mut impl rand::Rng) -> anyhow::Result<CrackedSubstitution> {
+        // The plaintext letter of each ciphertext letter, as indices into A-Z
+        let mut known: [Option<u8>; 26] = [None; 26];
+        for (plain, cipher) in &self.known_mapping {
+            if !plain.is_ascii_alphabetic() || !cipher.is_ascii_alphabetic() {
+                anyhow::bail!("Invalid known substitution mapping {plain:?} -> {cipher:?}: Mappings must be between English letters.");
+            }
+            let (plain, cipher) = (*plain as u8 - b'A', *cipher as u8 - b'A');
+            let conflict = known
+                .iter()
+                .enumerate()
+                .any(|(other_cipher, other_plain)| other_plain.is_some_and(|other_plain| (other_plain == plain) != (other_cipher == cipher as usize)));
+            if conflict {
+                anyhow::bail!(
+                    "Contradicting known substitution mapping {:?} -> {:?}: One of the letters is already mapped to another letter.",
+                    (plain + b'A') as char,
+                    (cipher + b'A') as char
+                );
+            }
+            known[cipher as usize] = Some(plain);
+        }
+
+        let letters = ciphertext
+            .bytes()
+            .filter(|byte| byte.is_ascii_alphabetic())
+            .map(|byte| byte.to_ascii_uppercase() - b'A')
+            .collect::<Vec<_>>();
+        let unknown_ciphers = (0..26).filter(|cipher| known[*cipher].is_none()).collect::<Vec<_>>();
+        let mut unused_plains = (0..26u8).filter(|plain| !known.contains(&Some(*plain))).collect::<Vec<_>>();
+
+        let mut evaluations = 0;
+        let mut score = |key: &[u8; 26]| {
+            evaluations += 1;
+            frequency::quadgram_score(&letters.iter().map(|letter| (key[*letter as usize] + b'A') as char).collect::<String>())
+        };
+
+        // Shifted and mirrored alphabets are common and cheap to check, so they're tried before hill climbing
+        let shifts = (0..26).map(|shift| std::array::from_fn(|cipher| ((cipher + 26 - shift) % 26) as u8));
+        let atbash = std::iter::once(std::array::from_fn(|cipher| 25 - cipher as u8));
+        let quadgrams = letters.len().saturating_sub(3).max(1) as f64;
+        let mut best = shifts
+            .chain(atbash)
+            .filter(|key: &[u8; 26]| known.iter().zip(key).all(|(known, plain)| known.is_none_or(|known| known == *plain)))
+            .map(|key| (key, score(&key)))
+            .max_by(|(_, first), (_, second)| first.total_cmp(second))
+            .filter(|(_, best_score)| best_score / quadgrams > Self::SIMPLE_KEY_THRESHOLD);
+
+        let restarts = if best.is_some() { 0 } else { Self::RESTARTS };
+        for _ in 0..restarts {
+            unused_plains.shuffle(rng);
+            let mut key = known.map(|plain| plain.unwrap_or_default());
+            for (cipher, plain) in unknown_ciphers.iter().zip(&unused_plains) {
+                key[*cipher] = *plain;
+            }
+
+            let mut current = score(&key);
+            let mut improved = true;
+            while improved {
+                improved = false;
+                for (index, first) in unknown_ciphers.iter().enumerate() {
+                    for second in &unknown_ciphers[index + 1..] {
+                        key.swap(*first, *second);
+                        let swapped = score(&key);
+                        if swapped > current {
+                            current = swapped;
+                            improved = true;
+                        } else {
+                            key.swap(*first, *second);
+                        }
+                    }
+                }
+            }
+
+            if best.is_none_or(|(_, best_score)| current > best_score) {
+                best = Some((key, current));
+            }
+        }
+
+        let (decryption_key, _) = best.unwrap();
+        let mut key = [b'A'; 26];
+        for (cipher, plain) in decryption_key.iter().enumerate() {
+            key[*plain as usize] = cipher as u8 + b'A';
+        }
+        let key = String::from_utf8(key.to_vec())?;
+        let plaintext = Substitution::new().key(&key).build()?.decrypt(ciphertext);
+
+        Ok(CrackedSubstitution { key, plaintext, evaluations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+    use substitution_cipher::{Substitution, SubstitutionBuilder as _};
+
+    use crate::SubstitutionCracker;
+
+    #[test]
+    fn known_mapping() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let ciphertext = Substitution::new().keyword("ZEBRAS").build()?.encrypt(plaintext);
+        let known = [('E', 'A'), ('T', 'Q'), ('h', 'd')];
+
+        let without_mapping = SubstitutionCracker::new().crack(&ciphertext, &mut rand::rngs::StdRng::seed_from_u64(1))?;
+        let with_mapping = SubstitutionCracker::new()
+            .with_known_mapping(&known)
+            .crack(&ciphertext, &mut rand::rngs::StdRng::seed_from_u64(1))?;
+
+        for (plain, cipher) in known {
+            assert_eq!(
+                cipher.to_ascii_uppercase(),
+                with_mapping.key.as_bytes()[(plain.to_ascii_uppercase() as u8 - b'A') as usize] as char
+            );
+        }
+        assert_eq!(plaintext, with_mapping.plaintext);
+        assert!(with_mapping.evaluations < without_mapping.evaluations);
+
+        assert!(SubstitutionCracker::new().with_known_mapping(&[('E', 'A'), ('T', 'A')]).decrypt(&ciphertext).is_err());
+        assert!(SubstitutionCracker::new().with_known_mapping(&[('E', 'A'), ('E', 'B')]).decrypt(&ciphertext).is_err());
+        assert!(SubstitutionCracker::new().with_known_mapping(&[('E', '1')]).decrypt(&ciphertext).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn simple_keys() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let rot13 = "NOPQRSTUVWXYZABCDEFGHIJKLM";
+        let atbash = "ZYXWVUTSRQPONMLKJIHGFEDCBA";
+
+        // Only the 26 shifts and the mirror are scored when one of them is the key
+        for key in [rot13, atbash] {
+            let ciphertext = Substitution::new().key(key).build()?.encrypt(plaintext);
+            let cracked = SubstitutionCracker::new().crack(&ciphertext, &mut rand::rngs::StdRng::seed_from_u64(1))?;
+            assert_eq!(key, cracked.key);
+            assert_eq!(plaintext, cracked.plaintext);
+            assert_eq!(27, cracked.evaluations);
+        }
+
+        let ciphertext = Substitution::new().keyword("ZEBRAS").build()?.encrypt(plaintext);
+        assert!(SubstitutionCracker::new().crack(&ciphertext, &mut rand::rngs::StdRng::seed_from_u64(1))?.evaluations > 27);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use cipher_utils::alphabet::Alphabet;
+
+/// A monoalphabetic substitution cipher. Each letter of the plaintext alphabet is replaced with the letter at the same
+/// position in the key alphabet.
+pub struct Substitution {
+    /// The alphabet that the letters of the plaintext are mapped to; The `n`th letter of the English alphabet is
+    /// encrypted as the `n`th letter of this alphabet.
+    key: Alphabet,
+}
+
+impl Substitution {
+    /// Encrypts the given plaintext. Casing is preserved, and characters that aren't English letters are left unchanged.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    ///
+    /// # Returns
+    /// The encrypted text.
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        Self::substitute(plaintext, &Alphabet::default(), &self.key)
+    }
+
+    /// Decrypts the given ciphertext. Casing is preserved, and characters that aren't English letters are left unchanged.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// The decrypted text.
+    pub fn decrypt(&self, ciphertext: &str) -> String {
+        Self::substitute(ciphertext, &self.key, &Alphabet::default())
+    }
+
+    /// Replaces each letter of the given text from one alphabet with the letter at the same position in another.
+    ///
+    /// # Parameters
+    /// - `text` - The text to substitute the letters of.
+    /// - `from` - The alphabet that the letters of the text are in.
+    /// - `to` - The alphabet to substitute the letters with.
+    ///
+    /// # Returns
+    /// The substituted text.
+    fn substitute(text: &str, from: &Alphabet, to: &Alphabet) -> String {
+        text.chars()
+            .map(|character| {
+                let Some(index) = from.index_of(character) else {
+                    return character;
+                };
+                let substituted = to.letter_at(index);
+                if character.is_uppercase() {
+                    substituted.to_ascii_uppercase()
+                } else {
+                    substituted.to_ascii_lowercase()
+                }
+            })
+            .collect()
+    }
+}
+
+pub trait SubstitutionBuilder {
+    /// Sets the key of the cipher to the given alphabet, which must be a permutation of the English alphabet.
+    fn key<T: AsRef<str>>(self, key: T) -> impl SubstitutionBuilder;
+
+    /// Sets the key of the cipher to the keyed alphabet generated from the given keyword, i.e., the unique letters of
+    /// the keyword followed by the rest of the alphabet in order. See `Alphabet::from_keyword()`.
+    fn keyword<T: AsRef<str>>(self, keyword: T) -> impl SubstitutionBuilder;
+
+    fn build(self) -> anyhow::Result<Substitution>;
+}
+
+#[derive(Debug, Default)]
+struct IncompleteSubstitution {
+    key: Option<Alphabet>,
+}
+
+impl SubstitutionBuilder for anyhow::Result<IncompleteSubstitution> {
+    fn key<T: AsRef<str>>(self, key: T) -> impl SubstitutionBuilder {
+        if let Ok(mut substitution) = self {
+            let key = Alphabet::caseless(key.as_ref())?;
+            if !key.is_standard_permutation() {
+                anyhow::bail!("Invalid key: {:?} is not a permutation of the English alphabet", key.characters().iter().collect::<String>());
+            }
+            substitution.key = Some(key);
+            Ok(substitution)
+        } else {
+            self
+        }
+    }
+
+    fn keyword<T: AsRef<str>>(self, keyword: T) -> impl SubstitutionBuilder {
+        if let Ok(mut substitution) = self {
+            substitution.key = Some(Alphabet::from_keyword(keyword.as_ref())?);
+            Ok(substitution)
+        } else {
+            self
+        }
+    }
+
+    fn build(self) -> anyhow::Result<Substitution> {
+        if let Ok(substitution) = self {
+            let Some(key) = substitution.key else {
+                anyhow::bail!("Error building Substitution: No key provided.");
+            };
+
+            Ok(Substitution { key })
+        } else {
+            Err(self.unwrap_err())
+        }
+    }
+}
+
+impl Substitution {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> impl SubstitutionBuilder {
+        Ok(IncompleteSubstitution::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Substitution, SubstitutionBuilder as _};
+
+    #[test]
+    fn encrypt_decrypt() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let substitution = Substitution::new().keyword("ZEBRAS").build()?;
+
+        assert_eq!("Siaa zq lkba, va zoa rfpbluaoar!", substitution.encrypt("Flee at once, we are discovered!"));
+        assert_eq!(plaintext, substitution.decrypt(&substitution.encrypt(plaintext)));
+
+        let explicit = Substitution::new().key("ZEBRASCDFGHIJKLMNOPQTUVWXY").build()?;
+        assert_eq!(substitution.encrypt(plaintext), explicit.encrypt(plaintext));
+
+        assert!(Substitution::new().build().is_err());
+        assert!(Substitution::new().key("ABC").build().is_err());
+
+        Ok(())
+    }
+}
@@ -1,14 +1,428 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
+use cipher_utils::{
+    alphabet::{Alphabet, AlphabetIndex},
+    frequency, Analyze as _,
+};
+use rand::seq::SliceRandom as _;
+use vigenere_lib::{Vigenere, VigenereBuilder as _};
+
+/// A cracker for Vigenere ciphers, including ones over scrambled alphabets.
+#[derive(Debug, Default, Clone)]
+pub struct VigenereCracker {
+    /// The key of the cipher, if it's known.
+    key: Option<String>,
+
+    /// The alphabet of the cipher, if it's known.
+    alphabet: Option<Alphabet>,
+}
+
+impl VigenereCracker {
+    /// The most times simulated annealing is started from a random alphabet.
+    const RESTARTS: usize = 12;
+
+    /// The number of random changes tried in each run of simulated annealing.
+    const ANNEALING_STEPS: usize = 1_000_000;
+
+    /// The temperature that simulated annealing starts at, which decreases exponentially to `FINAL_TEMPERATURE`.
+    const INITIAL_TEMPERATURE: f64 = 30.;
+
+    /// The temperature that simulated annealing ends at.
+    const FINAL_TEMPERATURE: f64 = 2.;
+
+    /// The largest key length that `VigenereCracker::crack_key()` checks.
+    const MAX_KEY_LENGTH: usize = 20;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cracker for a cipher whose key is known, but whose alphabet may be scrambled. See
+    /// `VigenereCracker::crack_alphabet()`.
+    ///
+    /// # Parameters
+    /// - `key` - The key of the cipher.
+    ///
+    /// # Returns
+    /// The created cracker.
+    pub fn with_known_key<T: AsRef<str>>(key: T) -> Self {
+        Self {
+            key: Some(key.as_ref().to_ascii_uppercase()),
+            alphabet: None,
+        }
+    }
+
+    /// Creates a cracker for a cipher whose alphabet is known, but whose key isn't. See `VigenereCracker::crack_key()`.
+    ///
+    /// # Parameters
+    /// - `alphabet` - The alphabet of the cipher.
+    ///
+    /// # Returns
+    /// The created cracker.
+    ///
+    /// # Errors
+    /// If the alphabet isn't a permutation of the English alphabet.
+    pub fn with_known_alphabet<T: AsRef<str>>(alphabet: T) -> anyhow::Result<Self> {
+        let alphabet = Alphabet::caseless(alphabet.as_ref())?;
+        if !alphabet.is_standard_permutation() {
+            anyhow::bail!(
+                "Invalid alphabet: {:?} is not a permutation of the English alphabet",
+                alphabet.characters().iter().collect::<String>()
+            );
+        }
+
+        Ok(Self {
+            key: None,
+            alphabet: Some(alphabet),
+        })
+    }
+
+    /// Finds the key of a Vigenere cipher with a known alphabet. The key length is the shortest period whose columns
+    /// have an index of coincidence close to the best of any period up to 20, and each letter of the key is the one
+    /// whose decryption of its column has the most English-like letter frequencies.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt. Longer texts are cracked more reliably, and the text should have several
+    ///   times as many letters as the key.
+    ///
+    /// # Returns
+    /// The recovered key and the ciphertext decrypted with it.
+    ///
+    /// # Errors
+    /// If the alphabet isn't known, or if the ciphertext has no letters.
+    ///
+    /// # Performance
+    /// This is `O(n)` for a ciphertext of length `n`.
+    pub fn crack_key(&self, ciphertext: &str) -> anyhow::Result<(String, String)> {
+        let Some(alphabet) = &self.alphabet else {
+            anyhow::bail!("Cracking a Vigenere key requires a known alphabet");
+        };
+
+        let letters = ciphertext
+            .chars()
+            .filter_map(|character| alphabet.index_of(character))
+            .map(|index| *index as usize - 1)
+            .collect::<Vec<_>>();
+        if letters.is_empty() {
+            anyhow::bail!("Error cracking Vigenere key: The ciphertext has no letters.");
+        }
+
+        // Multiples of the key length score just as well as the key length itself, so the shortest good period is used.
+        let profile = ciphertext.period_ioc_profile(Self::MAX_KEY_LENGTH.min(letters.len()));
+        let best = profile.iter().map(|(_, ioc)| *ioc).fold(0., f64::max);
+        let period = profile.into_iter().find(|(_, ioc)| *ioc >= 0.9 * best).map_or(1, |(period, _)| period);
+
+        let english = frequency::english_uppercase();
+        let log_frequencies: [f64; 26] = std::array::from_fn(|position| {
+            let letter = alphabet.letter_at(AlphabetIndex::new(position as u8 + 1).unwrap());
+            english[&letter.to_ascii_uppercase()].max(0.01).ln()
+        });
+
+        // Each plaintext letter is as far before its ciphertext letter in the alphabet as the key letter is from the start
+        // of the alphabet, so each column's key letter is the shift whose decryption is the most likely to be English.
+        let shifts = (0..period)
+            .map(|column| {
+                let mut counts = [0usize; 26];
+                for letter in letters.iter().skip(column).step_by(period) {
+                    counts[*letter] += 1;
+                }
+                let score = |shift: usize| (0..26).map(|letter| counts[letter] as f64 * log_frequencies[(letter + 26 - shift) % 26]).sum::<f64>();
+                (0..26).max_by(|first, other| score(*first).total_cmp(&score(*other))).unwrap() as u8
+            })
+            .collect::<Vec<_>>();
+        let key = Self::shifts_to_key(&shifts, alphabet);
+
+        let plaintext = Vigenere::new()
+            .alphabet(alphabet.characters().iter().collect::<String>())
+            .key(&key)
+            .build()?
+            .decrypt(ciphertext)?;
+        Ok((key, plaintext))
+    }
+
+    /// Converts the shift of each column of a Vigenere cipher back into its key. A shift of `n` is the letter `n` places
+    /// after the start of the alphabet, so a shift of `0` is the alphabet's first letter.
+    ///
+    /// # Parameters
+    /// - `shifts` - The shift of each column, in order. Shifts of 26 or more wrap around the alphabet.
+    /// - `alphabet` - The alphabet of the cipher.
+    ///
+    /// # Returns
+    /// The key, in uppercase.
+    pub fn shifts_to_key(shifts: &[u8], alphabet: &Alphabet) -> String {
+        shifts
+            .iter()
+            .map(|shift| alphabet.letter_at(AlphabetIndex::new(shift % 26 + 1).unwrap()).to_ascii_uppercase())
+            .collect()
+    }
+
+    /// Finds the scrambled alphabet of a Vigenere cipher with a known key. The alphabet is found by simulated annealing
+    /// on the letter frequencies of the decryption, which is restarted from random alphabets until two runs agree on the
+    /// best alphabet.
+    ///
+    /// Multiplying every position in an alphabet by a number coprime to 26 gives an alphabet that decrypts every text the
+    /// same way with the same key, so the alphabet can only be recovered up to such a "decimation". The returned alphabet
+    /// may differ from the one that the text was encrypted with, but the returned plaintext won't.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt. Longer texts are cracked more reliably.
+    ///
+    /// # Returns
+    /// The recovered alphabet and the ciphertext decrypted with it.
+    ///
+    /// # Errors
+    /// If the key isn't known, or if it's empty or contains a character that's not an English letter.
+    ///
+    /// # Performance
+    /// Each run of simulated annealing scores a fixed number of alphabets, and scoring an alphabet is `O(k)` for a key
+    /// with `k` distinct letters.
+    pub fn crack_alphabet(&self, ciphertext: &str) -> anyhow::Result<(Alphabet, String)> {
+        self.crack_alphabet_with_rng(ciphertext, &mut rand::thread_rng())
+    }
+
+    /// See `VigenereCracker::crack_alphabet()`.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    /// - `rng` - The random number generator to generate the starting alphabets and changes with.
+    ///
+    /// # Returns
+    /// The recovered alphabet and the ciphertext decrypted with it.
+    ///
+    /// # Errors
+    /// If the key isn't known, or if it's empty or contains a character that's not an English letter.
+    pub fn crack_alphabet_with_rng(&self, ciphertext: &str, rng: &mut impl rand::Rng) -> anyhow::Result<(Alphabet, String)> {
+        let Some(key) = &self.key else {
+            anyhow::bail!("Cracking a Vigenere alphabet requires a known key");
+        };
+        if key.is_empty() || !key.bytes().all(|byte| byte.is_ascii_alphabetic()) {
+            anyhow::bail!("Invalid Vigenere key {key:?}: Keys must be made of English letters.");
+        }
+
+        let key_letters = key.bytes().map(|byte| (byte - b'A') as usize).collect::<Vec<_>>();
+        let mut counts = [[0usize; 26]; 26];
+        for (index, letter) in ciphertext
+            .bytes()
+            .filter(|byte| byte.is_ascii_alphabetic())
+            .map(|byte| (byte.to_ascii_uppercase() - b'A') as usize)
+            .enumerate()
+        {
+            counts[key_letters[index % key_letters.len()]][letter] += 1;
+        }
+
+        // The number of times each letter is encrypted with each key letter, as `(key letter, letter, count)`
+        let counts = (0..26)
+            .flat_map(|key_letter| (0..26).map(move |letter| (key_letter, letter)))
+            .filter(|(key_letter, letter)| counts[*key_letter][*letter] != 0)
+            .map(|(key_letter, letter)| (key_letter, letter, counts[key_letter][letter] as f64))
+            .collect::<Vec<_>>();
+        let english = frequency::english_uppercase();
+        let log_frequencies: [f64; 26] = std::array::from_fn(|letter| english[&((letter as u8 + b'A') as char)].max(0.01).ln());
+
+        // The log-likelihood of the letter frequencies of the decryption. The plaintext letter of each ciphertext letter is
+        // the letter of the alphabet that's as far before the ciphertext letter as the key letter is from the start of the
+        // alphabet, so this only depends on how often each letter is encrypted with each key letter.
+        let score = |alphabet: &[u8; 26]| {
+            let positions = positions_of(alphabet);
+            let log_frequencies: [f64; 52] = std::array::from_fn(|position| log_frequencies[alphabet[position % 26] as usize]);
+            counts
+                .iter()
+                .map(|(key_letter, letter, count)| count * log_frequencies[positions[*letter] + 26 - positions[*key_letter]])
+                .sum::<f64>()
+        };
+
+        // Moving a key letter shifts every letter encrypted with it, so hill climbing gets stuck easily. Instead, each run
+        // anneals a random alphabet and then polishes it. Alphabets that decrypt identically score identically, so once two
+        // runs reach the same best score, it's very likely the true alphabet's.
+        let mut best: Option<([u8; 26], f64)> = None;
+        for _ in 0..Self::RESTARTS {
+            let mut alphabet: [u8; 26] = std::array::from_fn(|letter| letter as u8);
+            alphabet.shuffle(rng);
+            let mut current = score(&alphabet);
+            for step in 0..Self::ANNEALING_STEPS {
+                let temperature = Self::INITIAL_TEMPERATURE * (Self::FINAL_TEMPERATURE / Self::INITIAL_TEMPERATURE).powf(step as f64 / Self::ANNEALING_STEPS as f64);
+                let previous = alphabet;
+
+                // Besides moving letters around, half of the alphabet is sometimes shifted as a whole, since alphabets that
+                // are right on every other position score well but are many small changes away from the true one.
+                let (from, to) = (rng.gen_range(0..26), rng.gen_range(0..26));
+                match rng.gen_range(0..5) {
+                    0 => alphabet.swap(from, to),
+                    1 => alphabet[from.min(to)..=from.max(to)].reverse(),
+                    2 if from < to => alphabet[from..=to].rotate_left(1),
+                    2 => alphabet[to..=from].rotate_right(1),
+                    3 => rotate_parity(&mut alphabet, from % 2, if to % 2 == 0 { 1 } else { 12 }),
+                    _ => alphabet.rotate_left(13),
+                }
+
+                let changed = score(&alphabet);
+                if changed >= current || rng.gen::<f64>() < ((changed - current) / temperature).exp() {
+                    current = changed;
+                } else {
+                    alphabet = previous;
+                }
+            }
+            let current = polish(&mut alphabet, score);
+
+            match best {
+                Some((_, best_score)) if (current - best_score).abs() < 1e-6 => break,
+                Some((_, best_score)) if current < best_score => {}
+                _ => best = Some((alphabet, current)),
+            }
+        }
+
+        let (alphabet, _) = best.unwrap();
+        let alphabet = alphabet.iter().map(|letter| (letter + b'A') as char).collect::<String>();
+        let plaintext = Vigenere::new().alphabet(&alphabet).key(key).build()?.decrypt(ciphertext)?;
+        Ok((Alphabet::caseless(&alphabet)?, plaintext))
+    }
+}
+
+/// Returns the position of each letter in the given alphabet.
+///
+/// # Parameters
+/// - `alphabet` - The alphabet, as the indices of its letters in `A-Z`.
+///
+/// # Returns
+/// The index of each letter of `A-Z` in the alphabet.
+fn positions_of(alphabet: &[u8; 26]) -> [usize; 26] {
+    let mut positions = [0; 26];
+    for (position, letter) in alphabet.iter().enumerate() {
+        positions[*letter as usize] = position;
+    }
+    positions
+}
+
+/// Rotates the letters at either the even or the odd positions of the given alphabet to the left, leaving the other
+/// positions unchanged.
+///
+/// # Parameters
+/// - `alphabet` - The alphabet to change, as the indices of its letters in `A-Z`.
+/// - `parity` - `0` to rotate the letters at even positions, or `1` to rotate the ones at odd positions.
+/// - `shift` - The number of letters to rotate by.
+fn rotate_parity(alphabet: &mut [u8; 26], parity: usize, shift: usize) {
+    let mut letters = alphabet.iter().skip(parity).step_by(2).copied().collect::<Vec<_>>();
+    letters.rotate_left(shift);
+    for (position, letter) in (parity..26).step_by(2).zip(letters) {
+        alphabet[position] = letter;
+    }
+}
+
+/// Improves the given alphabet by trying every swap of two letters, every rotation of the letters at even or odd
+/// positions, and every way of swapping letters with the ones 13 positions away, and keeping any change that increases
+/// its score, until no change does.
+///
+/// # Parameters
+/// - `alphabet` - The alphabet to improve, as the indices of its letters in `A-Z`.
+/// - `score` - The function to score alphabets with.
+///
+/// # Returns
+/// The score of the improved alphabet.
+fn polish(alphabet: &mut [u8; 26], score: impl Fn(&[u8; 26]) -> f64) -> f64 {
+    let mut current = score(alphabet);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        let swaps = (0..26).flat_map(|first| (first + 1..26).map(move |second| (first, second))).map(|(first, second)| {
+            let mut changed = *alphabet;
+            changed.swap(first, second);
+            changed
+        });
+        let rotations = (0..2).flat_map(|parity| (1..13).map(move |shift| (parity, shift))).map(|(parity, shift)| {
+            let mut changed = *alphabet;
+            rotate_parity(&mut changed, parity, shift);
+            changed
+        });
+        let halves = (1..1u32 << 13).map(|halves| {
+            let mut changed = *alphabet;
+            for position in (0..13).filter(|position| halves & (1 << position) != 0) {
+                changed.swap(position, position + 13);
+            }
+            changed
+        });
+
+        let changes = swaps.chain(rotations).chain(halves).collect::<Vec<_>>();
+        for changed in changes {
+            let changed_score = score(&changed);
+            if changed_score > current {
+                *alphabet = changed;
+                current = changed_score;
+                improved = true;
+                break;
+            }
+        }
+    }
+    current
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use rand::SeedableRng as _;
+
+    use crate::VigenereCracker;
+
+    #[test]
+    fn crack_alphabet() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let ciphertext = include_str!("../tests/encrypted_letter.txt");
+        let true_alphabet = "AYCDWZIHGJKLQNOPMVSTXREUBF".chars().collect::<Vec<_>>();
+
+        let (alphabet, decrypted) = VigenereCracker::with_known_key("MYSUPERTOPSECRETKEY").crack_alphabet_with_rng(ciphertext, &mut rand::rngs::StdRng::seed_from_u64(1))?;
+        assert_eq!(plaintext, decrypted);
+
+        // The recovered alphabet is the true one with its positions multiplied by a number coprime to 26
+        let alphabet = alphabet.characters();
+        assert!((1..26)
+            .filter(|multiplier| multiplier % 2 != 0 && multiplier % 13 != 0)
+            .any(|multiplier| (0..26).all(|position| alphabet[position] == true_alphabet[position * multiplier % 26])));
+
+        assert!(VigenereCracker::new().crack_alphabet(ciphertext).is_err());
+        assert!(VigenereCracker::with_known_key("MY KEY").crack_alphabet(ciphertext).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn crack_key() -> anyhow::Result<()> {
+        use vigenere_lib::{Vigenere, VigenereBuilder as _};
+
+        let plaintext = include_str!("../tests/letter.txt");
+        for (alphabet, key) in [
+            ("ABCDEFGHIJKLMNOPQRSTUVWXYZ", "LEMON"),
+            ("AYCDWZIHGJKLQNOPMVSTXREUBF", "MYSUPERTOPSECRETKEY"),
+            ("ABCDEFGHIJKLMNOPQRSTUVWXYZ", "Q"),
+        ] {
+            let ciphertext = Vigenere::new().alphabet(alphabet).key(key).build()?.encrypt(plaintext)?;
+            assert_eq!(
+                (key.to_owned(), plaintext.to_owned()),
+                VigenereCracker::with_known_alphabet(alphabet)?.crack_key(&ciphertext)?
+            );
+        }
+
+        assert!(VigenereCracker::with_known_key("LEMON").crack_key(plaintext).is_err());
+        assert!(VigenereCracker::with_known_alphabet("ABC").is_err());
+        assert!(VigenereCracker::with_known_alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ")?.crack_key("123 456").is_err());
+
+        Ok(())
+    }
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn shifts_to_key() -> anyhow::Result<()> {
+        use cipher_utils::alphabet::Alphabet;
+        use vigenere_lib::{Vigenere, VigenereBuilder as _};
+
+        let plaintext = include_str!("../tests/letter.txt");
+        let standard = Alphabet::caseless("ABCDEFGHIJKLMNOPQRSTUVWXYZ")?;
+        assert_eq!("LEMON", VigenereCracker::shifts_to_key(&[11, 4, 12, 14, 13], &standard));
+        assert_eq!("AZ", VigenereCracker::shifts_to_key(&[26, 51], &standard));
+        assert_eq!("", VigenereCracker::shifts_to_key(&[], &standard));
+
+        let scrambled = "AYCDWZIHGJKLQNOPMVSTXREUBF";
+        assert_eq!("AYW", VigenereCracker::shifts_to_key(&[0, 1, 4], &Alphabet::caseless(scrambled)?));
+
+        // Encrypting the plaintext with the key recovered from the shifts gives back the ciphertext
+        let ciphertext = Vigenere::new().alphabet(scrambled).key("MYSUPERTOPSECRETKEY").build()?.encrypt(plaintext)?;
+        let (key, decrypted) = VigenereCracker::with_known_alphabet(scrambled)?.crack_key(&ciphertext)?;
+        assert_eq!(ciphertext, Vigenere::new().alphabet(scrambled).key(&key).build()?.encrypt(&decrypted)?);
+
+        Ok(())
     }
 }
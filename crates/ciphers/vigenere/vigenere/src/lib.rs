@@ -1,63 +1,154 @@
-use cipher_utils::alphabet::Alphabet;
+mod weakness_report;
+
+pub use weakness_report::{KeyWeakness, WeaknessReport, SHORT_KEY_REUSES};
+
+use cipher_utils::{alphabet::Alphabet, cipher::Cipher, unknown_char_policy::UnknownCharPolicy};
 
 pub struct Vigenere {
     alphabet: Alphabet,
     key: String,
+    unknown_char_policy: UnknownCharPolicy,
+    advance_key_on_all_chars: bool,
 }
 
 impl Vigenere {
-    pub fn encrypt(&self, plaintext: &str) -> String {
-        let repeated_key = self.key.repeat(plaintext.len() / self.key.len());
-        let key_bytes = repeated_key.as_bytes();
+    pub fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        let key_bytes = self.key.as_bytes();
         let mut index = 0;
         plaintext
             .chars()
-            .map(|plain_char| {
-                if !plain_char.is_alphabetic() {
-                    return plain_char;
-                }
-                let key_char = key_bytes[index] as char;
-                let plaintext_index = self.alphabet.index_of(plain_char).unwrap();
+            .filter_map(|plain_char| {
+                let Some(plaintext_index) = self.alphabet.index_of(plain_char) else {
+                    if self.advance_key_on_all_chars {
+                        index += 1;
+                    }
+                    return self.unknown_char_policy.apply(plain_char).transpose();
+                };
+                let key_char = key_bytes[index % key_bytes.len()] as char;
                 let key_index = self.alphabet.index_of(key_char).unwrap();
                 let result = self.alphabet.letter_at(plaintext_index + key_index - 1);
                 index += 1;
-                if plain_char.is_uppercase() {
+                Some(Ok(if plain_char.is_uppercase() {
                     result.to_ascii_uppercase()
                 } else {
                     result.to_ascii_lowercase()
-                }
+                }))
             })
             .collect()
     }
 
-    pub fn decrypt(&self, ciphertext: &str) -> String {
-        let repeated_key = self.key.repeat(ciphertext.len() / self.key.len());
-        let key_bytes = repeated_key.as_bytes();
+    pub fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Ok(self.decrypt_traced(ciphertext)?.into_iter().map(|(_, _, plain_char)| plain_char).collect())
+    }
+
+    /// Decrypts the given ciphertext, returning how each character was decrypted. This exposes the alignment of the
+    /// key against the ciphertext, which is useful for visualizing the cipher.
+    ///
+    /// Characters that aren't in the alphabet don't consume a key character, unless the cipher was built with
+    /// `VigenereBuilder::advance_key_on_all_chars()`; If they're kept by the unknown character policy, they appear in the
+    /// trace unchanged with a key character of `'\0'`.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The text to decrypt.
+    ///
+    /// # Returns
+    /// A `(ciphertext_char, key_char, plaintext_char)` triple for each character of the decrypted output.
+    ///
+    /// # Errors
+    /// If the ciphertext contains a character that's not in the alphabet and the unknown character policy is
+    /// `UnknownCharPolicy::Error`.
+    pub fn decrypt_traced(&self, ciphertext: &str) -> anyhow::Result<Vec<(char, char, char)>> {
+        let key_bytes = self.key.as_bytes();
         let mut index = 0;
         ciphertext
             .chars()
-            .map(|cipher_char| {
-                if !cipher_char.is_alphabetic() {
-                    return cipher_char;
-                }
-                let key_char = key_bytes[index] as char;
-                let ciphertext_index = self.alphabet.index_of(cipher_char).unwrap();
+            .filter_map(|cipher_char| {
+                let Some(ciphertext_index) = self.alphabet.index_of(cipher_char) else {
+                    if self.advance_key_on_all_chars {
+                        index += 1;
+                    }
+                    return self
+                        .unknown_char_policy
+                        .apply(cipher_char)
+                        .transpose()
+                        .map(|character| character.map(|character| (cipher_char, '\0', character)));
+                };
+                let key_char = key_bytes[index % key_bytes.len()] as char;
                 let key_index = self.alphabet.index_of(key_char).unwrap();
                 let result = self.alphabet.letter_at(ciphertext_index - key_index + 1);
                 index += 1;
-                if cipher_char.is_uppercase() {
-                    result.to_ascii_uppercase()
-                } else {
-                    result.to_ascii_lowercase()
-                }
+                Some(Ok((
+                    cipher_char,
+                    key_char,
+                    if cipher_char.is_uppercase() {
+                        result.to_ascii_uppercase()
+                    } else {
+                        result.to_ascii_lowercase()
+                    },
+                )))
             })
             .collect()
     }
+
+    /// Returns the length of this cipher's key after removing internal repetition. A key that's a shorter key repeated,
+    /// such as `"ABAB"`, encrypts identically to the shorter key, so its effective period is the shorter key's length,
+    /// which is `2` in this case.
+    ///
+    /// # Returns
+    /// The length of the shortest key that encrypts identically to this one.
+    pub fn effective_period(&self) -> usize {
+        let key = self.key.chars().map(|key_char| self.alphabet.index_of(key_char)).collect::<Vec<_>>();
+        (1..key.len())
+            .filter(|period| key.len() % period == 0)
+            .find(|period| (0..key.len()).all(|index| key[index] == key[index % period]))
+            .unwrap_or(key.len())
+    }
+
+    /// Returns a report of the weaknesses of this cipher's key for encrypting the given text, such as whether the key
+    /// repeats itself or is short enough relative to the text that its period is easy to find. This explains why a
+    /// Vigenere ciphertext is crackable.
+    ///
+    /// # Parameters
+    /// - `ciphertext` - The ciphertext (or plaintext) the key is used for. Only its length matters.
+    ///
+    /// # Returns
+    /// The weakness report.
+    pub fn weakness_report(&self, ciphertext: &str) -> WeaknessReport {
+        let key_length = self.key.chars().count();
+        let effective_period = self.effective_period();
+        let text_length = ciphertext.chars().filter(|character| self.alphabet.index_of(*character).is_some()).count();
+
+        let mut weaknesses = Vec::new();
+        if effective_period < key_length {
+            weaknesses.push(KeyWeakness::RepeatingKey { key_length, effective_period });
+        }
+        if text_length >= SHORT_KEY_REUSES * effective_period {
+            weaknesses.push(KeyWeakness::ShortKey { effective_period, text_length });
+        }
+
+        WeaknessReport {
+            key_length,
+            effective_period,
+            text_length,
+            weaknesses,
+        }
+    }
 }
 
 pub trait VigenereBuilder {
     fn alphabet<T: AsRef<str>>(self, alphabet: T) -> impl VigenereBuilder;
     fn key<T: AsRef<str>>(self, key: T) -> impl VigenereBuilder;
+
+    /// Sets what the cipher does with characters that aren't in its alphabet. By default, they're
+    /// passed through to the output unchanged.
+    fn unknown_char_policy(self, policy: UnknownCharPolicy) -> impl VigenereBuilder;
+
+    /// Makes the key advance on every character of the text, including characters that aren't in the alphabet, such
+    /// as spaces and punctuation. By default, the key only advances on letters of the alphabet, which is the usual
+    /// convention; This is for ciphertexts whose key also advanced over the characters between letters, such as puzzles
+    /// that were encrypted with their spaces and then had them removed or kept.
+    fn advance_key_on_all_chars(self) -> impl VigenereBuilder;
+
     fn build(self) -> anyhow::Result<Vigenere>;
 }
 
@@ -65,6 +156,8 @@ pub trait VigenereBuilder {
 struct IncompleteVigenere {
     key: Option<String>,
     alphabet: Option<Alphabet>,
+    unknown_char_policy: UnknownCharPolicy,
+    advance_key_on_all_chars: bool,
 }
 
 impl VigenereBuilder for anyhow::Result<IncompleteVigenere> {
@@ -79,7 +172,32 @@ impl VigenereBuilder for anyhow::Result<IncompleteVigenere> {
 
     fn alphabet<T: AsRef<str>>(self, alphabet: T) -> impl VigenereBuilder {
         if let Ok(mut vigenere) = self {
-            vigenere.alphabet = Some(Alphabet::caseless(alphabet.as_ref())?);
+            let alphabet = Alphabet::caseless(alphabet.as_ref())?;
+            if !alphabet.is_standard_permutation() {
+                anyhow::bail!(
+                    "Invalid alphabet: {:?} is not a permutation of the English alphabet",
+                    alphabet.characters().iter().collect::<String>()
+                );
+            }
+            vigenere.alphabet = Some(alphabet);
+            Ok(vigenere)
+        } else {
+            self
+        }
+    }
+
+    fn unknown_char_policy(self, policy: UnknownCharPolicy) -> impl VigenereBuilder {
+        if let Ok(mut vigenere) = self {
+            vigenere.unknown_char_policy = policy;
+            Ok(vigenere)
+        } else {
+            self
+        }
+    }
+
+    fn advance_key_on_all_chars(self) -> impl VigenereBuilder {
+        if let Ok(mut vigenere) = self {
+            vigenere.advance_key_on_all_chars = true;
             Ok(vigenere)
         } else {
             self
@@ -96,7 +214,20 @@ impl VigenereBuilder for anyhow::Result<IncompleteVigenere> {
                 anyhow::bail!("Error building Vigenere: No alphabet provided.");
             };
 
-            Ok(Vigenere { alphabet, key })
+            if key.is_empty() {
+                anyhow::bail!("Error building Vigenere: Key is empty.");
+            }
+
+            if let Some(key_char) = key.chars().find(|key_char| alphabet.index_of(*key_char).is_none()) {
+                anyhow::bail!("Error building Vigenere: Key character {key_char:?} is not in the alphabet.");
+            }
+
+            Ok(Vigenere {
+                alphabet,
+                key,
+                unknown_char_policy: vigenere.unknown_char_policy,
+                advance_key_on_all_chars: vigenere.advance_key_on_all_chars,
+            })
         } else {
             Err(self.unwrap_err())
         }
@@ -108,11 +239,42 @@ impl Vigenere {
     pub fn new() -> impl VigenereBuilder {
         Ok(IncompleteVigenere::default())
     }
+
+    /// Encrypts the given plaintext with a randomly generated key, and returns the key along with the ciphertext. Unlike
+    /// a one-time pad, the key is given to the caller, and may be shorter than the plaintext.
+    ///
+    /// # Parameters
+    /// - `plaintext` - The text to encrypt.
+    /// - `key_len` - The number of letters in the generated key.
+    /// - `alphabet` - The alphabet of the cipher, which the key's letters are chosen from.
+    ///
+    /// # Returns
+    /// The ciphertext and the generated key.
+    ///
+    /// # Errors
+    /// If `key_len` is `0`, or the alphabet isn't a permutation of the English alphabet.
+    pub fn encrypt_with_random_key<T: AsRef<str>>(plaintext: &str, key_len: usize, alphabet: T) -> anyhow::Result<(String, String)> {
+        let key = Alphabet::caseless(alphabet.as_ref())?.random_key(key_len, &mut rand::thread_rng());
+        let ciphertext = Vigenere::new().alphabet(alphabet).key(&key).build()?.encrypt(plaintext)?;
+        Ok((ciphertext, key))
+    }
+}
+
+impl Cipher for Vigenere {
+    fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        Vigenere::encrypt(self, plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> anyhow::Result<String> {
+        Vigenere::decrypt(self, ciphertext)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Vigenere, VigenereBuilder as _};
+    use cipher_utils::unknown_char_policy::UnknownCharPolicy;
+
+    use crate::{KeyWeakness, Vigenere, VigenereBuilder as _};
 
     #[test]
     fn encrypt_decrypt() -> anyhow::Result<()> {
@@ -121,8 +283,146 @@ mod tests {
 
         let vigenere = Vigenere::new().alphabet("AYCDWZIHGJKLQNOPMVSTXREUBF").key("MYSUPERTOPSECRETKEY").build()?;
 
-        assert_eq!(ciphertext, vigenere.encrypt(plaintext));
-        assert_eq!(plaintext, vigenere.decrypt(ciphertext));
+        assert_eq!(ciphertext, vigenere.encrypt(plaintext)?);
+        assert_eq!(plaintext, vigenere.decrypt(ciphertext)?);
+
+        assert!(Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("L3MON").build().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_char_policies() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+        let builder = || Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON");
+
+        let passthrough = builder().build()?;
+        assert_eq!("Xiqh zp ef 10:45, pl refs 3!", passthrough.encrypt(plaintext)?);
+        assert_eq!(plaintext, passthrough.decrypt(&passthrough.encrypt(plaintext)?)?);
+
+        let strip = builder().unknown_char_policy(UnknownCharPolicy::Strip).build()?;
+        assert_eq!("Xiqhzpefplrefs", strip.encrypt(plaintext)?);
+        assert_eq!("Meetmeatbygate", strip.decrypt(&strip.encrypt(plaintext)?)?);
+
+        let error = builder().unknown_char_policy(UnknownCharPolicy::Error).build()?;
+        assert!(error.encrypt(plaintext).is_err());
+        assert!(error.decrypt("Xiqh zp ef 10:45").is_err());
+        assert_eq!("Xiqhzpef", error.encrypt("Meetmeat")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn advance_key_on_all_chars() -> anyhow::Result<()> {
+        let plaintext = "Meet me at 10:45, by gate 3!";
+        let letters_only = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON").build()?;
+        let all_chars = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON").advance_key_on_all_chars().build()?;
+
+        assert_eq!("Xiqh zp ef 10:45, pl refs 3!", letters_only.encrypt(plaintext)?);
+        assert_eq!("Xiqh xi og 10:45, pl kmhr 3!", all_chars.encrypt(plaintext)?);
+        assert_eq!(plaintext, all_chars.decrypt(&all_chars.encrypt(plaintext)?)?);
+        assert_ne!(plaintext, letters_only.decrypt(&all_chars.encrypt(plaintext)?)?);
+
+        // Without any characters outside the alphabet, both conventions agree
+        assert_eq!(letters_only.encrypt("Meetmeat")?, all_chars.encrypt("Meetmeat")?);
+
+        let stripped = Vigenere::new()
+            .alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+            .key("LEMON")
+            .unknown_char_policy(UnknownCharPolicy::Strip)
+            .advance_key_on_all_chars()
+            .build()?;
+        assert_eq!("Xiqhxiogplkmhr", stripped.encrypt(plaintext)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encrypt_with_random_key() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let alphabet = "AYCDWZIHGJKLQNOPMVSTXREUBF";
+
+        let (ciphertext, key) = Vigenere::encrypt_with_random_key(plaintext, 12, alphabet)?;
+        assert_eq!(12, key.len());
+        assert!(key.chars().all(|letter| alphabet.contains(letter)));
+        assert_ne!(plaintext, ciphertext);
+        assert_eq!(plaintext, Vigenere::new().alphabet(alphabet).key(&key).build()?.decrypt(&ciphertext)?);
+
+        assert!(Vigenere::encrypt_with_random_key(plaintext, 0, alphabet).is_err());
+        assert!(Vigenere::encrypt_with_random_key(plaintext, 12, "ABC").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_traced() -> anyhow::Result<()> {
+        let ciphertext = include_str!("../tests/encrypted_letter.txt");
+        let key = "MYSUPERTOPSECRETKEY";
+        let vigenere = Vigenere::new().alphabet("AYCDWZIHGJKLQNOPMVSTXREUBF").key(key).build()?;
+
+        let trace = vigenere.decrypt_traced(ciphertext)?;
+        assert_eq!(ciphertext, trace.iter().map(|(cipher_char, _, _)| cipher_char).collect::<String>());
+        assert_eq!(vigenere.decrypt(ciphertext)?, trace.iter().map(|(_, _, plain_char)| plain_char).collect::<String>());
+
+        let key_chars = trace.iter().map(|(_, key_char, _)| *key_char).filter(|key_char| *key_char != '\0').collect::<String>();
+        assert!(key_chars.len() > key.len());
+        assert!(key_chars.chars().zip(key.chars().cycle()).all(|(traced, expected)| traced == expected));
+
+        let passthrough = trace.iter().filter(|(_, key_char, _)| *key_char == '\0');
+        assert!(passthrough.clone().count() > 0);
+        assert!(passthrough
+            .clone()
+            .all(|(cipher_char, _, plain_char)| cipher_char == plain_char && !cipher_char.is_alphabetic()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn effective_period() -> anyhow::Result<()> {
+        let builder = |key: &str| Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(key).build();
+
+        assert_eq!(2, builder("ABAB")?.effective_period());
+        assert_eq!(2, builder("abAB")?.effective_period());
+        assert_eq!(1, builder("KKKK")?.effective_period());
+        assert_eq!(3, builder("ABA")?.effective_period());
+        assert_eq!(5, builder("LEMON")?.effective_period());
+        assert_eq!(3, builder("XYZXYZXYZ")?.effective_period());
+
+        let report = builder("LEMONLEMON")?.weakness_report("Hi");
+        assert_eq!(
+            vec![KeyWeakness::RepeatingKey {
+                key_length: 10,
+                effective_period: 5
+            }],
+            report.weaknesses
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn weakness_report() -> anyhow::Result<()> {
+        let plaintext = include_str!("../tests/letter.txt");
+        let vigenere = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key("LEMON").build()?;
+        let ciphertext = vigenere.encrypt(plaintext)?;
+
+        let report = vigenere.weakness_report(&ciphertext);
+        assert!(report.is_weak());
+        assert_eq!(5, report.effective_period);
+        assert_eq!(ciphertext.chars().filter(|character| character.is_ascii_alphabetic()).count(), report.text_length);
+        assert_eq!(
+            vec![KeyWeakness::ShortKey {
+                effective_period: 5,
+                text_length: report.text_length
+            }],
+            report.weaknesses
+        );
+        assert!(report.to_string().contains("Kasiski"));
+
+        // A key as long as the text is never reused
+        let running_key = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+        let vigenere = Vigenere::new().alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ").key(running_key).build()?;
+        assert!(!vigenere.weakness_report("Meet me at the usual place").is_weak());
 
         Ok(())
     }
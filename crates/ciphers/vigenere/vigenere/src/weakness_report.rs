@@ -0,0 +1,68 @@
+use colored::Colorize as _;
+
+/// A weakness of a Vigenere key for a particular text, as found by `Vigenere::weakness_report()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyWeakness {
+    /// The key is made of a shorter key repeated, so it's only as strong as the shorter key. For example, `"ABAB"` is
+    /// equivalent to `"AB"`.
+    RepeatingKey { key_length: usize, effective_period: usize },
+
+    /// The key is reused at least `SHORT_KEY_REUSES` times over the text, so repeated plaintext fragments produce repeated
+    /// ciphertext fragments, which makes the period easy to find with the Kasiski examination or the index of coincidence.
+    ShortKey { effective_period: usize, text_length: usize },
+}
+
+/// The number of times a key must be reused over a text for it to be considered too short; See `KeyWeakness::ShortKey`.
+pub const SHORT_KEY_REUSES: usize = 2;
+
+/// A report of how strong a Vigenere key is for a particular text, for understanding why a Vigenere cipher can be
+/// cracked. This is created by `Vigenere::weakness_report()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeaknessReport {
+    /// The length of the key.
+    pub key_length: usize,
+
+    /// The length of the key after removing internal repetition; See `Vigenere::effective_period()`.
+    pub effective_period: usize,
+
+    /// The number of characters of the text that are encrypted, i.e., those that are in the cipher's alphabet.
+    pub text_length: usize,
+
+    /// The weaknesses that were found, which is empty if the key is strong for the text.
+    pub weaknesses: Vec<KeyWeakness>,
+}
+
+impl WeaknessReport {
+    /// Returns whether any weaknesses were found.
+    pub fn is_weak(&self) -> bool {
+        !self.weaknesses.is_empty()
+    }
+}
+
+impl std::fmt::Display for WeaknessReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", "Key Weakness Report".bold().cyan())?;
+        writeln!(f, "\t{}: {}", "Key length".bold(), self.key_length)?;
+        writeln!(f, "\t{}: {}", "Effective period".bold(), self.effective_period)?;
+        writeln!(f, "\t{}: {}", "Encrypted characters".bold(), self.text_length)?;
+
+        if self.weaknesses.is_empty() {
+            return writeln!(f, "\t{}", "No weaknesses found.".green().bold());
+        }
+
+        for weakness in &self.weaknesses {
+            let description = match weakness {
+                KeyWeakness::RepeatingKey { key_length, effective_period } => {
+                    format!("The key of length {key_length} repeats every {effective_period} characters, so it's only as strong as its first {effective_period}.")
+                }
+                KeyWeakness::ShortKey { effective_period, text_length } => format!(
+                    "The key is reused {} times over the text, which makes its period easy to find with the Kasiski examination.",
+                    text_length / effective_period
+                ),
+            };
+            writeln!(f, "\t{}: {description}", "Weakness".red().bold())?;
+        }
+
+        Ok(())
+    }
+}
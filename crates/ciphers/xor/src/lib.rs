@@ -0,0 +1,61 @@
+/// A repeating-key XOR cipher, which XORs each byte of the data with the next byte of the key, starting over from the
+/// beginning of the key when it runs out. XOR is its own inverse, so the same operation both encrypts and decrypts.
+pub struct XorCipher {
+    key: Vec<u8>,
+}
+
+impl XorCipher {
+    /// Creates a new XOR cipher with the given key.
+    ///
+    /// # Parameters
+    /// - `key` - The bytes to XOR the data with.
+    ///
+    /// # Returns
+    /// The created cipher.
+    ///
+    /// # Errors
+    /// If the key is empty.
+    pub fn new(key: impl AsRef<[u8]>) -> anyhow::Result<Self> {
+        let key = key.as_ref();
+        if key.is_empty() {
+            anyhow::bail!("Error creating XOR cipher: The key must not be empty");
+        }
+        Ok(Self { key: key.to_vec() })
+    }
+
+    /// XORs the given data with this cipher's key in place, without allocating. Since XOR is symmetric, this both
+    /// encrypts and decrypts.
+    ///
+    /// # Parameters
+    /// - `data` - The data to encrypt or decrypt.
+    pub fn apply_in_place(&self, data: &mut [u8]) {
+        for (byte, key) in data.iter_mut().zip(self.key.iter().cycle()) {
+            *byte ^= key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::XorCipher;
+
+    #[test]
+    fn apply_in_place() -> anyhow::Result<()> {
+        let plaintext = include_bytes!("../tests/letter.txt");
+        let xor = XorCipher::new("Violet")?;
+
+        let mut data = plaintext.to_vec();
+        xor.apply_in_place(&mut data);
+        assert_ne!(plaintext.as_slice(), data.as_slice());
+        xor.apply_in_place(&mut data);
+        assert_eq!(plaintext.as_slice(), data.as_slice());
+
+        let mut data = *b"abc";
+        XorCipher::new([1, 2])?.apply_in_place(&mut data);
+        assert_eq!(*b"``b", data);
+
+        assert!(XorCipher::new("").is_err());
+
+        Ok(())
+    }
+}